@@ -4,9 +4,7 @@ include!("grpc.rs");
 
 use super::*;
 
-#[cfg(not(feature = "stub_client"))]
-use lib_common::grpc::ClientConnect;
-use lib_common::grpc::{Client, GrpcClient};
+use lib_common::grpc::{Client, ClientConnect, GrpcClient};
 use rpc_service_client::RpcServiceClient;
 /// GrpcClient implementation of the RpcServiceClient
 pub type TemplateRustClient = GrpcClient<RpcServiceClient<Channel>>;
@@ -27,6 +25,12 @@ cfg_if::cfg_if! {
 impl crate::service::Client<RpcServiceClient<Channel>> for TemplateRustClient {
     type ReadyRequest = ReadyRequest;
     type ReadyResponse = ReadyResponse;
+    type RealmHealthRequest = RealmHealthRequest;
+    type RealmHealthResponse = RealmHealthResponse;
+    type ListSuitesRequest = ListSuitesRequest;
+    type ListSuitesResponse = ListSuitesResponse;
+    type RunSuiteRequest = RunSuiteRequest;
+    type RunEvent = RunEvent;
 
     async fn is_ready(
         &self,
@@ -38,6 +42,33 @@ impl crate::service::Client<RpcServiceClient<Channel>> for TemplateRustClient {
         grpc_debug!("(is_ready) request: {:?}", request);
         self.get_client().await?.is_ready(request).await
     }
+
+    async fn realm_health(
+        &self,
+        request: Self::RealmHealthRequest,
+    ) -> Result<tonic::Response<Self::RealmHealthResponse>, tonic::Status> {
+        grpc_info!("(realm_health) {} client.", self.get_name());
+        grpc_debug!("(realm_health) request: {:?}", request);
+        self.get_client().await?.realm_health(request).await
+    }
+
+    async fn list_suites(
+        &self,
+        request: Self::ListSuitesRequest,
+    ) -> Result<tonic::Response<Self::ListSuitesResponse>, tonic::Status> {
+        grpc_info!("(list_suites) {} client.", self.get_name());
+        grpc_debug!("(list_suites) request: {:?}", request);
+        self.get_client().await?.list_suites(request).await
+    }
+
+    async fn run_suite(
+        &self,
+        request: Self::RunSuiteRequest,
+    ) -> Result<tonic::Response<tonic::Streaming<Self::RunEvent>>, tonic::Status> {
+        grpc_info!("(run_suite) {} client.", self.get_name());
+        grpc_debug!("(run_suite) request: {:?}", request);
+        self.get_client().await?.run_suite(request).await
+    }
 }
 
 #[cfg(feature = "stub_client")]
@@ -45,6 +76,12 @@ impl crate::service::Client<RpcServiceClient<Channel>> for TemplateRustClient {
 impl crate::service::Client<RpcServiceClient<Channel>> for TemplateRustClient {
     type ReadyRequest = ReadyRequest;
     type ReadyResponse = ReadyResponse;
+    type RealmHealthRequest = RealmHealthRequest;
+    type RealmHealthResponse = RealmHealthResponse;
+    type ListSuitesRequest = ListSuitesRequest;
+    type ListSuitesResponse = ListSuitesResponse;
+    type RunSuiteRequest = RunSuiteRequest;
+    type RunEvent = RunEvent;
 
     async fn is_ready(
         &self,
@@ -54,6 +91,53 @@ impl crate::service::Client<RpcServiceClient<Channel>> for TemplateRustClient {
         grpc_debug!("(is_ready MOCK) request: {:?}", request);
         Ok(tonic::Response::new(ReadyResponse { ready: true }))
     }
+
+    async fn realm_health(
+        &self,
+        request: Self::RealmHealthRequest,
+    ) -> Result<tonic::Response<Self::RealmHealthResponse>, tonic::Status> {
+        grpc_warn!("(realm_health MOCK) {} client.", self.get_name());
+        grpc_debug!("(realm_health MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(RealmHealthResponse {
+            ready: true,
+            services: vec![],
+        }))
+    }
+
+    async fn list_suites(
+        &self,
+        request: Self::ListSuitesRequest,
+    ) -> Result<tonic::Response<Self::ListSuitesResponse>, tonic::Status> {
+        grpc_warn!("(list_suites MOCK) {} client.", self.get_name());
+        grpc_debug!("(list_suites MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(ListSuitesResponse {
+            suites: vec![SuiteInfo {
+                name: "mock".to_string(),
+                description: None,
+                steps: 1,
+            }],
+        }))
+    }
+
+    /// A [`tonic::Streaming`] can only be created by a connection, so the
+    /// events come from the stub server of the `stub_backends` feature.
+    /// Without it, the call fails as unimplemented.
+    async fn run_suite(
+        &self,
+        request: Self::RunSuiteRequest,
+    ) -> Result<tonic::Response<tonic::Streaming<Self::RunEvent>>, tonic::Status> {
+        grpc_warn!("(run_suite MOCK) {} client.", self.get_name());
+        grpc_debug!("(run_suite MOCK) request: {:?}", request);
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "stub_backends")] {
+                self.get_client().await?.run_suite(request).await
+            } else {
+                Err(tonic::Status::unimplemented(
+                    "run_suite MOCK needs the stub_backends feature",
+                ))
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -91,4 +175,85 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap().into_inner().ready, true);
     }
+
+    #[tokio::test]
+    async fn test_client_realm_health_request() {
+        let name = "itest";
+        let (server_host, server_port) =
+            lib_common::grpc::get_endpoint_from_env("GRPC_HOST", "GRPC_PORT");
+
+        let client: TemplateRustClient = GrpcClient::new_client(&server_host, server_port, name);
+        assert_eq!(client.get_name(), name);
+
+        let result = client.realm_health(RealmHealthRequest {}).await;
+        println!("{:?}", result);
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_client_list_suites_request() {
+        let name = "itest";
+        let (server_host, server_port) =
+            lib_common::grpc::get_endpoint_from_env("GRPC_HOST", "GRPC_PORT");
+
+        let client: TemplateRustClient = GrpcClient::new_client(&server_host, server_port, name);
+        assert_eq!(client.get_name(), name);
+
+        let result = client.list_suites(ListSuitesRequest {}).await;
+        println!("{:?}", result);
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    #[cfg(any(not(feature = "stub_client"), feature = "stub_backends"))]
+    async fn test_client_run_suite_request() {
+        let name = "itest";
+        let (server_host, server_port) =
+            lib_common::grpc::get_endpoint_from_env("GRPC_HOST", "GRPC_PORT");
+
+        let client: TemplateRustClient = GrpcClient::new_client(&server_host, server_port, name);
+        assert_eq!(client.get_name(), name);
+
+        let suites = client
+            .list_suites(ListSuitesRequest {})
+            .await
+            .unwrap()
+            .into_inner()
+            .suites;
+        let suite = suites.first().expect("no suites found").name.clone();
+
+        let result = client
+            .run_suite(RunSuiteRequest {
+                suite: suite.clone(),
+            })
+            .await;
+        println!("{:?}", result);
+        let mut events = result.unwrap().into_inner();
+
+        // The stream ends with the summary of the run
+        let mut summary = None;
+        while let Some(event) = events.message().await.unwrap() {
+            if let Some(run_event::Event::Summary(event)) = event.event {
+                summary = Some(event);
+            }
+        }
+        assert_eq!(summary.unwrap().suite, suite);
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "stub_client", not(feature = "stub_backends")))]
+    async fn test_client_run_suite_request_unimplemented() {
+        let name = "itest";
+        let (server_host, server_port) =
+            lib_common::grpc::get_endpoint_from_env("GRPC_HOST", "GRPC_PORT");
+
+        let client: TemplateRustClient = GrpcClient::new_client(&server_host, server_port, name);
+
+        let result = client
+            .run_suite(RunSuiteRequest {
+                suite: "mock".to_string(),
+            })
+            .await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::Unimplemented);
+    }
 }
@@ -15,6 +15,199 @@ pub struct ReadyResponse {
     #[prost(bool, tag = "1")]
     pub ready: bool,
 }
+/// Realm Health Request object
+///
+/// No arguments
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RealmHealthRequest {}
+/// Health of a single realm service
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ServiceHealth {
+    /// The name of the service, such as svc-storage
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// The host the service was probed at
+    #[prost(string, tag = "2")]
+    pub host: ::prost::alloc::string::String,
+    /// The port the service was probed at
+    #[prost(uint32, tag = "3")]
+    pub port: u32,
+    /// The status of the service: serving, not_serving or timeout
+    #[prost(string, tag = "4")]
+    pub status: ::prost::alloc::string::String,
+    /// The time the probe took, in milliseconds
+    #[prost(uint64, tag = "5")]
+    pub latency_ms: u64,
+    /// The error returned by the probe, if any
+    #[prost(string, optional, tag = "6")]
+    pub error: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Realm Health Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RealmHealthResponse {
+    /// True if all realm services are serving
+    #[prost(bool, tag = "1")]
+    pub ready: bool,
+    /// The health of each realm service
+    #[prost(message, repeated, tag = "2")]
+    pub services: ::prost::alloc::vec::Vec<ServiceHealth>,
+}
+/// List Suites Request object
+///
+/// No arguments
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListSuitesRequest {}
+/// A test suite which can be run
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SuiteInfo {
+    /// The unique name of the suite
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// What the suite tests
+    #[prost(string, optional, tag = "2")]
+    pub description: ::core::option::Option<::prost::alloc::string::String>,
+    /// The number of steps of the suite
+    #[prost(uint32, tag = "3")]
+    pub steps: u32,
+}
+/// List Suites Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListSuitesResponse {
+    /// The suites found in the suite directory
+    #[prost(message, repeated, tag = "1")]
+    pub suites: ::prost::alloc::vec::Vec<SuiteInfo>,
+}
+/// Run Suite Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RunSuiteRequest {
+    /// The name of the suite to run
+    #[prost(string, tag = "1")]
+    pub suite: ::prost::alloc::string::String,
+}
+/// The run of a suite started
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RunStarted {
+    /// The name of the suite
+    #[prost(string, tag = "1")]
+    pub suite: ::prost::alloc::string::String,
+    /// The run the seeded resources belong to
+    #[prost(string, tag = "2")]
+    pub run_id: ::prost::alloc::string::String,
+}
+/// A step of the suite started
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StepStarted {
+    /// The position of the step in the suite, starting at 0
+    #[prost(uint32, tag = "1")]
+    pub index: u32,
+    /// The name of the step
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    /// The kind of step: seed, call, wait or assert
+    #[prost(string, tag = "3")]
+    pub kind: ::prost::alloc::string::String,
+}
+/// A step of the suite finished
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StepFinished {
+    /// The position of the step in the suite, starting at 0
+    #[prost(uint32, tag = "1")]
+    pub index: u32,
+    /// The name of the step
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    /// The kind of step: seed, call, wait or assert
+    #[prost(string, tag = "3")]
+    pub kind: ::prost::alloc::string::String,
+    /// The outcome of the step: passed, failed or skipped
+    #[prost(string, tag = "4")]
+    pub status: ::prost::alloc::string::String,
+    /// The time the step took, in milliseconds
+    #[prost(uint64, tag = "5")]
+    pub duration_ms: u64,
+    /// The request the step made, as JSON
+    #[prost(string, optional, tag = "6")]
+    pub request: ::core::option::Option<::prost::alloc::string::String>,
+    /// The response the step received, as JSON
+    #[prost(string, optional, tag = "7")]
+    pub response: ::core::option::Option<::prost::alloc::string::String>,
+    /// Why the step failed
+    #[prost(string, optional, tag = "8")]
+    pub error: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// A log message of the run
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RunLog {
+    /// The log level: error, warn, info or debug
+    #[prost(string, tag = "1")]
+    pub level: ::prost::alloc::string::String,
+    /// The log message
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+}
+/// The run of a suite finished
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RunSummary {
+    /// The name of the suite
+    #[prost(string, tag = "1")]
+    pub suite: ::prost::alloc::string::String,
+    /// The run the seeded resources belong to
+    #[prost(string, tag = "2")]
+    pub run_id: ::prost::alloc::string::String,
+    /// True if all steps passed
+    #[prost(bool, tag = "3")]
+    pub passed: bool,
+    /// The time the suite took, in milliseconds
+    #[prost(uint64, tag = "4")]
+    pub duration_ms: u64,
+    /// The number of steps which passed
+    #[prost(uint32, tag = "5")]
+    pub passed_steps: u32,
+    /// The number of steps which failed
+    #[prost(uint32, tag = "6")]
+    pub failed_steps: u32,
+    /// The number of steps which were skipped
+    #[prost(uint32, tag = "7")]
+    pub skipped_steps: u32,
+}
+/// Event of a suite run
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RunEvent {
+    /// The event
+    #[prost(oneof = "run_event::Event", tags = "1, 2, 3, 4, 5")]
+    pub event: ::core::option::Option<run_event::Event>,
+}
+/// Nested message and enum types in `RunEvent`.
+pub mod run_event {
+    /// The event
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Event {
+        #[prost(message, tag = "1")]
+        Started(super::RunStarted),
+        #[prost(message, tag = "2")]
+        StepStarted(super::StepStarted),
+        #[prost(message, tag = "3")]
+        StepFinished(super::StepFinished),
+        #[prost(message, tag = "4")]
+        Log(super::RunLog),
+        #[prost(message, tag = "5")]
+        Summary(super::RunSummary),
+    }
+}
 /// Generated client implementations.
 #[cfg(not(tarpaulin_include))]
 pub mod rpc_service_client {
@@ -122,5 +315,79 @@ pub mod rpc_service_client {
             req.extensions_mut().insert(GrpcMethod::new("grpc.RpcService", "isReady"));
             self.inner.unary(req, path, codec).await
         }
+        /// Realm Interfaces
+        pub async fn realm_health(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RealmHealthRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RealmHealthResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/realmHealth",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "realmHealth"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Test Runner Interfaces
+        pub async fn list_suites(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListSuitesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListSuitesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/listSuites",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "listSuites"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn run_suite(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RunSuiteRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::RunEvent>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/grpc.RpcService/runSuite");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("grpc.RpcService", "runSuite"));
+            self.inner.server_streaming(req, path, codec).await
+        }
     }
 }
@@ -11,6 +11,18 @@ where
     type ReadyRequest;
     /// The type expected for ReadyResponse structs.
     type ReadyResponse;
+    /// The type expected for RealmHealthRequest structs.
+    type RealmHealthRequest;
+    /// The type expected for RealmHealthResponse structs.
+    type RealmHealthResponse;
+    /// The type expected for ListSuitesRequest structs.
+    type ListSuitesRequest;
+    /// The type expected for ListSuitesResponse structs.
+    type ListSuitesResponse;
+    /// The type expected for RunSuiteRequest structs.
+    type RunSuiteRequest;
+    /// The type expected for RunEvent structs.
+    type RunEvent;
 
     /// Returns a [`tonic::Response`] containing a [`ReadyResponse`](Self::ReadyResponse)
     /// Takes an [`ReadyRequest`](Self::ReadyRequest).
@@ -38,4 +50,95 @@ where
         &self,
         request: Self::ReadyRequest,
     ) -> Result<tonic::Response<Self::ReadyResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`RealmHealthResponse`](Self::RealmHealthResponse)
+    /// with the gRPC health status of every realm service found in the
+    /// server's environment.
+    /// Takes an [`RealmHealthRequest`](Self::RealmHealthRequest).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] if the server could not be reached.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_itest_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = TemplateRustClient::new_client(&host, port, "itest");
+    ///     let response = client
+    ///         .realm_health(itest::RealmHealthRequest {})
+    ///         .await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn realm_health(
+        &self,
+        request: Self::RealmHealthRequest,
+    ) -> Result<tonic::Response<Self::RealmHealthResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`ListSuitesResponse`](Self::ListSuitesResponse)
+    /// with the test suites the server can run.
+    /// Takes an [`ListSuitesRequest`](Self::ListSuitesRequest).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`tonic::Code::FailedPrecondition`] if
+    /// the server could not load its suites.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_itest_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = TemplateRustClient::new_client(&host, port, "itest");
+    ///     let response = client
+    ///         .list_suites(itest::ListSuitesRequest {})
+    ///         .await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn list_suites(
+        &self,
+        request: Self::ListSuitesRequest,
+    ) -> Result<tonic::Response<Self::ListSuitesResponse>, tonic::Status>;
+
+    /// Starts a run of a test suite and returns a [`tonic::Response`]
+    /// containing the stream of its [`RunEvent`](Self::RunEvent)s. The
+    /// stream ends with a summary once the run finished.
+    /// Takes an [`RunSuiteRequest`](Self::RunSuiteRequest).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`tonic::Code::NotFound`] if the
+    /// server has no suite with the requested name.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_itest_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = TemplateRustClient::new_client(&host, port, "itest");
+    ///     let mut events = client
+    ///         .run_suite(itest::RunSuiteRequest { suite: "vertiport_roundtrip".to_string() })
+    ///         .await?
+    ///         .into_inner();
+    ///     while let Some(event) = events.message().await? {
+    ///         println!("EVENT={:?}", event);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn run_suite(
+        &self,
+        request: Self::RunSuiteRequest,
+    ) -> Result<tonic::Response<tonic::Streaming<Self::RunEvent>>, tonic::Status>;
 }
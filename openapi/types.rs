@@ -1,6 +1,8 @@
 /// Types used for REST communication with the svc-cargo server
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use utoipa::{IntoParams, ToSchema};
 
 /// Information needed to build a vertipad
@@ -21,7 +23,17 @@ pub struct AddVertipadRequest {
     pub altitude: f64,
 
     /// The informal label for this pad
-    pub label: String
+    pub label: String,
+
+    /// The iCalendar schedule (DTSTART/DURATION header and RRULE lines) of
+    /// this pad, defaults to always available
+    pub schedule: Option<String>,
+
+    /// The run this pad is created for, may also be provided through the
+    /// `x-run-id` header. Ignored for scenario entries, which use the run of
+    /// the scenario.
+    #[serde(default)]
+    pub run_id: Option<String>
 }
 
 /// Information needed to add a vertiport
@@ -39,7 +51,17 @@ pub struct AddVertiportRequest {
     pub vertices: Vec<(f64, f64)>,
 
     /// The starting altitude of the vertiport in meters
-    pub altitude: f64
+    pub altitude: f64,
+
+    /// The iCalendar schedule (DTSTART/DURATION header and RRULE lines) of
+    /// the opening hours, defaults to always open
+    pub schedule: Option<String>,
+
+    /// The run this vertiport is created for, may also be provided through the
+    /// `x-run-id` header. Ignored for scenario entries, which use the run of
+    /// the scenario.
+    #[serde(default)]
+    pub run_id: Option<String>
 }
 
 /// Information needed to add an aircraft
@@ -53,11 +75,34 @@ pub struct AddAircraftRequest {
     /// The registration number of the aircraft
     pub registration_number: String,
 
-    /// The hangar ID
+    /// The hangar ID, the ID of an existing vertiport
     pub hangar_id: String,
 
-    /// The hangar bay ID
-    pub hangar_bay_id: String
+    /// The hangar bay ID, the ID of a vertipad of the hangar
+    pub hangar_bay_id: String,
+
+    /// The vehicle model ID, defaults to a random ID
+    #[serde(default)]
+    pub vehicle_model_id: Option<String>,
+
+    /// The name of a vehicle model from the catalog, instead of the
+    /// `vehicle_model_id`
+    #[serde(default)]
+    pub vehicle_model: Option<String>,
+
+    /// The serial number of the aircraft, defaults to a random serial number
+    #[serde(default)]
+    pub serial_number: Option<String>,
+
+    /// The iCalendar schedule (DTSTART/DURATION header and RRULE lines) of
+    /// the aircraft's availability, defaults to always available
+    pub schedule: Option<String>,
+
+    /// The run this aircraft is created for, may also be provided through the
+    /// `x-run-id` header. Ignored for scenario entries, which use the run of
+    /// the scenario.
+    #[serde(default)]
+    pub run_id: Option<String>
 }
 
 /// Information needed to build a vertipad
@@ -69,6 +114,11 @@ pub struct AddUserRequest {
     pub display_name: String,
     /// The email of the user
     pub email: String,
+    /// The run this user is created for, may also be provided through the
+    /// `x-run-id` header. Ignored for scenario entries, which use the run of
+    /// the scenario.
+    #[serde(default)]
+    pub run_id: Option<String>
 }
 
 /// Information needed to build a vertipad
@@ -80,4 +130,1323 @@ pub struct AddScannerRequest {
     pub organization_id: String,
     /// The email of the user
     pub scanner_type: String,
+    /// The run this scanner is created for, may also be provided through the
+    /// `x-run-id` header. Ignored for scenario entries, which use the run of
+    /// the scenario.
+    #[serde(default)]
+    pub run_id: Option<String>
+}
+
+/// A vertiport entry of a scenario
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct ScenarioVertiport {
+    /// Symbolic name used by other scenario entries to reference this vertiport
+    pub name: String,
+
+    /// The vertiport to create
+    #[serde(flatten)]
+    pub vertiport: AddVertiportRequest
+}
+
+/// A vertipad entry of a scenario
+///
+/// The `vertiport_id` may hold the symbolic name of a scenario vertiport.
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct ScenarioVertipad {
+    /// Symbolic name used by other scenario entries to reference this vertipad
+    pub name: String,
+
+    /// The vertipad to create
+    #[serde(flatten)]
+    pub vertipad: AddVertipadRequest
+}
+
+/// An aircraft entry of a scenario
+///
+/// The `hangar_id` and `hangar_bay_id` may hold the symbolic names of a
+/// scenario vertiport and vertipad.
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct ScenarioAircraft {
+    /// Symbolic name used by other scenario entries to reference this aircraft
+    pub name: String,
+
+    /// The aircraft to create
+    #[serde(flatten)]
+    pub aircraft: AddAircraftRequest
+}
+
+/// A user entry of a scenario
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct ScenarioUser {
+    /// Symbolic name used by other scenario entries to reference this user
+    pub name: String,
+
+    /// The user to create
+    #[serde(flatten)]
+    pub user: AddUserRequest
+}
+
+/// A scanner entry of a scenario
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct ScenarioScanner {
+    /// Symbolic name used by other scenario entries to reference this scanner
+    pub name: String,
+
+    /// The scanner to create
+    #[serde(flatten)]
+    pub scanner: AddScannerRequest
+}
+
+/// A set of resources to create in one call
+///
+/// Entries reference each other by their symbolic names, or by the ID
+/// of a resource which already exists.
+#[derive(Debug, Clone, Default)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct Scenario {
+    /// The vertiports to create
+    #[serde(default)]
+    pub vertiports: Vec<ScenarioVertiport>,
+
+    /// The vertipads to create
+    #[serde(default)]
+    pub vertipads: Vec<ScenarioVertipad>,
+
+    /// The aircraft to create
+    #[serde(default)]
+    pub aircraft: Vec<ScenarioAircraft>,
+
+    /// The users to create
+    #[serde(default)]
+    pub users: Vec<ScenarioUser>,
+
+    /// The scanners to create
+    #[serde(default)]
+    pub scanners: Vec<ScenarioScanner>,
+
+    /// The run the resources are created for, may also be provided
+    /// through the `x-run-id` header
+    #[serde(default)]
+    pub run_id: Option<String>
+}
+
+/// Result of a scenario which has been created
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct ScenarioResponse {
+    /// The IDs of the created resources, by symbolic name
+    pub ids: BTreeMap<String, String>
+}
+
+/// Outcome of undoing the changes of a failed request
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct RollbackReport {
+    /// True if all changes which can be undone have been undone
+    pub complete: bool,
+
+    /// The changes which have been undone, in the order they were undone
+    pub undone: Vec<String>,
+
+    /// The changes which could not be undone, with the reason
+    pub failed: Vec<String>,
+
+    /// The changes which can't be undone at all, such as vertiports and
+    /// waypoints sent to svc-gis. These don't make the rollback incomplete.
+    #[serde(default)]
+    pub not_removable: Vec<String>
+}
+
+/// Problem document returned when a request fails
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct ErrorResponse {
+    /// Short summary of the kind of problem
+    pub title: String,
+
+    /// The HTTP status code of the response
+    pub status: u16,
+
+    /// Description of the error
+    pub error: String,
+
+    /// The dependency which failed or rejected the request, if any
+    pub dependency: Option<String>,
+
+    /// The field of the request which was rejected, if known
+    pub field: Option<String>,
+
+    /// Outcome of undoing the changes made before the error occurred
+    pub rollback: Option<RollbackReport>
+}
+
+/// Vertiport as registered with svc-gis by svc-itest
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct GisVertiport {
+    /// The identifier of the vertiport in svc-gis
+    pub identifier: String,
+
+    /// The label of the vertiport
+    pub label: Option<String>,
+
+    /// The bounding polygon of the vertiport
+    pub vertices: Vec<(f64, f64)>,
+
+    /// The time the vertiport was sent to svc-gis
+    pub timestamp: DateTime<Utc>
+}
+
+/// Vertipad waypoint as registered with svc-gis by svc-itest
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct GisWaypoint {
+    /// The identifier of the waypoint in svc-gis
+    pub identifier: String,
+
+    /// The latitude of the waypoint
+    pub latitude: f64,
+
+    /// The longitude of the waypoint
+    pub longitude: f64,
+
+    /// The time the waypoint was sent to svc-gis
+    pub timestamp: DateTime<Utc>
+}
+
+/// Vertiport as known by svc-storage and svc-gis
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct VertiportResponse {
+    /// The ID of the vertiport
+    pub id: String,
+
+    /// The label of the vertiport
+    pub label: String,
+
+    /// The address of the vertiport
+    pub address: String,
+
+    /// The bounding polygon of this vertiport
+    pub vertices: Vec<(f64, f64)>,
+
+    /// The starting altitude of the vertiport in meters
+    pub altitude: f64,
+
+    /// The schedule of the opening hours
+    pub schedule: Option<String>,
+
+    /// The vertiport as last sent to svc-gis by this svc-itest instance, not
+    /// read back from svc-gis. Empty if this instance has not sent the vertiport
+    /// since it started.
+    pub gis: Option<GisVertiport>
+}
+
+/// Vertipad as known by svc-storage and svc-gis
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct VertipadResponse {
+    /// The ID of the vertipad
+    pub id: String,
+
+    /// The ID of the vertiport
+    pub vertiport_id: String,
+
+    /// The informal label for this pad
+    pub label: String,
+
+    /// The latitude of the pad
+    pub latitude: f64,
+
+    /// The longitude of the pad
+    pub longitude: f64,
+
+    /// The altitude of the pad in meters
+    pub altitude: f64,
+
+    /// True if the pad can be used
+    pub enabled: bool,
+
+    /// True if the pad is occupied
+    pub occupied: bool,
+
+    /// The schedule of this pad
+    pub schedule: Option<String>,
+
+    /// The pad as last sent to svc-gis by this svc-itest instance, not
+    /// read back from svc-gis. Empty if this instance has not sent the pad
+    /// since it started.
+    pub gis: Option<GisWaypoint>
+}
+
+/// Aircraft as known by svc-storage
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct AircraftResponse {
+    /// The ID of the aircraft
+    pub id: String,
+
+    /// The nickname of the aircraft
+    pub nickname: Option<String>,
+
+    /// The registration number of the aircraft
+    pub registration_number: String,
+
+    /// The serial number of the aircraft
+    pub serial_number: String,
+
+    /// The vehicle model ID
+    pub vehicle_model_id: String,
+
+    /// The hangar ID
+    pub hangar_id: Option<String>,
+
+    /// The hangar bay ID
+    pub hangar_bay_id: Option<String>,
+
+    /// The schedule of the aircraft's availability
+    pub schedule: Option<String>
+}
+
+/// User as known by svc-storage
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct UserResponse {
+    /// The ID of the user
+    pub id: String,
+
+    /// The display name of the user
+    pub display_name: String,
+
+    /// The email of the user
+    pub email: String,
+
+    /// The authentication method of the user
+    pub auth_method: Option<String>
+}
+
+/// Scanner as known by svc-storage
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct ScannerResponse {
+    /// The ID of the scanner
+    pub id: String,
+
+    /// The organization ID of the scanner
+    pub organization_id: String,
+
+    /// The type of scanner
+    pub scanner_type: String,
+
+    /// The status of the scanner
+    pub scanner_status: String
+}
+
+/// Filters for listing vertiports
+#[derive(Debug, Clone, Default)]
+#[derive(Deserialize, Serialize)]
+#[derive(IntoParams)]
+pub struct VertiportFilter {
+    /// Only return vertiports with this label
+    pub label: Option<String>
+}
+
+/// Filters for listing vertipads
+#[derive(Debug, Clone, Default)]
+#[derive(Deserialize, Serialize)]
+#[derive(IntoParams)]
+pub struct VertipadFilter {
+    /// Only return vertipads of this vertiport
+    pub vertiport_id: Option<String>,
+
+    /// Only return vertipads with this label
+    pub label: Option<String>
+}
+
+/// Filters for listing aircraft
+#[derive(Debug, Clone, Default)]
+#[derive(Deserialize, Serialize)]
+#[derive(IntoParams)]
+pub struct AircraftFilter {
+    /// Only return aircraft with this registration number
+    pub registration_number: Option<String>,
+
+    /// Only return aircraft hangared at this vertiport
+    pub hangar_id: Option<String>
+}
+
+/// Filters for listing users
+#[derive(Debug, Clone, Default)]
+#[derive(Deserialize, Serialize)]
+#[derive(IntoParams)]
+pub struct UserFilter {
+    /// Only return users with this email
+    pub email: Option<String>,
+
+    /// Only return users with this display name
+    pub display_name: Option<String>
+}
+
+/// Filters for listing scanners
+#[derive(Debug, Clone, Default)]
+#[derive(Deserialize, Serialize)]
+#[derive(IntoParams)]
+pub struct ScannerFilter {
+    /// Only return scanners of this organization
+    pub organization_id: Option<String>
+}
+
+/// Changes to a vertiport, fields which are not provided are left unchanged
+#[derive(Debug, Clone, Default)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct UpdateVertiportRequest {
+    /// The new label of the vertiport
+    pub label: Option<String>,
+
+    /// The new address of the vertiport
+    pub address: Option<String>,
+
+    /// The new bounding polygon of the vertiport
+    pub vertices: Option<Vec<(f64, f64)>>,
+
+    /// The new starting altitude of the vertiport in meters
+    pub altitude: Option<f64>,
+
+    /// The new iCalendar schedule of the opening hours
+    pub schedule: Option<String>
+}
+
+/// Changes to a vertipad, fields which are not provided are left unchanged
+#[derive(Debug, Clone, Default)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct UpdateVertipadRequest {
+    /// The new label of the pad
+    pub label: Option<String>,
+
+    /// The new latitude of the pad
+    pub latitude: Option<f64>,
+
+    /// The new longitude of the pad
+    pub longitude: Option<f64>,
+
+    /// The new altitude of the pad in meters
+    pub altitude: Option<f64>,
+
+    /// Whether the pad can be used
+    pub enabled: Option<bool>,
+
+    /// Whether the pad is occupied
+    pub occupied: Option<bool>,
+
+    /// The new iCalendar schedule of this pad
+    pub schedule: Option<String>
+}
+
+/// Changes to an aircraft, fields which are not provided are left unchanged
+#[derive(Debug, Clone, Default)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct UpdateAircraftRequest {
+    /// The new nickname of the aircraft
+    pub nickname: Option<String>,
+
+    /// The new registration number of the aircraft
+    pub registration_number: Option<String>,
+
+    /// The new hangar ID
+    pub hangar_id: Option<String>,
+
+    /// The new hangar bay ID
+    pub hangar_bay_id: Option<String>,
+
+    /// The new iCalendar schedule of the aircraft's availability
+    pub schedule: Option<String>
+}
+
+/// Changes to a user, fields which are not provided are left unchanged
+#[derive(Debug, Clone, Default)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct UpdateUserRequest {
+    /// The new display name of the user
+    pub display_name: Option<String>,
+
+    /// The new email of the user
+    pub email: Option<String>
+}
+
+/// Changes to a scanner, fields which are not provided are left unchanged
+#[derive(Debug, Clone, Default)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct UpdateScannerRequest {
+    /// The new organization ID of the scanner
+    pub organization_id: Option<String>,
+
+    /// The new status of the scanner, `active` or `disabled`
+    pub scanner_status: Option<String>
+}
+
+/// Outcome of the removal of all resources created for a run
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct RunTeardownResponse {
+    /// The ID of the run
+    pub run_id: String,
+
+    /// The outcome of the removal. Resources which could not be removed
+    /// remain part of the run, so the removal can be retried.
+    pub teardown: RollbackReport
+}
+
+/// What an upsert did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum UpsertOutcome {
+    /// No resource with the natural key existed, a new one was created
+    Created,
+
+    /// The existing resource with the natural key was updated
+    Updated
+}
+
+/// Result of a `/demo/upsert/*` request
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct UpsertResponse {
+    /// The ID of the created or updated resource
+    pub id: String,
+
+    /// Whether the resource was created or updated
+    pub outcome: UpsertOutcome
+}
+
+/// Options of the `/demo/bulk/*` handlers
+#[derive(Debug, Clone, Default)]
+#[derive(Deserialize, Serialize)]
+#[derive(IntoParams)]
+pub struct BulkParams {
+    /// Update existing resources with the same natural key instead of
+    /// creating duplicates. Not supported for scanners.
+    pub upsert: Option<bool>,
+
+    /// Maximum number of items seeded at the same time, limited by the
+    /// configured maximum
+    pub concurrency: Option<u8>
+}
+
+/// Result of a single item of a bulk request
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct BulkItemResult {
+    /// The position of the item in the request
+    pub index: usize,
+
+    /// The ID of the created or updated resource, if successful
+    pub id: Option<String>,
+
+    /// The error which occurred, if unsuccessful. Changes made for this
+    /// item have been rolled back.
+    pub error: Option<ErrorResponse>
+}
+
+/// Result of a bulk request
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct BulkResponse {
+    /// The number of successful items
+    pub succeeded: usize,
+
+    /// The number of failed items
+    pub failed: usize,
+
+    /// The result of each item, in order of the request
+    pub results: Vec<BulkItemResult>
+}
+
+/// Aircraft model with its performance parameters
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct VehicleModel {
+    /// The vehicle model ID used by aircraft of this model
+    pub id: String,
+
+    /// The unique name of the model, used to reference it
+    pub name: String,
+
+    /// The manufacturer of the model
+    pub manufacturer: String,
+
+    /// The propulsion type, such as `electric` or `hybrid-electric`
+    pub propulsion: String,
+
+    /// The maximum number of passengers
+    pub max_passengers: u32,
+
+    /// The maximum payload in kilograms
+    pub max_payload_kg: f64,
+
+    /// The maximum range in kilometers
+    pub max_range_km: f64,
+
+    /// The cruise speed in kilometers per hour
+    pub cruise_speed_kmh: f64
+}
+
+/// Area between two latitudes and two longitudes, in degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct BoundingBox {
+    /// The southern edge of the box
+    pub min_latitude: f64,
+
+    /// The western edge of the box
+    pub min_longitude: f64,
+
+    /// The northern edge of the box
+    pub max_latitude: f64,
+
+    /// The eastern edge of the box
+    pub max_longitude: f64
+}
+
+/// Parameters of a synthetic realm
+///
+/// The same parameters, including the seed, always produce the same realm.
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct GenerateRealmRequest {
+    /// The seed of the random number generator
+    pub seed: u64,
+
+    /// The area the vertiports are placed in
+    pub bounding_box: BoundingBox,
+
+    /// The number of vertiports to generate
+    pub vertiports: u32,
+
+    /// The minimum number of pads of each vertiport
+    pub min_pads_per_vertiport: u32,
+
+    /// The maximum number of pads of each vertiport
+    pub max_pads_per_vertiport: u32,
+
+    /// The number of aircraft to generate, hangared at random pads
+    pub aircraft: u32,
+
+    /// The number of users to generate
+    pub users: u32,
+
+    /// The run the resources are created for, may also be provided
+    /// through the `x-run-id` header
+    #[serde(default)]
+    pub run_id: Option<String>
+}
+
+/// GeoJSON geometry (RFC 7946)
+///
+/// Positions are `[longitude, latitude]`, optionally followed by the
+/// altitude.
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+#[serde(tag = "type")]
+pub enum GeoJsonGeometry {
+    /// A single position
+    Point {
+        /// The position
+        coordinates: Vec<f64>
+    },
+
+    /// A polygon, made of closed linear rings
+    Polygon {
+        /// The exterior ring, followed by the rings of any holes
+        coordinates: Vec<Vec<Vec<f64>>>
+    },
+
+    /// Any other geometry type, which svc-itest does not support
+    #[serde(other)]
+    Unsupported
+}
+
+/// Properties of a GeoJSON feature describing a realm resource
+///
+/// Unknown properties are ignored.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct GeoJsonProperties {
+    /// The ID of the resource
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// The kind of resource: `vertiport`, `vertipad` or `aircraft`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+
+    /// The label of the resource
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+
+    /// The name of the feature, used as label if no label is provided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// The address of a vertiport
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+
+    /// The altitude of the resource in meters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub altitude: Option<f64>,
+
+    /// The iCalendar schedule of the resource
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+
+    /// The vertiport of a vertipad, or the hangar of an aircraft
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vertiport_id: Option<String>,
+
+    /// The hangar bay of an aircraft
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vertipad_id: Option<String>,
+
+    /// The registration number of an aircraft
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registration_number: Option<String>
+}
+
+/// GeoJSON feature
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct GeoJsonFeature {
+    /// Always `Feature`
+    #[serde(rename = "type")]
+    pub feature_type: String,
+
+    /// The geometry of the feature, if any
+    #[serde(default)]
+    pub geometry: Option<GeoJsonGeometry>,
+
+    /// The properties of the feature, if any
+    #[serde(default)]
+    pub properties: Option<GeoJsonProperties>
+}
+
+/// GeoJSON feature collection
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct GeoJsonFeatureCollection {
+    /// Always `FeatureCollection`
+    #[serde(rename = "type")]
+    pub collection_type: String,
+
+    /// The features of the collection
+    pub features: Vec<GeoJsonFeature>
+}
+
+/// Formats the realm can be exported in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// GeoJSON feature collection (RFC 7946)
+    #[default]
+    GeoJson,
+
+    /// Keyhole Markup Language document
+    Kml
+}
+
+/// Options of the realm export
+///
+/// A bounding box needs all four of its edges.
+#[derive(Debug, Clone, Default)]
+#[derive(Deserialize, Serialize)]
+#[derive(IntoParams)]
+pub struct ExportParams {
+    /// The format of the export, defaults to GeoJSON
+    pub format: Option<ExportFormat>,
+
+    /// Only export the resources created for this run
+    pub run_id: Option<String>,
+
+    /// The southern edge of the bounding box
+    pub min_latitude: Option<f64>,
+
+    /// The western edge of the bounding box
+    pub min_longitude: Option<f64>,
+
+    /// The northern edge of the bounding box
+    pub max_latitude: Option<f64>,
+
+    /// The eastern edge of the bounding box
+    pub max_longitude: Option<f64>
+}
+
+/// Status of a dependency, as reported by its health probe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyStatus {
+    /// The dependency is ready to handle requests
+    Serving,
+
+    /// The dependency could not be reached or is not ready
+    NotServing,
+
+    /// The dependency did not answer within the probe timeout
+    Timeout
+}
+
+/// Health of a single dependency
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct DependencyHealth {
+    /// The name of the dependency, such as `svc-storage/vertiport`
+    pub name: String,
+
+    /// The status of the dependency
+    pub status: DependencyStatus,
+
+    /// True if the service is unhealthy while this dependency is down
+    pub critical: bool,
+
+    /// The time the probe took, in milliseconds
+    pub latency_ms: u64,
+
+    /// The error returned by the probe, if any
+    pub error: Option<String>
+}
+
+/// Health of the service and its dependencies
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct HealthResponse {
+    /// True if all critical dependencies are serving
+    pub healthy: bool,
+
+    /// The health of each dependency
+    pub dependencies: Vec<DependencyHealth>
+}
+
+/// Health of a single realm service, probed with the standard gRPC health
+/// protocol
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct ServiceHealth {
+    /// The name of the service, such as `svc-storage`
+    pub name: String,
+
+    /// The host the service was probed at
+    pub host: String,
+
+    /// The port the service was probed at
+    pub port: u16,
+
+    /// The status of the service
+    pub status: DependencyStatus,
+
+    /// The time the probe took, in milliseconds
+    pub latency_ms: u64,
+
+    /// The error returned by the probe, if any
+    pub error: Option<String>
+}
+
+/// Readiness of all realm services found in the environment
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct RealmHealthResponse {
+    /// True if all realm services are serving
+    pub ready: bool,
+
+    /// The health of each realm service
+    pub services: Vec<ServiceHealth>
+}
+
+/// Options of the readiness gate
+#[derive(Debug, Clone, Default)]
+#[derive(Deserialize, Serialize)]
+#[derive(IntoParams)]
+pub struct WaitParams {
+    /// Time to wait for the critical dependencies in milliseconds,
+    /// defaults to and is limited to the configured readiness gate timeout
+    pub timeout_ms: Option<u64>
+}
+
+/// Outcome of a step of a test suite
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    /// The step succeeded
+    Passed,
+
+    /// The step failed
+    Failed,
+
+    /// The step did not run because an earlier step failed or the run was
+    /// cancelled
+    Skipped
+}
+
+/// Result of a single step of a test suite
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct StepResult {
+    /// The name of the step
+    pub name: String,
+
+    /// The kind of step: seed, call, wait or assert
+    pub kind: String,
+
+    /// The outcome of the step
+    pub status: StepStatus,
+
+    /// The time the step took, in milliseconds
+    pub duration_ms: u64,
+
+    /// The request the step made, with all references replaced
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub request: Option<serde_json::Value>,
+
+    /// The response the step received
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub response: Option<serde_json::Value>,
+
+    /// Why the step failed
+    #[serde(default)]
+    pub error: Option<String>,
+
+    /// The realm resources the step created, in order of creation
+    #[serde(default)]
+    pub created: Vec<CreatedResource>
+}
+
+/// A realm resource created by a step of a test suite
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct CreatedResource {
+    /// The kind of resource: vertiport, vertipad, aircraft, user or scanner
+    pub kind: String,
+
+    /// The symbolic name of the resource in the scenario
+    pub name: String,
+
+    /// The ID of the resource
+    pub id: String
+}
+
+/// Result of a run of a test suite
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct SuiteResult {
+    /// The name of the suite
+    pub suite: String,
+
+    /// The run the seeded resources belong to
+    pub run_id: String,
+
+    /// True if all steps passed
+    pub passed: bool,
+
+    /// When the suite started
+    pub started_at: DateTime<Utc>,
+
+    /// The time the suite took, in milliseconds
+    pub duration_ms: u64,
+
+    /// The result of each step, in the order of the steps
+    pub steps: Vec<StepResult>,
+
+    /// The outcome of the removal of the seeded resources, if any
+    #[serde(default)]
+    pub teardown: Option<RollbackReport>
+}
+
+/// Work done by a background job
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobRequest {
+    /// Create all resources of a scenario
+    Scenario {
+        /// The scenario to create
+        scenario: Scenario
+    },
+
+    /// Generate a synthetic realm and create all of its resources
+    Realm {
+        /// The parameters of the realm
+        realm: GenerateRealmRequest
+    },
+
+    /// Run a test suite of the configured suite directory
+    Suite {
+        /// The name of the suite
+        suite: String
+    }
+}
+
+/// State of a background job
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// The job has been accepted but did not start yet
+    Queued,
+
+    /// The job is running
+    Running,
+
+    /// The job finished successfully
+    Succeeded,
+
+    /// The job finished with an error
+    Failed,
+
+    /// The job was cancelled, its changes have been rolled back
+    Cancelled
+}
+
+/// Progress of a background job
+#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct JobProgress {
+    /// The number of resources created or steps run so far
+    pub completed: u64,
+
+    /// The number of resources to create or steps to run
+    pub total: u64,
+
+    /// What the job is doing
+    #[serde(default)]
+    pub message: Option<String>
+}
+
+/// Result of a finished background job
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobResult {
+    /// The resources created by a scenario or realm job
+    Seed(ScenarioResponse),
+
+    /// The result of a suite job
+    Suite(SuiteResult)
+}
+
+/// A background job
+#[derive(Debug, Clone)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct JobResponse {
+    /// The ID of the job
+    pub id: String,
+
+    /// The kind of work: scenario, realm or suite
+    pub kind: String,
+
+    /// The state of the job
+    pub status: JobStatus,
+
+    /// The progress of the job
+    pub progress: JobProgress,
+
+    /// When the job was accepted
+    pub created_at: DateTime<Utc>,
+
+    /// When the job started
+    #[serde(default)]
+    pub started_at: Option<DateTime<Utc>>,
+
+    /// When the job finished
+    #[serde(default)]
+    pub finished_at: Option<DateTime<Utc>>,
+
+    /// The result of the job, once it succeeded. Suite jobs also return
+    /// their result when a step failed.
+    #[serde(default)]
+    pub result: Option<JobResult>,
+
+    /// Why the job failed, including the outcome of the rollback
+    #[serde(default)]
+    pub error: Option<ErrorResponse>
+}
+
+/// Formats a suite run can be reported in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    /// JUnit XML, as ingested by most CI servers
+    Junit,
+
+    /// Test Anything Protocol, version 13
+    Tap,
+
+    /// The versioned JSON schema of [`SuiteReport`]
+    #[default]
+    Json
+}
+
+/// Options of a suite report
+#[derive(Debug, Clone, Default)]
+#[derive(Deserialize, Serialize)]
+#[derive(IntoParams)]
+pub struct ReportParams {
+    /// The format of the report, defaults to JSON
+    pub format: Option<ReportFormat>
+}
+
+/// Number of steps of a suite run by outcome
+#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct ReportSummary {
+    /// The number of steps
+    pub total: u32,
+
+    /// The number of steps which passed
+    pub passed: u32,
+
+    /// The number of steps which failed
+    pub failed: u32,
+
+    /// The number of steps which did not run
+    pub skipped: u32
+}
+
+/// Report of a single step of a suite run
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct StepReport {
+    /// The name of the step
+    pub name: String,
+
+    /// The kind of step: seed, call, wait or assert
+    pub kind: String,
+
+    /// The outcome of the step
+    pub status: StepStatus,
+
+    /// The time the step took, in milliseconds
+    pub duration_ms: u64,
+
+    /// Why the step failed
+    #[serde(default)]
+    pub error: Option<String>,
+
+    /// The realm resources the step created, in order of creation
+    #[serde(default)]
+    pub created: Vec<CreatedResource>
+}
+
+/// Report of a suite run
+///
+/// Fields are only added to this schema. Incompatible changes increase the
+/// schema version.
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct SuiteReport {
+    /// The version of the report schema
+    pub schema_version: u32,
+
+    /// The name of the suite
+    pub suite: String,
+
+    /// The run the seeded resources belong to
+    pub run_id: String,
+
+    /// True if all steps passed
+    pub passed: bool,
+
+    /// When the suite started
+    pub started_at: DateTime<Utc>,
+
+    /// The time the suite took, in milliseconds
+    pub duration_ms: u64,
+
+    /// The number of steps by outcome
+    pub summary: ReportSummary,
+
+    /// The report of each step, in the order of the steps
+    pub steps: Vec<StepReport>,
+
+    /// The outcome of the removal of the seeded resources, if any
+    #[serde(default)]
+    pub teardown: Option<RollbackReport>
+}
+
+/// A suite run in the run history
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct RunOverview {
+    /// The ID of the run
+    pub run_id: String,
+
+    /// The name of the suite
+    pub suite: String,
+
+    /// True if all steps passed
+    pub passed: bool,
+
+    /// When the suite started
+    pub started_at: DateTime<Utc>,
+
+    /// The time the suite took, in milliseconds
+    pub duration_ms: u64,
+
+    /// The number of steps by outcome
+    pub summary: ReportSummary
+}
+
+/// Filters of the run history
+#[derive(Debug, Clone, Default)]
+#[derive(Deserialize, Serialize)]
+#[derive(IntoParams)]
+pub struct RunsParams {
+    /// Only list the runs of this suite
+    pub suite: Option<String>,
+
+    /// Only list the runs which passed, or failed if false
+    pub passed: Option<bool>,
+
+    /// The maximum number of runs to list, defaults to 20
+    pub limit: Option<u32>
+}
+
+/// How a step changed between two runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StepChange {
+    /// The step has the same outcome in both runs
+    Unchanged,
+
+    /// The step failed in the base run and passed in the other run
+    Fixed,
+
+    /// The step passed in the base run and failed in the other run
+    Broken,
+
+    /// The outcome of the step changed otherwise, such as a step which
+    /// was skipped in one of the runs
+    Changed,
+
+    /// The step is only part of the other run
+    Added,
+
+    /// The step is only part of the base run
+    Removed
+}
+
+/// A step compared between two runs
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct StepComparison {
+    /// The name of the step
+    pub name: String,
+
+    /// How the step changed
+    pub change: StepChange,
+
+    /// The outcome of the step in the base run
+    #[serde(default)]
+    pub base_status: Option<StepStatus>,
+
+    /// The outcome of the step in the other run
+    #[serde(default)]
+    pub other_status: Option<StepStatus>,
+
+    /// The time the step took in the base run, in milliseconds
+    #[serde(default)]
+    pub base_duration_ms: Option<u64>,
+
+    /// The time the step took in the other run, in milliseconds
+    #[serde(default)]
+    pub other_duration_ms: Option<u64>,
+
+    /// The time the step took in the other run minus the time it took in
+    /// the base run, in milliseconds
+    #[serde(default)]
+    pub duration_change_ms: Option<i64>,
+
+    /// Why the step failed in the other run
+    #[serde(default)]
+    pub error: Option<String>
+}
+
+/// Step by step comparison of two runs
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize)]
+#[derive(ToSchema)]
+pub struct RunComparison {
+    /// The run compared against
+    pub base: RunOverview,
+
+    /// The run compared to the base run
+    pub other: RunOverview,
+
+    /// The steps of the base run in order, followed by the steps which are
+    /// only part of the other run
+    pub steps: Vec<StepComparison>
 }
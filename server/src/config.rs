@@ -31,6 +31,34 @@ pub struct Config {
     /// Full url (including port number) to be allowed as request origin for
     /// REST requests
     pub rest_cors_allowed_origin: String,
+    /// Maximum number of items of a bulk request seeded at the same time
+    pub rest_bulk_concurrency_limit: u8,
+    /// Optional path to a YAML or JSON file with vehicle models to add to
+    /// the built in catalog
+    pub vehicle_model_catalog: Option<String>,
+    /// Comma separated dependencies which have to be serving for the
+    /// service to be healthy. A service name, such as `svc-storage`,
+    /// includes all of its clients.
+    pub health_critical_dependencies: String,
+    /// Time to wait for the answer of a dependency health probe
+    pub health_probe_timeout_ms: u64,
+    /// Time between the dependency probes of the gRPC health watcher
+    pub health_watch_interval_ms: u64,
+    /// Time the readiness gate waits for the critical dependencies before
+    /// giving up
+    pub readiness_gate_timeout_ms: u64,
+    /// Delay after the first failed probe of the readiness gate, doubled
+    /// after each following probe
+    pub readiness_gate_initial_backoff_ms: u64,
+    /// Upper limit of the delay between the probes of the readiness gate
+    pub readiness_gate_max_backoff_ms: u64,
+    /// Directory with the YAML and JSON test suites of the runner
+    pub runner_suite_dir: String,
+    /// Path of the SQLite database keeping the history of suite runs
+    pub runner_history_db: String,
+    /// Number of runs kept in the history, older runs are removed. Zero
+    /// keeps all runs.
+    pub runner_history_max_runs: u32,
 }
 
 impl Default for Config {
@@ -54,6 +82,17 @@ impl Config {
             rest_request_limit_per_second: 2,
             rest_concurrency_limit_per_service: 5,
             rest_cors_allowed_origin: String::from("http://localhost:3000"),
+            rest_bulk_concurrency_limit: 10,
+            vehicle_model_catalog: None,
+            health_critical_dependencies: String::from("svc-storage,svc-gis"),
+            health_probe_timeout_ms: 1000,
+            health_watch_interval_ms: 5000,
+            readiness_gate_timeout_ms: 60000,
+            readiness_gate_initial_backoff_ms: 250,
+            readiness_gate_max_backoff_ms: 5000,
+            runner_suite_dir: String::from("suites"),
+            runner_history_db: String::from("/var/lib/svc-itest/history.db"),
+            runner_history_max_runs: 1000,
         }
     }
 
@@ -79,6 +118,40 @@ impl Config {
                 "rest_cors_allowed_origin",
                 default_config.rest_cors_allowed_origin,
             )?
+            .set_default(
+                "rest_bulk_concurrency_limit",
+                default_config.rest_bulk_concurrency_limit,
+            )?
+            .set_default(
+                "health_critical_dependencies",
+                default_config.health_critical_dependencies,
+            )?
+            .set_default(
+                "health_probe_timeout_ms",
+                default_config.health_probe_timeout_ms,
+            )?
+            .set_default(
+                "health_watch_interval_ms",
+                default_config.health_watch_interval_ms,
+            )?
+            .set_default(
+                "readiness_gate_timeout_ms",
+                default_config.readiness_gate_timeout_ms,
+            )?
+            .set_default(
+                "readiness_gate_initial_backoff_ms",
+                default_config.readiness_gate_initial_backoff_ms,
+            )?
+            .set_default(
+                "readiness_gate_max_backoff_ms",
+                default_config.readiness_gate_max_backoff_ms,
+            )?
+            .set_default("runner_suite_dir", default_config.runner_suite_dir)?
+            .set_default("runner_history_db", default_config.runner_history_db)?
+            .set_default(
+                "runner_history_max_runs",
+                default_config.runner_history_max_runs,
+            )?
             .add_source(Environment::default().separator("__"))
             .build()?
             .try_deserialize()
@@ -109,6 +182,23 @@ mod tests {
             config.rest_cors_allowed_origin,
             String::from("http://localhost:3000")
         );
+        assert_eq!(config.rest_bulk_concurrency_limit, 10);
+        assert_eq!(config.vehicle_model_catalog, None);
+        assert_eq!(
+            config.health_critical_dependencies,
+            String::from("svc-storage,svc-gis")
+        );
+        assert_eq!(config.health_probe_timeout_ms, 1000);
+        assert_eq!(config.health_watch_interval_ms, 5000);
+        assert_eq!(config.readiness_gate_timeout_ms, 60000);
+        assert_eq!(config.readiness_gate_initial_backoff_ms, 250);
+        assert_eq!(config.readiness_gate_max_backoff_ms, 5000);
+        assert_eq!(config.runner_suite_dir, String::from("suites"));
+        assert_eq!(
+            config.runner_history_db,
+            String::from("/var/lib/svc-itest/history.db")
+        );
+        assert_eq!(config.runner_history_max_runs, 1000);
 
         ut_info!("(test_config_from_default) Success.");
     }
@@ -131,6 +221,17 @@ mod tests {
             "REST_CORS_ALLOWED_ORIGIN",
             "https://allowed.origin.host:443",
         );
+        std::env::set_var("REST_BULK_CONCURRENCY_LIMIT", "20");
+        std::env::set_var("VEHICLE_MODEL_CATALOG", "vehicle_models.yaml");
+        std::env::set_var("HEALTH_CRITICAL_DEPENDENCIES", "svc-storage/vertiport");
+        std::env::set_var("HEALTH_PROBE_TIMEOUT_MS", "250");
+        std::env::set_var("HEALTH_WATCH_INTERVAL_MS", "2000");
+        std::env::set_var("READINESS_GATE_TIMEOUT_MS", "30000");
+        std::env::set_var("READINESS_GATE_INITIAL_BACKOFF_MS", "100");
+        std::env::set_var("READINESS_GATE_MAX_BACKOFF_MS", "2000");
+        std::env::set_var("RUNNER_SUITE_DIR", "/etc/itest/suites");
+        std::env::set_var("RUNNER_HISTORY_DB", "/var/lib/itest/history.db");
+        std::env::set_var("RUNNER_HISTORY_MAX_RUNS", "50");
 
         let config = Config::try_from_env();
         assert!(config.is_ok());
@@ -149,6 +250,26 @@ mod tests {
             config.rest_cors_allowed_origin,
             String::from("https://allowed.origin.host:443")
         );
+        assert_eq!(config.rest_bulk_concurrency_limit, 20);
+        assert_eq!(
+            config.vehicle_model_catalog,
+            Some(String::from("vehicle_models.yaml"))
+        );
+        assert_eq!(
+            config.health_critical_dependencies,
+            String::from("svc-storage/vertiport")
+        );
+        assert_eq!(config.health_probe_timeout_ms, 250);
+        assert_eq!(config.health_watch_interval_ms, 2000);
+        assert_eq!(config.readiness_gate_timeout_ms, 30000);
+        assert_eq!(config.readiness_gate_initial_backoff_ms, 100);
+        assert_eq!(config.readiness_gate_max_backoff_ms, 2000);
+        assert_eq!(config.runner_suite_dir, String::from("/etc/itest/suites"));
+        assert_eq!(
+            config.runner_history_db,
+            String::from("/var/lib/itest/history.db")
+        );
+        assert_eq!(config.runner_history_max_runs, 50);
 
         ut_info!("(test_config_from_env) Success.");
     }
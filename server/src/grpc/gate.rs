@@ -0,0 +1,257 @@
+//! Readiness gate, waiting for the critical dependencies to come up
+//!
+//! Containers of a realm start in any order. The gate polls the critical
+//! dependencies with exponential backoff and jitter, so seeding and test
+//! runs only start once the services they need are serving.
+
+use crate::grpc::client::GrpcClients;
+use crate::grpc::health;
+use crate::rest::api::rest_types::{DependencyHealth, DependencyStatus};
+use crate::seed::{DependencyError, SeedError};
+use crate::Config;
+use rand::Rng;
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// Backoff between the probes of the gate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+    /// Delay after the first failed probe
+    pub initial: Duration,
+    /// Upper limit of the delay
+    pub max: Duration,
+}
+
+impl Backoff {
+    /// Returns the delay after `attempt` failed probes, starting at 0.
+    /// The delay doubles with each attempt up to the limit, of which a
+    /// random part up to half is left out so waiting clients spread out.
+    pub fn delay(&self, attempt: u32, rng: &mut impl Rng) -> Duration {
+        let base = self
+            .initial
+            .saturating_mul(2_u32.saturating_pow(attempt))
+            .min(self.max);
+        let jitter = rng.gen_range(0.0..=0.5);
+        base.mul_f64(1.0 - jitter)
+    }
+}
+
+/// The dependencies did not come up before the deadline
+#[derive(Debug, Clone, PartialEq)]
+pub struct GateTimeout {
+    /// Time waited for the dependencies
+    pub waited: Duration,
+    /// Number of probes made
+    pub attempts: u32,
+    /// The critical dependencies which were down at the last probe
+    pub down: Vec<DependencyHealth>,
+}
+
+impl Display for GateTimeout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let down: Vec<String> = self
+            .down
+            .iter()
+            .map(|dependency| match &dependency.error {
+                Some(error) => format!("{} ({:?}: {})", dependency.name, dependency.status, error),
+                None => format!("{} ({:?})", dependency.name, dependency.status),
+            })
+            .collect();
+        write!(
+            f,
+            "dependencies not ready after {} ms and {} probes: {}",
+            self.waited.as_millis(),
+            self.attempts,
+            down.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for GateTimeout {}
+
+impl From<GateTimeout> for SeedError {
+    fn from(timeout: GateTimeout) -> Self {
+        let dependency = timeout
+            .down
+            .first()
+            .map(|dependency| dependency.name.clone())
+            .unwrap_or_default();
+        SeedError::Dependency(DependencyError {
+            dependency,
+            code: tonic::Code::Unavailable,
+            message: timeout.to_string(),
+            field: None,
+        })
+    }
+}
+
+/// Runs `probe` until all critical dependencies are serving or `timeout`
+/// passed, sleeping according to `backoff` in between. Returns the last
+/// probe results when the dependencies are up.
+pub async fn wait_until_healthy<F, Fut>(
+    timeout: Duration,
+    backoff: Backoff,
+    mut probe: F,
+) -> Result<Vec<DependencyHealth>, GateTimeout>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Vec<DependencyHealth>>,
+{
+    let start = Instant::now();
+    let mut attempts = 0;
+
+    loop {
+        let dependencies = probe().await;
+        attempts += 1;
+        if health::is_healthy(&dependencies) {
+            grpc_info!(
+                "(wait_until_healthy) dependencies ready after {} ms and {} probes.",
+                start.elapsed().as_millis(),
+                attempts
+            );
+            return Ok(dependencies);
+        }
+
+        let delay = backoff.delay(attempts - 1, &mut rand::thread_rng());
+        if start.elapsed() + delay > timeout {
+            let timeout = GateTimeout {
+                waited: start.elapsed(),
+                attempts,
+                down: dependencies
+                    .into_iter()
+                    .filter(|dependency| {
+                        dependency.critical && dependency.status != DependencyStatus::Serving
+                    })
+                    .collect(),
+            };
+            grpc_error!("(wait_until_healthy) {}.", timeout);
+            return Err(timeout);
+        }
+
+        grpc_debug!(
+            "(wait_until_healthy) dependencies not ready, next probe in {} ms.",
+            delay.as_millis()
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Waits for the critical dependencies of the configuration, giving up
+/// after `timeout`
+pub async fn wait_for_dependencies(
+    grpc_clients: &GrpcClients,
+    config: &Config,
+    timeout: Duration,
+) -> Result<Vec<DependencyHealth>, GateTimeout> {
+    grpc_debug!(
+        "(wait_for_dependencies) entry [timeout {} ms].",
+        timeout.as_millis()
+    );
+
+    let critical = health::parse_dependencies(&config.health_critical_dependencies);
+    let probe_timeout = Duration::from_millis(config.health_probe_timeout_ms);
+    let backoff = Backoff {
+        initial: Duration::from_millis(config.readiness_gate_initial_backoff_ms),
+        max: Duration::from_millis(config.readiness_gate_max_backoff_ms),
+    };
+
+    wait_until_healthy(timeout, backoff, || {
+        health::probe_all(grpc_clients, probe_timeout, &critical)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn dependency(name: &str, status: DependencyStatus) -> DependencyHealth {
+        DependencyHealth {
+            name: name.to_string(),
+            status,
+            critical: true,
+            latency_ms: 1,
+            error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_backoff_delay() {
+        crate::get_log_handle().await;
+        ut_info!("(test_backoff_delay) Start.");
+
+        let backoff = Backoff {
+            initial: Duration::from_millis(100),
+            max: Duration::from_millis(1000),
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        for (attempt, base) in [
+            (0, 100),
+            (1, 200),
+            (2, 400),
+            (3, 800),
+            (4, 1000),
+            (40, 1000),
+        ] {
+            let delay = backoff.delay(attempt, &mut rng);
+            assert!(delay <= Duration::from_millis(base), "attempt {}", attempt);
+            assert!(
+                delay >= Duration::from_millis(base / 2),
+                "attempt {}",
+                attempt
+            );
+        }
+
+        ut_info!("(test_backoff_delay) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_healthy() {
+        crate::get_log_handle().await;
+        ut_info!("(test_wait_until_healthy) Start.");
+
+        let backoff = Backoff {
+            initial: Duration::from_millis(1),
+            max: Duration::from_millis(5),
+        };
+
+        // svc-gis comes up at the third probe
+        let mut probes = 0;
+        let result = wait_until_healthy(Duration::from_secs(5), backoff, || {
+            probes += 1;
+            let status = match probes {
+                3.. => DependencyStatus::Serving,
+                _ => DependencyStatus::NotServing,
+            };
+            async move { vec![dependency("svc-gis", status)] }
+        })
+        .await;
+        assert!(result.is_ok());
+        assert_eq!(probes, 3);
+
+        // svc-storage never comes up, svc-gis does
+        let result = wait_until_healthy(Duration::from_millis(20), backoff, || async {
+            vec![
+                dependency("svc-gis", DependencyStatus::Serving),
+                dependency("svc-storage/user", DependencyStatus::Timeout),
+            ]
+        })
+        .await;
+        let timeout = result.unwrap_err();
+        assert!(timeout.attempts > 1);
+        assert_eq!(timeout.down.len(), 1);
+        assert_eq!(timeout.down[0].name, "svc-storage/user");
+
+        let error: SeedError = timeout.into();
+        assert!(matches!(
+            error,
+            SeedError::Dependency(DependencyError { ref dependency, code: tonic::Code::Unavailable, .. })
+                if dependency == "svc-storage/user"
+        ));
+
+        ut_info!("(test_wait_until_healthy) Success.");
+    }
+}
@@ -0,0 +1,448 @@
+//! Health probes of the gRPC dependencies
+//!
+//! The dependency watcher probes the dependencies in the background and
+//! keeps the gRPC health status and the `is_ready` answer up to date.
+//!
+//! The realm probe finds every `*_HOST_GRPC` and `*_PORT_GRPC` pair in the
+//! environment and checks each service with the standard gRPC health
+//! protocol (`grpc.health.v1`).
+
+use crate::grpc::client::GrpcClients;
+use crate::grpc::server::{RpcServiceServer, ServerImpl};
+use crate::rest::api::rest_types::{
+    DependencyHealth, DependencyStatus, RealmHealthResponse, ServiceHealth,
+};
+use crate::Config;
+use futures::future::{join_all, BoxFuture, FutureExt};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use svc_gis_client_grpc::prelude::GisServiceClient;
+use svc_storage_client_grpc::prelude::{ReadyRequest, SimpleClient};
+use tonic::transport::Endpoint;
+use tonic::Status;
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_client::HealthClient;
+use tonic_health::pb::HealthCheckRequest;
+use tonic_health::server::HealthReporter;
+
+/// Returns the dependencies named in a comma separated list
+pub fn parse_dependencies(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Returns true if the dependency is one of the critical dependencies.
+/// A service name includes all of its clients.
+pub fn is_critical(name: &str, critical: &[String]) -> bool {
+    critical.iter().any(|critical| {
+        name == critical
+            || name
+                .strip_prefix(critical.as_str())
+                .map(|rest| rest.starts_with('/'))
+                .unwrap_or(false)
+    })
+}
+
+/// Returns true if all critical dependencies are serving
+pub fn is_healthy(dependencies: &[DependencyHealth]) -> bool {
+    dependencies
+        .iter()
+        .filter(|dependency| dependency.critical)
+        .all(|dependency| dependency.status == DependencyStatus::Serving)
+}
+
+/// Runs a single readiness probe, waiting up to `timeout` for the answer
+async fn probe<F>(name: &str, timeout: Duration, critical: &[String], ready: F) -> DependencyHealth
+where
+    F: Future<Output = Result<bool, Status>>,
+{
+    let start = Instant::now();
+    let result = tokio::time::timeout(timeout, ready).await;
+    let latency_ms = start.elapsed().as_millis() as u64;
+
+    let (status, error) = match result {
+        Ok(Ok(true)) => (DependencyStatus::Serving, None),
+        Ok(Ok(false)) => (
+            DependencyStatus::NotServing,
+            Some("dependency is not ready".to_string()),
+        ),
+        Ok(Err(e)) => (DependencyStatus::NotServing, Some(e.message().to_string())),
+        Err(_) => (
+            DependencyStatus::Timeout,
+            Some(format!("no answer within {} ms", timeout.as_millis())),
+        ),
+    };
+
+    if status != DependencyStatus::Serving {
+        grpc_debug!("(probe) {} is {:?}: {:?}.", name, status, error);
+    }
+
+    DependencyHealth {
+        name: name.to_string(),
+        status,
+        critical: is_critical(name, critical),
+        latency_ms,
+        error,
+    }
+}
+
+/// Probes every client of [`GrpcClients`] at the same time
+pub async fn probe_all(
+    grpc_clients: &GrpcClients,
+    timeout: Duration,
+    critical: &[String],
+) -> Vec<DependencyHealth> {
+    let storage = &grpc_clients.storage;
+
+    // Each client has its own type, box the ready checks to run them together
+    macro_rules! storage_ready {
+        ($client:expr) => {
+            async { Ok($client.is_ready(ReadyRequest {}).await?.into_inner().ready) }.boxed()
+        };
+    }
+
+    let ready_checks: Vec<(&str, BoxFuture<'_, Result<bool, Status>>)> = vec![
+        ("svc-storage/adsb", storage_ready!(storage.adsb)),
+        ("svc-storage/vertiport", storage_ready!(storage.vertiport)),
+        ("svc-storage/vertipad", storage_ready!(storage.vertipad)),
+        ("svc-storage/vehicle", storage_ready!(storage.vehicle)),
+        ("svc-storage/user", storage_ready!(storage.user)),
+        ("svc-storage/scanner", storage_ready!(storage.scanner)),
+        (
+            "svc-gis",
+            async {
+                let request = svc_gis_client_grpc::client::ReadyRequest {};
+                Ok(grpc_clients.gis.is_ready(request).await?.into_inner().ready)
+            }
+            .boxed(),
+        ),
+    ];
+
+    join_all(
+        ready_checks
+            .into_iter()
+            .map(|(name, ready)| probe(name, timeout, critical, ready)),
+    )
+    .await
+}
+
+/// Returns the `(name, host, port)` of every realm service with both a
+/// `<SERVICE>_HOST_GRPC` and a `<SERVICE>_PORT_GRPC` variable, sorted by
+/// name. `STORAGE_HOST_GRPC` becomes `svc-storage`.
+pub fn discover_services(
+    vars: impl Iterator<Item = (String, String)>,
+) -> Vec<(String, String, u16)> {
+    let vars: Vec<(String, String)> = vars.collect();
+
+    let mut services: Vec<(String, String, u16)> = vars
+        .iter()
+        .filter_map(|(key, host)| {
+            let prefix = key.strip_suffix("_HOST_GRPC")?;
+            let port_key = format!("{}_PORT_GRPC", prefix);
+            let (_, port) = vars.iter().find(|(key, _)| *key == port_key)?;
+            let port = match port.trim().parse::<u16>() {
+                Ok(port) => port,
+                Err(e) => {
+                    grpc_warn!(
+                        "(discover_services) invalid port [{}] for {}: {}",
+                        port,
+                        port_key,
+                        e
+                    );
+                    return None;
+                }
+            };
+            let name = format!("svc-{}", prefix.to_lowercase().replace('_', "-"));
+            Some((name, host.trim().to_string(), port))
+        })
+        .collect();
+
+    services.sort();
+    services
+}
+
+/// Asks a service for its overall status with the gRPC health protocol
+async fn health_check(host: &str, port: u16) -> Result<bool, Status> {
+    let endpoint = Endpoint::from_shared(format!("http://{}:{}", host, port))
+        .map_err(|e| Status::invalid_argument(format!("invalid address: {}", e)))?;
+    let channel = endpoint
+        .connect()
+        .await
+        .map_err(|e| Status::unavailable(format!("could not connect: {}", e)))?;
+
+    let response = HealthClient::new(channel)
+        .check(HealthCheckRequest {
+            service: String::new(),
+        })
+        .await?;
+    Ok(response.into_inner().status == ServingStatus::Serving as i32)
+}
+
+/// Probes every realm service found in the environment at the same time.
+/// The realm is ready when all of them are serving.
+pub async fn probe_realm(timeout: Duration) -> RealmHealthResponse {
+    grpc_debug!("(probe_realm) entry.");
+
+    let services = discover_services(std::env::vars());
+    let services: Vec<ServiceHealth> =
+        join_all(services.into_iter().map(|(name, host, port)| async move {
+            let health = probe(&name, timeout, &[], health_check(&host, port)).await;
+            ServiceHealth {
+                name,
+                host,
+                port,
+                status: health.status,
+                latency_ms: health.latency_ms,
+                error: health.error,
+            }
+        }))
+        .await;
+
+    let ready = services
+        .iter()
+        .all(|service| service.status == DependencyStatus::Serving);
+    grpc_debug!(
+        "(probe_realm) {} services probed, realm ready: {}.",
+        services.len(),
+        ready
+    );
+    RealmHealthResponse { ready, services }
+}
+
+/// Readiness of the service, shared by the dependency watcher and the
+/// gRPC server. Ready unless a watcher reports otherwise.
+#[derive(Debug, Clone)]
+pub struct Readiness(Arc<AtomicBool>);
+
+impl Default for Readiness {
+    fn default() -> Self {
+        Readiness::new(true)
+    }
+}
+
+impl Readiness {
+    /// Create a new readiness with the provided initial state
+    pub fn new(ready: bool) -> Self {
+        Readiness(Arc::new(AtomicBool::new(ready)))
+    }
+
+    /// Returns true if the service is ready
+    pub fn is_ready(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Updates the readiness, returns true if it changed
+    fn set(&self, ready: bool) -> bool {
+        self.0.swap(ready, Ordering::SeqCst) != ready
+    }
+}
+
+/// Applies the probe results to the readiness and the gRPC health status,
+/// logging the transition if the readiness changed
+async fn update(
+    readiness: &Readiness,
+    health_reporter: &mut HealthReporter,
+    dependencies: &[DependencyHealth],
+) -> bool {
+    let ready = is_healthy(dependencies);
+    if !readiness.set(ready) {
+        return false;
+    }
+
+    match ready {
+        true => {
+            grpc_info!("(update) critical dependencies recovered, now SERVING.");
+            health_reporter
+                .set_serving::<RpcServiceServer<ServerImpl>>()
+                .await;
+        }
+        false => {
+            let down: Vec<String> = dependencies
+                .iter()
+                .filter(|dependency| {
+                    dependency.critical && dependency.status != DependencyStatus::Serving
+                })
+                .map(|dependency| format!("{} ({:?})", dependency.name, dependency.status))
+                .collect();
+            grpc_error!(
+                "(update) critical dependencies down: {}, now NOT_SERVING.",
+                down.join(", ")
+            );
+            health_reporter
+                .set_not_serving::<RpcServiceServer<ServerImpl>>()
+                .await;
+        }
+    }
+
+    true
+}
+
+/// Probes the dependencies every `health_watch_interval_ms`, updating the
+/// readiness and the gRPC health status when the critical dependencies go
+/// down or recover. Runs until the task is aborted.
+pub async fn watch_dependencies(
+    grpc_clients: GrpcClients,
+    config: Config,
+    readiness: Readiness,
+    mut health_reporter: HealthReporter,
+) {
+    grpc_debug!("(watch_dependencies) entry.");
+
+    let critical = parse_dependencies(&config.health_critical_dependencies);
+    let timeout = Duration::from_millis(config.health_probe_timeout_ms);
+    let mut interval = tokio::time::interval(Duration::from_millis(
+        config.health_watch_interval_ms.max(1),
+    ));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        let dependencies = probe_all(&grpc_clients, timeout, &critical).await;
+        update(&readiness, &mut health_reporter, &dependencies).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_is_critical() {
+        crate::get_log_handle().await;
+        ut_info!("(test_is_critical) Start.");
+
+        let critical = parse_dependencies(" svc-storage/vertiport, svc-gis,,");
+        assert_eq!(critical, vec!["svc-storage/vertiport", "svc-gis"]);
+        assert!(is_critical("svc-storage/vertiport", &critical));
+        assert!(!is_critical("svc-storage/vertipad", &critical));
+        assert!(is_critical("svc-gis", &critical));
+
+        let critical = parse_dependencies("svc-storage");
+        assert!(is_critical("svc-storage/vertipad", &critical));
+        assert!(!is_critical("svc-storage-other", &critical));
+        assert!(!is_critical("svc-gis", &critical));
+
+        ut_info!("(test_is_critical) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_probe() {
+        crate::get_log_handle().await;
+        ut_info!("(test_probe) Start.");
+
+        let critical = parse_dependencies("svc-gis");
+        let timeout = Duration::from_millis(20);
+
+        let serving = probe("svc-gis", timeout, &critical, async { Ok(true) }).await;
+        assert_eq!(serving.status, DependencyStatus::Serving);
+        assert!(serving.critical);
+
+        let failing = probe("svc-storage/user", timeout, &critical, async {
+            Err(Status::unavailable("connection refused"))
+        })
+        .await;
+        assert_eq!(failing.status, DependencyStatus::NotServing);
+        assert_eq!(failing.error.as_deref(), Some("connection refused"));
+        assert!(!failing.critical);
+
+        // Only critical dependencies affect the health
+        assert!(is_healthy(&[serving.clone(), failing]));
+
+        let slow = probe("svc-gis", timeout, &critical, async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Ok(true)
+        })
+        .await;
+        assert_eq!(slow.status, DependencyStatus::Timeout);
+        assert!(!is_healthy(&[serving, slow]));
+
+        ut_info!("(test_probe) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_discover_services() {
+        crate::get_log_handle().await;
+        ut_info!("(test_discover_services) Start.");
+
+        let vars = [
+            ("STORAGE_HOST_GRPC", "svc-storage"),
+            ("STORAGE_PORT_GRPC", "50051"),
+            ("DEVOPS_TEST_HOST_GRPC", "svc-devops-test"),
+            ("DEVOPS_TEST_PORT_GRPC", " 50052 "),
+            ("DOCKER_PORT_GRPC", "50051"),
+            ("GIS_HOST_GRPC", "svc-gis"),
+            ("PRICING_HOST_GRPC", "svc-pricing"),
+            ("PRICING_PORT_GRPC", "not-a-port"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()));
+
+        assert_eq!(
+            discover_services(vars),
+            vec![
+                (
+                    "svc-devops-test".to_string(),
+                    "svc-devops-test".to_string(),
+                    50052
+                ),
+                ("svc-storage".to_string(), "svc-storage".to_string(), 50051),
+            ]
+        );
+
+        ut_info!("(test_discover_services) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_health_check_unreachable() {
+        crate::get_log_handle().await;
+        ut_info!("(test_health_check_unreachable) Start.");
+
+        // Nothing listens on port 1
+        let health = probe(
+            "svc-unreachable",
+            Duration::from_millis(500),
+            &[],
+            health_check("127.0.0.1", 1),
+        )
+        .await;
+        assert_ne!(health.status, DependencyStatus::Serving);
+        assert!(health.error.is_some());
+
+        ut_info!("(test_health_check_unreachable) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_update() {
+        crate::get_log_handle().await;
+        ut_info!("(test_update) Start.");
+
+        let (mut health_reporter, _) = tonic_health::server::health_reporter();
+        let readiness = Readiness::new(false);
+        let mut dependency = DependencyHealth {
+            name: "svc-gis".to_string(),
+            status: DependencyStatus::Serving,
+            critical: true,
+            latency_ms: 1,
+            error: None,
+        };
+
+        assert!(update(&readiness, &mut health_reporter, &[dependency.clone()]).await);
+        assert!(readiness.is_ready());
+        assert!(!update(&readiness, &mut health_reporter, &[dependency.clone()]).await);
+
+        dependency.status = DependencyStatus::Timeout;
+        assert!(update(&readiness, &mut health_reporter, &[dependency.clone()]).await);
+        assert!(!readiness.is_ready());
+
+        // Non critical dependencies don't affect the readiness
+        dependency.critical = false;
+        assert!(update(&readiness, &mut health_reporter, &[dependency]).await);
+        assert!(readiness.is_ready());
+
+        ut_info!("(test_update) Success.");
+    }
+}
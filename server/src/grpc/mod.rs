@@ -4,4 +4,6 @@
 #[macro_use]
 pub mod macros;
 pub mod client;
+pub mod gate;
+pub mod health;
 pub mod server;
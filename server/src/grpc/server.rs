@@ -2,28 +2,67 @@
 
 /// module generated from proto/svc-itest-grpc.proto
 mod grpc_server {
-    #![allow(unused_qualifications, missing_docs)]
+    #![allow(unused_qualifications, missing_docs, non_camel_case_types)]
     tonic::include_proto!("grpc");
 }
 pub use grpc_server::rpc_service_server::{RpcService, RpcServiceServer};
-pub use grpc_server::{ReadyRequest, ReadyResponse};
+pub use grpc_server::{
+    run_event, ListSuitesRequest, ListSuitesResponse, ReadyRequest, ReadyResponse,
+    RealmHealthRequest, RealmHealthResponse, RunEvent, RunLog, RunStarted, RunSuiteRequest,
+    RunSummary, ServiceHealth, StepFinished, StepStarted, SuiteInfo,
+};
 
+use crate::grpc::client::GrpcClients;
+use crate::grpc::health::{self, Readiness};
+use crate::rest::api::rest_types;
+use crate::runner::{self, suite::Suite, StepStatus};
 use crate::shutdown_signal;
 use crate::Config;
 
+use futures::stream::{self, BoxStream, StreamExt};
 use std::fmt::Debug;
 use std::net::SocketAddr;
 use tonic::transport::Server;
 use tonic::{Request, Response, Status};
 
+/// Stream of the events of a suite run
+pub type RunEventStream = BoxStream<'static, Result<RunEvent, Status>>;
+
 /// struct to implement the gRPC server functions
-#[derive(Debug, Default, Copy, Clone)]
-pub struct ServerImpl {}
+#[derive(Debug, Clone)]
+pub struct ServerImpl {
+    /// Readiness of the service, based on its critical dependencies
+    pub readiness: Readiness,
+
+    /// Configuration the server was started with
+    pub config: Config,
+
+    /// Clients of the realm services, used by suite runs
+    pub grpc_clients: GrpcClients,
+}
+
+impl ServerImpl {
+    /// Creates the server functions for the provided configuration
+    pub fn new(config: Config, readiness: Readiness) -> Self {
+        ServerImpl {
+            readiness,
+            grpc_clients: GrpcClients::default(config.clone()),
+            config,
+        }
+    }
+}
+
+impl Default for ServerImpl {
+    fn default() -> Self {
+        ServerImpl::new(Config::default(), Readiness::default())
+    }
+}
 
 #[cfg(not(feature = "stub_server"))]
 #[tonic::async_trait]
 impl RpcService for ServerImpl {
-    /// Returns ready:true when service is available
+    /// Returns ready:true when service is available, ready:false when any
+    /// of its critical dependencies is down
     async fn is_ready(
         &self,
         request: Request<ReadyRequest>,
@@ -32,9 +71,180 @@ impl RpcService for ServerImpl {
         // the health checks.
         grpc_debug!("(is_ready) itest server.");
         grpc_debug!("(is_ready) [{:?}].", request);
-        let response = ReadyResponse { ready: true };
+        let response = ReadyResponse {
+            ready: self.readiness.is_ready(),
+        };
         Ok(Response::new(response))
     }
+
+    /// Probes every realm service found in the environment with the gRPC
+    /// health protocol, returns ready:true when all of them are serving
+    async fn realm_health(
+        &self,
+        request: Request<RealmHealthRequest>,
+    ) -> Result<Response<RealmHealthResponse>, Status> {
+        grpc_debug!("(realm_health) itest server.");
+        grpc_debug!("(realm_health) [{:?}].", request);
+        let timeout = std::time::Duration::from_millis(self.config.health_probe_timeout_ms);
+        let response = health::probe_realm(timeout).await;
+        Ok(Response::new(response.into()))
+    }
+
+    /// Returns the suites found in the configured suite directory
+    async fn list_suites(
+        &self,
+        request: Request<ListSuitesRequest>,
+    ) -> Result<Response<ListSuitesResponse>, Status> {
+        grpc_info!("(list_suites) itest server.");
+        grpc_debug!("(list_suites) [{:?}].", request);
+        let suites = runner::load_suites(&self.config).map_err(|e| {
+            grpc_error!("(list_suites) could not load suites: {}", e);
+            Status::failed_precondition(e.to_string())
+        })?;
+
+        Ok(Response::new(ListSuitesResponse {
+            suites: suites.iter().map(Into::into).collect(),
+        }))
+    }
+
+    type runSuiteStream = RunEventStream;
+
+    /// Starts a run of the requested suite and streams its events until
+    /// the run finished. The run completes even if the caller goes away.
+    async fn run_suite(
+        &self,
+        request: Request<RunSuiteRequest>,
+    ) -> Result<Response<Self::runSuiteStream>, Status> {
+        grpc_info!("(run_suite) itest server.");
+        grpc_debug!("(run_suite) [{:?}].", request);
+        let name = request.into_inner().suite;
+        let suite = runner::load_suites(&self.config)
+            .map_err(|e| {
+                grpc_error!("(run_suite) could not load suites: {}", e);
+                Status::failed_precondition(e.to_string())
+            })?
+            .into_iter()
+            .find(|suite| suite.name == name)
+            .ok_or_else(|| {
+                grpc_warn!("(run_suite) suite [{}] not found.", name);
+                Status::not_found(format!("suite [{}] not found", name))
+            })?;
+
+        let (config, grpc_clients) = (self.config.clone(), self.grpc_clients.clone());
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let result = runner::run(
+                &grpc_clients,
+                &config,
+                &suite,
+                Some(sender),
+                tokio_util::sync::CancellationToken::new(),
+            )
+            .await;
+            runner::history::save(&config, &suite, &result).await;
+        });
+
+        let events = stream::unfold(receiver, |mut receiver| async move {
+            let event = receiver.recv().await?;
+            Some((Ok(event.into()), receiver))
+        });
+        Ok(Response::new(events.boxed()))
+    }
+}
+
+impl From<&Suite> for SuiteInfo {
+    fn from(suite: &Suite) -> Self {
+        SuiteInfo {
+            name: suite.name.clone(),
+            description: suite.description.clone(),
+            steps: suite.steps.len() as u32,
+        }
+    }
+}
+
+/// Returns the name of a step status
+fn status_name(status: StepStatus) -> &'static str {
+    match status {
+        StepStatus::Passed => "passed",
+        StepStatus::Failed => "failed",
+        StepStatus::Skipped => "skipped",
+    }
+}
+
+impl From<runner::RunEvent> for RunEvent {
+    fn from(event: runner::RunEvent) -> Self {
+        let event = match event {
+            runner::RunEvent::Started { suite, run_id } => {
+                run_event::Event::Started(RunStarted { suite, run_id })
+            }
+            runner::RunEvent::StepStarted { index, name, kind } => {
+                run_event::Event::StepStarted(StepStarted {
+                    index: index as u32,
+                    name,
+                    kind,
+                })
+            }
+            runner::RunEvent::StepFinished { index, result } => {
+                run_event::Event::StepFinished(StepFinished {
+                    index: index as u32,
+                    name: result.name,
+                    kind: result.kind,
+                    status: status_name(result.status).to_string(),
+                    duration_ms: result.duration_ms,
+                    request: result.request.map(|request| request.to_string()),
+                    response: result.response.map(|response| response.to_string()),
+                    error: result.error,
+                })
+            }
+            runner::RunEvent::Log { level, message } => run_event::Event::Log(RunLog {
+                level: level.as_str().to_lowercase(),
+                message,
+            }),
+            runner::RunEvent::Finished(result) => {
+                let count = |status: StepStatus| {
+                    result.steps.iter().filter(|s| s.status == status).count() as u32
+                };
+                run_event::Event::Summary(RunSummary {
+                    passed_steps: count(StepStatus::Passed),
+                    failed_steps: count(StepStatus::Failed),
+                    skipped_steps: count(StepStatus::Skipped),
+                    suite: result.suite,
+                    run_id: result.run_id,
+                    passed: result.passed,
+                    duration_ms: result.duration_ms,
+                })
+            }
+        };
+
+        RunEvent { event: Some(event) }
+    }
+}
+
+impl From<rest_types::ServiceHealth> for ServiceHealth {
+    fn from(service: rest_types::ServiceHealth) -> Self {
+        let status = match service.status {
+            rest_types::DependencyStatus::Serving => "serving",
+            rest_types::DependencyStatus::NotServing => "not_serving",
+            rest_types::DependencyStatus::Timeout => "timeout",
+        };
+        ServiceHealth {
+            name: service.name,
+            host: service.host,
+            port: service.port as u32,
+            status: status.to_string(),
+            latency_ms: service.latency_ms,
+            error: service.error,
+        }
+    }
+}
+
+impl From<rest_types::RealmHealthResponse> for RealmHealthResponse {
+    fn from(response: rest_types::RealmHealthResponse) -> Self {
+        RealmHealthResponse {
+            ready: response.ready,
+            services: response.services.into_iter().map(Into::into).collect(),
+        }
+    }
 }
 
 /// Starts the grpc servers for this microservice using the provided configuration
@@ -62,11 +272,20 @@ pub async fn grpc_server(config: Config, shutdown_rx: Option<tokio::sync::onesho
         }
     };
 
-    let imp = ServerImpl::default();
+    // Not serving until the dependency watcher found the critical
+    // dependencies to be up
+    let readiness = Readiness::new(false);
+    let imp = ServerImpl::new(config.clone(), readiness.clone());
     let (mut health_reporter, health_service) = tonic_health::server::health_reporter();
     health_reporter
-        .set_serving::<RpcServiceServer<ServerImpl>>()
+        .set_not_serving::<RpcServiceServer<ServerImpl>>()
         .await;
+    let watcher = tokio::spawn(health::watch_dependencies(
+        imp.grpc_clients.clone(),
+        config,
+        readiness,
+        health_reporter,
+    ));
 
     //start server
     grpc_info!(
@@ -84,6 +303,8 @@ pub async fn grpc_server(config: Config, shutdown_rx: Option<tokio::sync::onesho
             grpc_error!("(grpc_server) Could not start gRPC server: {}", e);
         }
     };
+
+    watcher.abort();
 }
 
 #[cfg(feature = "stub_server")]
@@ -98,6 +319,58 @@ impl RpcService for ServerImpl {
         let response = ReadyResponse { ready: true };
         Ok(Response::new(response))
     }
+
+    async fn realm_health(
+        &self,
+        request: Request<RealmHealthRequest>,
+    ) -> Result<Response<RealmHealthResponse>, Status> {
+        grpc_warn!("(realm_health MOCK) itest server.");
+        grpc_debug!("(realm_health MOCK) [{:?}].", request);
+        let response = RealmHealthResponse {
+            ready: true,
+            services: vec![],
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn list_suites(
+        &self,
+        request: Request<ListSuitesRequest>,
+    ) -> Result<Response<ListSuitesResponse>, Status> {
+        grpc_warn!("(list_suites MOCK) itest server.");
+        grpc_debug!("(list_suites MOCK) [{:?}].", request);
+        let response = ListSuitesResponse {
+            suites: vec![SuiteInfo {
+                name: "mock".to_string(),
+                description: None,
+                steps: 1,
+            }],
+        };
+        Ok(Response::new(response))
+    }
+
+    type runSuiteStream = RunEventStream;
+
+    async fn run_suite(
+        &self,
+        request: Request<RunSuiteRequest>,
+    ) -> Result<Response<Self::runSuiteStream>, Status> {
+        grpc_warn!("(run_suite MOCK) itest server.");
+        grpc_debug!("(run_suite MOCK) [{:?}].", request);
+        let suite = request.into_inner().suite;
+        let summary = RunEvent {
+            event: Some(run_event::Event::Summary(RunSummary {
+                suite,
+                run_id: uuid::Uuid::new_v4().to_string(),
+                passed: true,
+                duration_ms: 0,
+                passed_steps: 1,
+                failed_steps: 0,
+                skipped_steps: 0,
+            })),
+        };
+        Ok(Response::new(stream::iter(vec![Ok(summary)]).boxed()))
+    }
 }
 
 #[cfg(test)]
@@ -117,4 +390,87 @@ mod tests {
 
         ut_info!("(test_grpc_server_is_ready) Success.");
     }
+
+    #[tokio::test]
+    async fn test_realm_health_conversion() {
+        crate::get_log_handle().await;
+        ut_info!("(test_realm_health_conversion) Start.");
+
+        let response: RealmHealthResponse = rest_types::RealmHealthResponse {
+            ready: false,
+            services: vec![rest_types::ServiceHealth {
+                name: "svc-gis".to_string(),
+                host: "svc-gis".to_string(),
+                port: 50051,
+                status: rest_types::DependencyStatus::NotServing,
+                latency_ms: 3,
+                error: Some("connection refused".to_string()),
+            }],
+        }
+        .into();
+        assert!(!response.ready);
+        assert_eq!(response.services[0].port, 50051);
+        assert_eq!(response.services[0].status, "not_serving");
+
+        ut_info!("(test_realm_health_conversion) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_run_event_conversion() {
+        crate::get_log_handle().await;
+        ut_info!("(test_run_event_conversion) Start.");
+
+        let result = runner::StepResult {
+            name: "read vertiport".to_string(),
+            kind: "call".to_string(),
+            status: StepStatus::Failed,
+            duration_ms: 4,
+            request: Some(serde_json::json!({ "id": "port" })),
+            response: None,
+            error: Some("Object not found: port".to_string()),
+            created: vec![],
+        };
+        let event: RunEvent = runner::RunEvent::StepFinished {
+            index: 2,
+            result: result.clone(),
+        }
+        .into();
+        let Some(run_event::Event::StepFinished(finished)) = event.event else {
+            panic!("expected a step finished event");
+        };
+        assert_eq!(finished.index, 2);
+        assert_eq!(finished.status, "failed");
+        assert_eq!(finished.request.as_deref(), Some(r#"{"id":"port"}"#));
+
+        let event: RunEvent = runner::RunEvent::Finished(runner::SuiteResult {
+            suite: "suite".to_string(),
+            run_id: "run".to_string(),
+            passed: false,
+            started_at: chrono::Utc::now(),
+            duration_ms: 10,
+            steps: vec![result.clone(), result],
+            teardown: None,
+        })
+        .into();
+        let Some(run_event::Event::Summary(summary)) = event.event else {
+            panic!("expected a summary event");
+        };
+        assert_eq!(summary.failed_steps, 2);
+        assert_eq!(summary.passed_steps, 0);
+
+        let event: RunEvent = runner::RunEvent::Log {
+            level: log::Level::Warn,
+            message: "step failed".to_string(),
+        }
+        .into();
+        assert_eq!(
+            event.event,
+            Some(run_event::Event::Log(RunLog {
+                level: "warn".to_string(),
+                message: "step failed".to_string(),
+            }))
+        );
+
+        ut_info!("(test_run_event_conversion) Success.");
+    }
 }
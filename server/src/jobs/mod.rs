@@ -0,0 +1,367 @@
+//! Background jobs for long-running seeds and suite runs
+//!
+//! Seeding hundreds of resources or running a suite can take longer than
+//! the proxies between a client and svc-itest allow a request to take.
+//! Jobs do the same work on the runtime of the REST server and report their
+//! state and progress through the [`Jobs`] registry, where clients poll or
+//! follow them.
+//!
+//! Cancelling a job rolls back the resources it created. On shutdown, all
+//! unfinished jobs are cancelled and awaited, see [`Jobs::shutdown`].
+
+#[macro_use]
+pub mod macros;
+pub mod work;
+
+use crate::rest::api::rest_types::{ErrorResponse, JobProgress, JobResponse, JobResult, JobStatus};
+use crate::seed::SeedError;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Number of finished jobs kept for their clients. The oldest finished
+/// jobs are removed once there are more.
+pub const MAX_FINISHED_JOBS: usize = 100;
+
+/// Returns true if the job will not change anymore
+pub fn is_finished(status: JobStatus) -> bool {
+    !matches!(status, JobStatus::Queued | JobStatus::Running)
+}
+
+/// A job in the registry
+struct Job {
+    /// The latest state of the job
+    state: Arc<watch::Sender<JobResponse>>,
+    /// Cancels the job
+    cancel: CancellationToken,
+    /// The task doing the work of the job
+    task: Option<JoinHandle<()>>,
+}
+
+/// Handle of a running job, used by its work to report progress
+#[derive(Clone)]
+pub struct JobHandle {
+    state: Arc<watch::Sender<JobResponse>>,
+    cancel: CancellationToken,
+}
+
+impl JobHandle {
+    /// Returns the ID of the job
+    pub fn id(&self) -> String {
+        self.state.borrow().id.clone()
+    }
+
+    /// Returns the token cancelling the job
+    pub fn cancel_token(&self) -> &CancellationToken {
+        &self.cancel
+    }
+
+    /// Completes once the job has been cancelled
+    pub async fn cancelled(&self) {
+        self.cancel.cancelled().await
+    }
+
+    /// Sets the amount of work of the job
+    pub fn total(&self, total: u64) {
+        self.state.send_modify(|job| job.progress.total = total);
+    }
+
+    /// Sets the amount of work done, and what the job is doing now
+    pub fn progress(&self, completed: u64, message: Option<String>) {
+        self.state.send_modify(|job| {
+            job.progress.completed = completed;
+            if message.is_some() {
+                job.progress.message = message;
+            }
+        });
+    }
+
+    /// Sets what the job is doing now
+    pub fn message(&self, message: impl Into<String>) {
+        let message = message.into();
+        self.state
+            .send_modify(|job| job.progress.message = Some(message));
+    }
+}
+
+/// Registry of the background jobs of the REST server
+#[derive(Clone, Default)]
+pub struct Jobs {
+    jobs: Arc<Mutex<HashMap<String, Job>>>,
+    shutdown: CancellationToken,
+}
+
+impl Jobs {
+    /// Locks the registry. Jobs only update their state through their
+    /// channel, so a registry poisoned by a panic is still consistent.
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Job>> {
+        match self.jobs.lock() {
+            Ok(jobs) => jobs,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Starts `work` as a job on the current runtime, returns the state of
+    /// the new job.
+    ///
+    /// The job succeeds if the work returns a result, unless it is the
+    /// result of a suite which did not pass. Jobs returning an error after
+    /// they have been cancelled end up cancelled.
+    pub fn spawn<F, Fut>(&self, kind: &str, work: F) -> JobResponse
+    where
+        F: FnOnce(JobHandle) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<JobResult, ErrorResponse>> + Send + 'static,
+    {
+        let id = Uuid::new_v4().to_string();
+        let initial = JobResponse {
+            id: id.clone(),
+            kind: kind.to_string(),
+            status: JobStatus::Queued,
+            progress: JobProgress::default(),
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            result: None,
+            error: None,
+        };
+        let (state, _) = watch::channel(initial.clone());
+        let handle = JobHandle {
+            state: Arc::new(state),
+            cancel: self.shutdown.child_token(),
+        };
+
+        let mut jobs = self.lock();
+        prune(&mut jobs);
+        jobs.insert(
+            id.clone(),
+            Job {
+                state: handle.state.clone(),
+                cancel: handle.cancel.clone(),
+                task: None,
+            },
+        );
+
+        jobs_info!("(spawn) starting {} job [{}].", kind, id);
+        let task = tokio::spawn(async move {
+            handle.state.send_modify(|job| {
+                job.status = JobStatus::Running;
+                job.started_at = Some(Utc::now());
+            });
+
+            let outcome = work(handle.clone()).await;
+            let status = match &outcome {
+                Ok(JobResult::Suite(result)) if !result.passed => JobStatus::Failed,
+                Ok(_) => JobStatus::Succeeded,
+                Err(_) if handle.cancel.is_cancelled() => JobStatus::Cancelled,
+                Err(_) => JobStatus::Failed,
+            };
+
+            handle.state.send_modify(|job| {
+                jobs_info!("(spawn) {} job [{}] {:?}.", job.kind, job.id, status);
+                job.status = status;
+                job.finished_at = Some(Utc::now());
+                match outcome {
+                    Ok(result) => job.result = Some(result),
+                    Err(error) => job.error = Some(error),
+                }
+            });
+        });
+
+        if let Some(job) = jobs.get_mut(&id) {
+            job.task = Some(task);
+        }
+
+        initial
+    }
+
+    /// Returns the latest state of a job
+    pub fn get(&self, id: &str) -> Option<JobResponse> {
+        self.lock().get(id).map(|job| job.state.borrow().clone())
+    }
+
+    /// Returns a receiver of the state changes of a job
+    pub fn subscribe(&self, id: &str) -> Option<watch::Receiver<JobResponse>> {
+        self.lock().get(id).map(|job| job.state.subscribe())
+    }
+
+    /// Cancels an unfinished job, returns its state. The job finishes
+    /// once its changes have been rolled back.
+    pub fn cancel(&self, id: &str) -> Result<JobResponse, SeedError> {
+        let jobs = self.lock();
+        let job = jobs
+            .get(id)
+            .ok_or_else(|| SeedError::NotFound(format!("job [{}]", id)))?;
+
+        let state = job.state.borrow().clone();
+        if is_finished(state.status) {
+            return Err(SeedError::Conflict(format!(
+                "job [{}] already finished",
+                id
+            )));
+        }
+
+        jobs_info!("(cancel) cancelling job [{}].", id);
+        job.cancel.cancel();
+        Ok(state)
+    }
+
+    /// Cancels all unfinished jobs and waits until they finished
+    pub async fn shutdown(&self) {
+        self.shutdown.cancel();
+
+        let tasks: Vec<JoinHandle<()>> = self
+            .lock()
+            .values_mut()
+            .filter_map(|job| job.task.take())
+            .collect();
+
+        jobs_info!("(shutdown) waiting for {} jobs.", tasks.len());
+        for task in tasks {
+            if let Err(e) = task.await {
+                jobs_error!("(shutdown) job ended abnormally: {}.", e);
+            }
+        }
+    }
+}
+
+/// Removes the oldest finished jobs, keeping at most [`MAX_FINISHED_JOBS`]
+fn prune(jobs: &mut HashMap<String, Job>) {
+    let mut finished: Vec<(chrono::DateTime<Utc>, String)> = jobs
+        .iter()
+        .filter_map(|(id, job)| {
+            let state = job.state.borrow();
+            match is_finished(state.status) {
+                true => Some((state.finished_at.unwrap_or(state.created_at), id.clone())),
+                false => None,
+            }
+        })
+        .collect();
+
+    if finished.len() < MAX_FINISHED_JOBS {
+        return;
+    }
+
+    finished.sort();
+    for (_, id) in &finished[..=finished.len() - MAX_FINISHED_JOBS] {
+        jobs.remove(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::api::rest_types::ScenarioResponse;
+    use std::collections::BTreeMap;
+
+    /// Waits until the job finished, returns its final state
+    async fn finished(jobs: &Jobs, id: &str) -> JobResponse {
+        let mut receiver = jobs.subscribe(id).unwrap();
+        loop {
+            let state = receiver.borrow_and_update().clone();
+            if is_finished(state.status) {
+                return state;
+            }
+            receiver.changed().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawn() {
+        crate::get_log_handle().await;
+        ut_info!("(test_spawn) Start.");
+
+        let jobs = Jobs::default();
+        let job = jobs.spawn("scenario", |handle| async move {
+            handle.total(2);
+            handle.progress(1, Some("creating".to_string()));
+            handle.progress(2, None);
+            Ok(JobResult::Seed(ScenarioResponse {
+                ids: BTreeMap::from([("port_a".to_string(), "1".to_string())]),
+            }))
+        });
+        assert_eq!(job.status, JobStatus::Queued);
+
+        let job = finished(&jobs, &job.id).await;
+        assert_eq!(job.status, JobStatus::Succeeded);
+        assert!(job.started_at.is_some() && job.finished_at.is_some());
+        assert_eq!(
+            job.progress,
+            JobProgress {
+                completed: 2,
+                total: 2,
+                message: Some("creating".to_string())
+            }
+        );
+        assert!(matches!(job.result, Some(JobResult::Seed(_))));
+        assert!(matches!(jobs.cancel(&job.id), Err(SeedError::Conflict(_))));
+        assert!(matches!(
+            jobs.cancel("unknown"),
+            Err(SeedError::NotFound(_))
+        ));
+
+        ut_info!("(test_spawn) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_cancel() {
+        crate::get_log_handle().await;
+        ut_info!("(test_cancel) Start.");
+
+        let jobs = Jobs::default();
+        let job = jobs.spawn("realm", |handle| async move {
+            handle.cancelled().await;
+            Err(crate::seed::SeedFailure::from(SeedError::Cancelled).into())
+        });
+        assert_eq!(jobs.cancel(&job.id).unwrap().id, job.id);
+
+        let job = finished(&jobs, &job.id).await;
+        assert_eq!(job.status, JobStatus::Cancelled);
+        assert_eq!(job.error.unwrap().error, "Request cancelled.");
+
+        // Shutdown cancels and awaits the unfinished jobs
+        let job = jobs.spawn("realm", |handle| async move {
+            handle.cancelled().await;
+            Err(crate::seed::SeedFailure::from(SeedError::Cancelled).into())
+        });
+        jobs.shutdown().await;
+        assert_eq!(jobs.get(&job.id).unwrap().status, JobStatus::Cancelled);
+
+        ut_info!("(test_cancel) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_prune() {
+        crate::get_log_handle().await;
+        ut_info!("(test_prune) Start.");
+
+        let jobs = Jobs::default();
+        let mut ids = vec![];
+        for _ in 0..MAX_FINISHED_JOBS + 1 {
+            let job = jobs.spawn("scenario", |_| async {
+                Ok(JobResult::Seed(ScenarioResponse {
+                    ids: BTreeMap::new(),
+                }))
+            });
+            finished(&jobs, &job.id).await;
+            ids.push(job.id);
+        }
+
+        // Spawning another job removes the oldest finished ones
+        let job = jobs.spawn("scenario", |handle| async move {
+            handle.cancelled().await;
+            Err(crate::seed::SeedFailure::from(SeedError::Cancelled).into())
+        });
+        assert!(jobs.get(&ids[0]).is_none());
+        assert!(jobs.get(&ids[1]).is_none());
+        assert!(jobs.get(&ids[2]).is_some());
+        assert!(jobs.get(&job.id).is_some());
+        jobs.shutdown().await;
+
+        ut_info!("(test_prune) Success.");
+    }
+}
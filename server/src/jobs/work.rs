@@ -0,0 +1,250 @@
+//! The work of the jobs of a [`JobRequest`]
+
+use super::{JobHandle, Jobs};
+use crate::grpc::client::GrpcClients;
+use crate::grpc::gate;
+use crate::rest::api::rest_types::{
+    ErrorResponse, JobRequest, JobResponse, JobResult, Scenario, ScenarioResponse, StepStatus,
+};
+use crate::runner::{self, history, suite::Suite, RunEvent};
+use crate::seed::{self, Saga, SeedError};
+use crate::Config;
+use std::time::Duration;
+
+/// Returns the number of resources of a scenario
+fn count(scenario: &Scenario) -> usize {
+    scenario.vertiports.len()
+        + scenario.vertipads.len()
+        + scenario.aircraft.len()
+        + scenario.users.len()
+        + scenario.scanners.len()
+}
+
+/// Validates the request and starts its job. Seed jobs create their
+/// resources for `run_id`, if provided. All jobs first wait up to
+/// `wait_timeout` for the critical dependencies, if provided.
+pub fn start(
+    jobs: &Jobs,
+    grpc_clients: GrpcClients,
+    config: Config,
+    request: JobRequest,
+    run_id: Option<String>,
+    wait_timeout: Option<Duration>,
+) -> Result<JobResponse, SeedError> {
+    match request {
+        JobRequest::Scenario { scenario } => {
+            seed::scenario::validate(&scenario)?;
+            Ok(jobs.spawn("scenario", move |job| {
+                apply(grpc_clients, config, job, scenario, run_id, wait_timeout)
+            }))
+        }
+        JobRequest::Realm { realm } => {
+            let scenario = seed::generator::generate(&realm)?;
+            Ok(jobs.spawn("realm", move |job| {
+                apply(grpc_clients, config, job, scenario, run_id, wait_timeout)
+            }))
+        }
+        JobRequest::Suite { suite } => {
+            let suite = runner::load_suites(&config)?
+                .into_iter()
+                .find(|s| s.name == suite)
+                .ok_or_else(|| SeedError::NotFound(format!("suite [{}]", suite)))?;
+            Ok(jobs.spawn("suite", move |job| {
+                run(grpc_clients, config, job, suite, wait_timeout)
+            }))
+        }
+    }
+}
+
+/// Waits for the critical dependencies, unless the job is cancelled first
+async fn wait_for_dependencies(
+    grpc_clients: &GrpcClients,
+    config: &Config,
+    job: &JobHandle,
+    timeout: Option<Duration>,
+) -> Result<(), SeedError> {
+    let Some(timeout) = timeout else {
+        return Ok(());
+    };
+
+    job.message("waiting for dependencies");
+    tokio::select! {
+        result = gate::wait_for_dependencies(grpc_clients, config, timeout) => result?,
+        _ = job.cancelled() => return Err(SeedError::Cancelled),
+    };
+
+    Ok(())
+}
+
+/// Creates the resources of a scenario. Cancelling the job stops the
+/// creation and rolls back the resources created so far.
+async fn apply(
+    grpc_clients: GrpcClients,
+    config: Config,
+    job: JobHandle,
+    scenario: Scenario,
+    run_id: Option<String>,
+    wait_timeout: Option<Duration>,
+) -> Result<JobResult, ErrorResponse> {
+    job.total(count(&scenario) as u64);
+    wait_for_dependencies(&grpc_clients, &config, &job, wait_timeout)
+        .await
+        .map_err(|e| ErrorResponse::from(seed::SeedFailure::from(e)))?;
+
+    job.message("creating resources");
+    let progress = job.clone();
+    let mut saga =
+        Saga::for_run(run_id).with_progress(move |created| progress.progress(created as u64, None));
+    let result = tokio::select! {
+        result = seed::scenario::apply(&grpc_clients, &scenario, &mut saga) => result,
+        _ = job.cancelled() => Err(SeedError::Cancelled),
+    };
+
+    let ids = saga.finish(result).await.map_err(|e| {
+        jobs_error!("(apply) job [{}] failed: {}.", job.id(), e);
+        ErrorResponse::from(e)
+    })?;
+
+    Ok(JobResult::Seed(ScenarioResponse { ids }))
+}
+
+/// Runs a suite, following its steps as progress. The result is recorded
+/// in the run history.
+async fn run(
+    grpc_clients: GrpcClients,
+    config: Config,
+    job: JobHandle,
+    suite: Suite,
+    wait_timeout: Option<Duration>,
+) -> Result<JobResult, ErrorResponse> {
+    job.total(suite.steps.len() as u64);
+    wait_for_dependencies(&grpc_clients, &config, &job, wait_timeout)
+        .await
+        .map_err(|e| ErrorResponse::from(seed::SeedFailure::from(e)))?;
+
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let follow = async {
+        while let Some(event) = receiver.recv().await {
+            match event {
+                RunEvent::StepStarted { index, name, .. } => {
+                    job.progress(index as u64, Some(format!("running step [{}]", name)))
+                }
+                RunEvent::StepFinished { index, result } => {
+                    let status = match result.status {
+                        StepStatus::Passed => "passed",
+                        StepStatus::Failed => "failed",
+                        StepStatus::Skipped => "skipped",
+                    };
+                    job.progress(
+                        index as u64 + 1,
+                        Some(format!("step [{}] {}", result.name, status)),
+                    )
+                }
+                RunEvent::Log { message, .. } => job.message(message),
+                RunEvent::Started { .. } | RunEvent::Finished(_) => {}
+            }
+        }
+    };
+
+    let (result, _) = tokio::join!(
+        runner::run(
+            &grpc_clients,
+            &config,
+            &suite,
+            Some(sender),
+            job.cancel_token().clone(),
+        ),
+        follow
+    );
+
+    history::save(&config, &suite, &result).await;
+    Ok(JobResult::Suite(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::api::rest_types::{GenerateRealmRequest, JobStatus};
+
+    #[tokio::test]
+    async fn test_start() {
+        crate::get_log_handle().await;
+        ut_info!("(test_start) Start.");
+
+        let dir = std::env::temp_dir().join(format!("test_start_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("pause.yaml"),
+            "name: pause\nsteps:\n  - name: pause\n    wait:\n      duration_ms: 1\n",
+        )
+        .unwrap();
+
+        let config = Config {
+            runner_suite_dir: dir.display().to_string(),
+            runner_history_db: dir.join("history.db").display().to_string(),
+            ..Config::default()
+        };
+        let grpc_clients = GrpcClients::default(config.clone());
+        let jobs = Jobs::default();
+
+        let start_job = |request| {
+            start(
+                &jobs,
+                grpc_clients.clone(),
+                config.clone(),
+                request,
+                None,
+                None,
+            )
+        };
+
+        assert!(matches!(
+            start_job(JobRequest::Suite {
+                suite: "unknown".to_string()
+            }),
+            Err(SeedError::NotFound(_))
+        ));
+        let realm: GenerateRealmRequest = serde_json::from_value(serde_json::json!({
+            "seed": 1,
+            "bounding_box": { "min_latitude": 1.0, "min_longitude": 1.0, "max_latitude": 0.0, "max_longitude": 0.0 },
+            "vertiports": 1,
+            "min_pads_per_vertiport": 1,
+            "max_pads_per_vertiport": 1,
+            "aircraft": 0,
+            "users": 0
+        }))
+        .unwrap();
+        assert!(start_job(JobRequest::Realm { realm }).is_err());
+
+        let job = start_job(JobRequest::Suite {
+            suite: "pause".to_string(),
+        })
+        .unwrap();
+        assert_eq!(job.kind, "suite");
+
+        let mut receiver = jobs.subscribe(&job.id).unwrap();
+        let job = loop {
+            let job = receiver.borrow_and_update().clone();
+            if crate::jobs::is_finished(job.status) {
+                break job;
+            }
+            receiver.changed().await.unwrap();
+        };
+        assert_eq!(job.status, JobStatus::Succeeded);
+        assert_eq!(job.progress.completed, 1);
+        assert_eq!(job.progress.total, 1);
+        assert!(matches!(job.result, Some(JobResult::Suite(ref result)) if result.passed));
+
+        // The run is recorded in the history
+        let runs = history::query(&config, |connection| {
+            history::list_runs(connection, &Default::default())
+        })
+        .await
+        .unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].suite, "pause");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        ut_info!("(test_start) Success.");
+    }
+}
@@ -8,6 +8,9 @@ pub mod test_util;
 
 pub mod config;
 pub mod grpc;
+pub mod jobs;
+pub mod runner;
+pub mod seed;
 
 pub use crate::config::Config;
 
@@ -25,6 +28,36 @@ pub struct Cli {
     /// Target file to write the OpenAPI Spec
     #[arg(long)]
     pub openapi: Option<String>,
+
+    /// Wait for the critical dependencies before starting the servers
+    #[arg(long)]
+    pub wait_for_dependencies: bool,
+
+    /// Only wait for the critical dependencies, then exit. Exits with an
+    /// error if a dependency did not come up in time.
+    #[arg(long)]
+    pub wait_only: bool,
+
+    /// Time to wait for the critical dependencies in milliseconds,
+    /// overrides the configured readiness gate timeout
+    #[arg(long)]
+    pub wait_timeout_ms: Option<u64>,
+
+    /// Run the suite with this name from the configured suite directory,
+    /// then exit. May be repeated, `all` runs all suites. Exits with an
+    /// error if a suite failed.
+    #[arg(long, value_name = "NAME")]
+    pub run_suite: Vec<String>,
+
+    /// Directory to write the reports of the suites run with `--run-suite`
+    /// to
+    #[arg(long, value_name = "DIR")]
+    pub report_dir: Option<String>,
+
+    /// Formats of the reports written to the report directory: junit, tap
+    /// or json, separated by commas
+    #[arg(long, value_delimiter = ',', default_value = "junit,tap,json")]
+    pub report_format: Vec<rest::api::rest_types::ReportFormat>,
 }
 
 // --------------------------------------------------
@@ -16,6 +16,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     load_logger_config_from_file(config.log_config.as_str()).await?;
 
     info!("(main) Server startup.");
+
+    // Add the vehicle models of the provided file to the built in catalog
+    if let Some(path) = &config.vehicle_model_catalog {
+        seed::vehicle_model::load_file(path)?;
+    }
     // --------------------------------------------------
     // START REST SECTION
     // This section should be removed if there is no REST interface
@@ -29,12 +34,57 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         return rest::generate_openapi_spec(&target);
     }
 
-    tokio::spawn(rest::server::rest_server(config.clone(), None));
+    // Hold off until the critical dependencies are up, so seeding and test
+    // runs don't fail on services which are still starting
+    if args.wait_for_dependencies || args.wait_only {
+        let timeout = std::time::Duration::from_millis(
+            args.wait_timeout_ms
+                .unwrap_or(config.readiness_gate_timeout_ms),
+        );
+        let grpc_clients = grpc::client::GrpcClients::default(config.clone());
+        grpc::gate::wait_for_dependencies(&grpc_clients, &config, timeout).await?;
+        if args.wait_only {
+            return Ok(());
+        }
+    }
+
+    // Run suites instead of starting the servers, writing their reports
+    if !args.run_suite.is_empty() {
+        let grpc_clients = grpc::client::GrpcClients::default(config.clone());
+        let mut failed = vec![];
+        for suite in runner::select_suites(&config, &args.run_suite)? {
+            let result =
+                runner::run(&grpc_clients, &config, &suite, None, Default::default()).await;
+            runner::history::save(&config, &suite, &result).await;
+            if let Some(dir) = &args.report_dir {
+                runner::report::write(dir, &result, &args.report_format)?;
+            }
+            if !result.passed {
+                failed.push(result.suite);
+            }
+        }
+
+        log::logger().flush();
+        return match failed.is_empty() {
+            true => Ok(()),
+            false => Err(format!("suites failed: {}", failed.join(", ")).into()),
+        };
+    }
+
+    let rest_server = tokio::spawn(rest::server::rest_server(config.clone(), None));
     // --------------------------------------------------
     // END REST SECTION
     // --------------------------------------------------
 
-    tokio::spawn(grpc::server::grpc_server(config, None)).await?;
+    let grpc_server = tokio::spawn(grpc::server::grpc_server(config, None));
+
+    // Await both servers, the REST server only stops once its unfinished
+    // jobs have been rolled back
+    let (rest_result, grpc_result) = tokio::join!(rest_server, grpc_server);
+    grpc_result?;
+    if rest_result?.is_err() {
+        log::error!("(main) REST server failed.");
+    }
 
     info!("(main) Server shutdown.");
 
@@ -0,0 +1,271 @@
+//! Demo REST API implementations, creating many realm resources at once
+
+use super::{get_run_id, rest_types::*, seed_error_response};
+use crate::grpc::client::GrpcClients;
+use crate::seed::{self, bulk, Saga, SeedError};
+use crate::Config;
+use axum::{
+    extract::{Extension, Query},
+    Json,
+};
+use futures::future::{BoxFuture, FutureExt, TryFutureExt};
+use hyper::{HeaderMap, StatusCode};
+
+/// Seeds each item of a bulk request with `seed`, in the saga of the run
+/// the item is created for
+///
+/// `seed` receives whether existing resources should be updated in place.
+async fn seed_all<T, F>(
+    grpc_clients: &GrpcClients,
+    config: &Config,
+    params: &BulkParams,
+    headers: &HeaderMap,
+    payload: Vec<T>,
+    run_id: fn(&T) -> Option<&str>,
+    seed: F,
+) -> BulkResponse
+where
+    F: for<'a> Fn(
+        &'a GrpcClients,
+        &'a T,
+        bool,
+        &'a mut Saga,
+    ) -> BoxFuture<'a, Result<String, SeedError>>,
+{
+    let upsert = params.upsert.unwrap_or_default();
+    let concurrency = bulk::concurrency(params.concurrency, config.rest_bulk_concurrency_limit);
+    bulk::apply(payload, concurrency, |request| {
+        let grpc_clients = grpc_clients.clone();
+        let run_id = get_run_id(headers, run_id(&request));
+        let seed = &seed;
+        async move {
+            let mut saga = Saga::for_run(run_id?);
+            let result = seed(&grpc_clients, &request, upsert, &mut saga).await;
+            saga.finish(result).await
+        }
+    })
+    .await
+}
+
+/// Add vertiports to storage and GIS
+///
+/// Each item is seeded on its own, failing items don't affect the others.
+#[utoipa::path(
+    put,
+    path = "/demo/bulk/vertiport",
+    tag = "svc-itest",
+    params(
+        BulkParams,
+        ("x-run-id" = Option<String>, Header, description = "The run the resources are created for")
+    ),
+    request_body = [AddVertiportRequest],
+    responses(
+        (status = 200, description = "Request processed, see the result of each item.", body = BulkResponse),
+    )
+)]
+pub async fn add_vertiports(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(config): Extension<Config>,
+    Query(params): Query<BulkParams>,
+    headers: HeaderMap,
+    Json(payload): Json<Vec<AddVertiportRequest>>,
+) -> Json<BulkResponse> {
+    rest_debug!("(add_vertiports) entry [{} items].", payload.len());
+
+    let response = seed_all(
+        &grpc_clients,
+        &config,
+        &params,
+        &headers,
+        payload,
+        |request| request.run_id.as_deref(),
+        |grpc_clients, request, upsert, saga| match upsert {
+            true => seed::upsert::upsert_vertiport(grpc_clients, request, saga)
+                .map_ok(|response| response.id)
+                .boxed(),
+            false => seed::create_vertiport(grpc_clients, request, saga).boxed(),
+        },
+    )
+    .await;
+
+    Json(response)
+}
+
+/// Add vertipads to storage and GIS
+///
+/// Each item is seeded on its own, failing items don't affect the others.
+#[utoipa::path(
+    put,
+    path = "/demo/bulk/vertipad",
+    tag = "svc-itest",
+    params(
+        BulkParams,
+        ("x-run-id" = Option<String>, Header, description = "The run the resources are created for")
+    ),
+    request_body = [AddVertipadRequest],
+    responses(
+        (status = 200, description = "Request processed, see the result of each item.", body = BulkResponse),
+    )
+)]
+pub async fn add_vertipads(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(config): Extension<Config>,
+    Query(params): Query<BulkParams>,
+    headers: HeaderMap,
+    Json(payload): Json<Vec<AddVertipadRequest>>,
+) -> Json<BulkResponse> {
+    rest_debug!("(add_vertipads) entry [{} items].", payload.len());
+
+    let response = seed_all(
+        &grpc_clients,
+        &config,
+        &params,
+        &headers,
+        payload,
+        |request| request.run_id.as_deref(),
+        |grpc_clients, request, upsert, saga| match upsert {
+            true => seed::upsert::upsert_vertipad(grpc_clients, request, saga)
+                .map_ok(|response| response.id)
+                .boxed(),
+            false => seed::create_vertipad(grpc_clients, request, saga).boxed(),
+        },
+    )
+    .await;
+
+    Json(response)
+}
+
+/// Add aircraft to storage
+///
+/// Each item is seeded on its own, failing items don't affect the others.
+#[utoipa::path(
+    put,
+    path = "/demo/bulk/aircraft",
+    tag = "svc-itest",
+    params(
+        BulkParams,
+        ("x-run-id" = Option<String>, Header, description = "The run the resources are created for")
+    ),
+    request_body = [AddAircraftRequest],
+    responses(
+        (status = 200, description = "Request processed, see the result of each item.", body = BulkResponse),
+    )
+)]
+pub async fn add_aircraft_bulk(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(config): Extension<Config>,
+    Query(params): Query<BulkParams>,
+    headers: HeaderMap,
+    Json(payload): Json<Vec<AddAircraftRequest>>,
+) -> Json<BulkResponse> {
+    rest_debug!("(add_aircraft_bulk) entry [{} items].", payload.len());
+
+    let response = seed_all(
+        &grpc_clients,
+        &config,
+        &params,
+        &headers,
+        payload,
+        |request| request.run_id.as_deref(),
+        |grpc_clients, request, upsert, saga| match upsert {
+            true => seed::upsert::upsert_aircraft(grpc_clients, request, saga)
+                .map_ok(|response| response.id)
+                .boxed(),
+            false => seed::create_aircraft(grpc_clients, request, saga).boxed(),
+        },
+    )
+    .await;
+
+    Json(response)
+}
+
+/// Add users to storage
+///
+/// Each item is seeded on its own, failing items don't affect the others.
+#[utoipa::path(
+    put,
+    path = "/demo/bulk/user",
+    tag = "svc-itest",
+    params(
+        BulkParams,
+        ("x-run-id" = Option<String>, Header, description = "The run the resources are created for")
+    ),
+    request_body = [AddUserRequest],
+    responses(
+        (status = 200, description = "Request processed, see the result of each item.", body = BulkResponse),
+    )
+)]
+pub async fn add_users(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(config): Extension<Config>,
+    Query(params): Query<BulkParams>,
+    headers: HeaderMap,
+    Json(payload): Json<Vec<AddUserRequest>>,
+) -> Json<BulkResponse> {
+    rest_debug!("(add_users) entry [{} items].", payload.len());
+
+    let response = seed_all(
+        &grpc_clients,
+        &config,
+        &params,
+        &headers,
+        payload,
+        |request| request.run_id.as_deref(),
+        |grpc_clients, request, upsert, saga| match upsert {
+            true => seed::upsert::upsert_user(grpc_clients, request, saga)
+                .map_ok(|response| response.id)
+                .boxed(),
+            false => seed::create_user(grpc_clients, request, saga).boxed(),
+        },
+    )
+    .await;
+
+    Json(response)
+}
+
+/// Add scanners to storage
+///
+/// Each item is seeded on its own, failing items don't affect the others.
+#[utoipa::path(
+    put,
+    path = "/demo/bulk/scanner",
+    tag = "svc-itest",
+    params(
+        BulkParams,
+        ("x-run-id" = Option<String>, Header, description = "The run the resources are created for")
+    ),
+    request_body = [AddScannerRequest],
+    responses(
+        (status = 200, description = "Request processed, see the result of each item.", body = BulkResponse),
+        (status = 400, description = "Invalid request.", body = ErrorResponse),
+    )
+)]
+pub async fn add_scanners(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(config): Extension<Config>,
+    Query(params): Query<BulkParams>,
+    headers: HeaderMap,
+    Json(payload): Json<Vec<AddScannerRequest>>,
+) -> Result<Json<BulkResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(add_scanners) entry [{} items].", payload.len());
+
+    if params.upsert.unwrap_or_default() {
+        let error =
+            SeedError::InvalidRequest("scanners have no natural key to upsert by".to_string());
+        rest_error!("(add_scanners) Error: {}.", error);
+        return Err(seed_error_response(error));
+    }
+
+    let response = seed_all(
+        &grpc_clients,
+        &config,
+        &params,
+        &headers,
+        payload,
+        |request| request.run_id.as_deref(),
+        |grpc_clients, request, _, saga| seed::create_scanner(grpc_clients, request, saga).boxed(),
+    )
+    .await;
+
+    Ok(Json(response))
+}
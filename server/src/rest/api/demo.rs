@@ -0,0 +1,202 @@
+//! Demo REST API implementations, creating single realm resources
+
+use super::{get_run_id, rest_types::*, seed_error_response};
+use crate::grpc::client::GrpcClients;
+use crate::seed::{self, Saga};
+use axum::{extract::Extension, Json};
+use hyper::{HeaderMap, StatusCode};
+
+/// Add a vertiport to storage and GIS
+#[utoipa::path(
+    put,
+    path = "/demo/vertiport",
+    tag = "svc-itest",
+    params(
+        ("x-run-id" = Option<String>, Header, description = "The run the resource is created for")
+    ),
+    request_body = AddVertiportRequest,
+    responses(
+        (status = 200, description = "Request successful.", body = String),
+        (status = 400, description = "Invalid request.", body = ErrorResponse),
+        (status = 409, description = "Request conflicts with the current state of the realm.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful, changes have been rolled back.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn add_vertiport(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    headers: HeaderMap,
+    Json(payload): Json<AddVertiportRequest>,
+) -> Result<Json<String>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(add_vertiport) entry.");
+
+    let run_id = get_run_id(&headers, payload.run_id.as_deref()).map_err(|e| {
+        rest_error!("(add_vertiport) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    let mut saga = Saga::for_run(run_id);
+    let result = seed::create_vertiport(&grpc_clients, &payload, &mut saga).await;
+    let vertiport_id = saga.finish(result).await.map_err(|e| {
+        rest_error!("(add_vertiport) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(vertiport_id))
+}
+
+/// Add a vertipad to storage and GIS
+#[utoipa::path(
+    put,
+    path = "/demo/vertipad",
+    tag = "svc-itest",
+    params(
+        ("x-run-id" = Option<String>, Header, description = "The run the resource is created for")
+    ),
+    request_body = AddVertipadRequest,
+    responses(
+        (status = 200, description = "Request successful.", body = String),
+        (status = 400, description = "Invalid request.", body = ErrorResponse),
+        (status = 409, description = "Request conflicts with the current state of the realm.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful, changes have been rolled back.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn add_vertipad(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    headers: HeaderMap,
+    Json(payload): Json<AddVertipadRequest>,
+) -> Result<Json<String>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(add_vertipad) entry.");
+
+    let run_id = get_run_id(&headers, payload.run_id.as_deref()).map_err(|e| {
+        rest_error!("(add_vertipad) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    let mut saga = Saga::for_run(run_id);
+    let result = seed::create_vertipad(&grpc_clients, &payload, &mut saga).await;
+    let vertipad_id = saga.finish(result).await.map_err(|e| {
+        rest_error!("(add_vertipad) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(vertipad_id))
+}
+
+/// Add aircraft to storage
+#[utoipa::path(
+    put,
+    path = "/demo/aircraft",
+    tag = "svc-itest",
+    params(
+        ("x-run-id" = Option<String>, Header, description = "The run the resource is created for")
+    ),
+    request_body = AddAircraftRequest,
+    responses(
+        (status = 200, description = "Request successful.", body = String),
+        (status = 400, description = "Invalid request.", body = ErrorResponse),
+        (status = 409, description = "Request conflicts with the current state of the realm.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful, changes have been rolled back.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn add_aircraft(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    headers: HeaderMap,
+    Json(payload): Json<AddAircraftRequest>,
+) -> Result<Json<String>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(add_aircraft) entry.");
+
+    let run_id = get_run_id(&headers, payload.run_id.as_deref()).map_err(|e| {
+        rest_error!("(add_aircraft) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    let mut saga = Saga::for_run(run_id);
+    let result = seed::create_aircraft(&grpc_clients, &payload, &mut saga).await;
+    let aircraft_id = saga.finish(result).await.map_err(|e| {
+        rest_error!("(add_aircraft) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(aircraft_id))
+}
+
+/// Add user to storage
+#[utoipa::path(
+    put,
+    path = "/demo/user",
+    tag = "svc-itest",
+    params(
+        ("x-run-id" = Option<String>, Header, description = "The run the resource is created for")
+    ),
+    request_body = AddUserRequest,
+    responses(
+        (status = 200, description = "Request successful.", body = String),
+        (status = 400, description = "Invalid request.", body = ErrorResponse),
+        (status = 409, description = "Request conflicts with the current state of the realm.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful, changes have been rolled back.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn add_user(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    headers: HeaderMap,
+    Json(payload): Json<AddUserRequest>,
+) -> Result<Json<String>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(add_user) entry.");
+
+    let run_id = get_run_id(&headers, payload.run_id.as_deref()).map_err(|e| {
+        rest_error!("(add_user) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    let mut saga = Saga::for_run(run_id);
+    let result = seed::create_user(&grpc_clients, &payload, &mut saga).await;
+    let user_id = saga.finish(result).await.map_err(|e| {
+        rest_error!("(add_user) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(user_id))
+}
+
+/// Add scanner to storage
+#[utoipa::path(
+    put,
+    path = "/demo/scanner",
+    tag = "svc-itest",
+    params(
+        ("x-run-id" = Option<String>, Header, description = "The run the resource is created for")
+    ),
+    request_body = AddScannerRequest,
+    responses(
+        (status = 200, description = "Request successful.", body = String),
+        (status = 400, description = "Invalid request.", body = ErrorResponse),
+        (status = 409, description = "Request conflicts with the current state of the realm.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful, changes have been rolled back.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn add_scanner(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    headers: HeaderMap,
+    Json(payload): Json<AddScannerRequest>,
+) -> Result<Json<String>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(add_scanner) entry.");
+
+    let run_id = get_run_id(&headers, payload.run_id.as_deref()).map_err(|e| {
+        rest_error!("(add_scanner) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    let mut saga = Saga::for_run(run_id);
+    let result = seed::create_scanner(&grpc_clients, &payload, &mut saga).await;
+    let scanner_id = saga.finish(result).await.map_err(|e| {
+        rest_error!("(add_scanner) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(scanner_id))
+}
@@ -0,0 +1,105 @@
+//! Demo REST API implementations, exchanging realm resources as GeoJSON
+//! and KML
+
+use super::{get_run_id, rest_types::*, seed_error_response};
+use crate::grpc::client::GrpcClients;
+use crate::seed::{export, geojson};
+use axum::{
+    extract::{Extension, Query},
+    response::{IntoResponse, Response},
+    Json,
+};
+use hyper::{header::CONTENT_TYPE, HeaderMap, StatusCode};
+
+/// Import vertiports and vertipads from a GeoJSON feature collection
+///
+/// Polygon features become vertiports, Point features become vertipads of
+/// the imported vertiport containing them. Each feature is seeded on its
+/// own, rejected features don't affect the others.
+#[utoipa::path(
+    put,
+    path = "/demo/geojson",
+    tag = "svc-itest",
+    params(
+        ("x-run-id" = Option<String>, Header, description = "The run the resources are created for")
+    ),
+    request_body(content = GeoJsonFeatureCollection, content_type = "application/geo+json"),
+    responses(
+        (status = 200, description = "Request processed, see the result of each feature.", body = BulkResponse),
+        (status = 400, description = "Request body is not a feature collection.", body = ErrorResponse),
+    )
+)]
+pub async fn import_geojson(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    headers: HeaderMap,
+    Json(payload): Json<GeoJsonFeatureCollection>,
+) -> Result<Json<BulkResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!(
+        "(import_geojson) entry [{} features].",
+        payload.features.len()
+    );
+
+    let run_id = get_run_id(&headers, None).map_err(|e| {
+        rest_error!("(import_geojson) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    let response = geojson::import(&grpc_clients, &payload, run_id)
+        .await
+        .map_err(|e| {
+            rest_error!("(import_geojson) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    Ok(Json(response))
+}
+
+/// Export the vertiports, vertipads and aircraft hangar positions in
+/// storage as GeoJSON or KML
+#[utoipa::path(
+    get,
+    path = "/demo/export",
+    tag = "svc-itest",
+    params(ExportParams),
+    responses(
+        (status = 200, description = "Request successful.", content(
+            ("application/geo+json" = GeoJsonFeatureCollection),
+            ("application/vnd.google-earth.kml+xml" = String)
+        )),
+        (status = 400, description = "Invalid bounding box.", body = ErrorResponse),
+        (status = 404, description = "Run not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn export_realm(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Query(params): Query<ExportParams>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(export_realm) entry [{:?}].", params);
+
+    let bbox = export::bounding_box(&params).map_err(|e| {
+        rest_error!("(export_realm) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    let collection = export::export(&grpc_clients, params.run_id.as_deref(), bbox.as_ref())
+        .await
+        .map_err(|e| {
+            rest_error!("(export_realm) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    let response = match params.format.unwrap_or_default() {
+        ExportFormat::GeoJson => {
+            ([(CONTENT_TYPE, "application/geo+json")], Json(collection)).into_response()
+        }
+        ExportFormat::Kml => (
+            [(CONTENT_TYPE, "application/vnd.google-earth.kml+xml")],
+            export::to_kml(&collection),
+        )
+            .into_response(),
+    };
+
+    Ok(response)
+}
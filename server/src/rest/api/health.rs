@@ -0,0 +1,179 @@
+//! Health check REST API implementation
+
+use super::rest_types::{HealthResponse, RealmHealthResponse, WaitParams};
+use crate::grpc::client::GrpcClients;
+use crate::grpc::{gate, health};
+use crate::Config;
+use axum::{
+    extract::{Extension, Query},
+    Json,
+};
+use hyper::StatusCode;
+use std::time::Duration;
+
+/// Provides a way to tell a caller if the service is healthy.
+/// Probes all dependencies at the same time and reports the status and
+/// latency of each. The service is unhealthy if any of the configured
+/// critical dependencies is down.
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "svc-itest",
+    responses(
+        (status = 200, description = "Service is healthy, all critical dependencies running.", body = HealthResponse),
+        (status = 503, description = "Service is unhealthy, one or more critical dependencies unavailable.", body = HealthResponse)
+    )
+)]
+pub async fn health_check(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(config): Extension<Config>,
+) -> Result<Json<HealthResponse>, (StatusCode, Json<HealthResponse>)> {
+    rest_debug!("(health_check) entry.");
+
+    let critical = health::parse_dependencies(&config.health_critical_dependencies);
+    let timeout = Duration::from_millis(config.health_probe_timeout_ms);
+    let dependencies = health::probe_all(&grpc_clients, timeout, &critical).await;
+    let healthy = health::is_healthy(&dependencies);
+    let response = HealthResponse {
+        healthy,
+        dependencies,
+    };
+
+    match healthy {
+        true => {
+            rest_debug!("(health_check) healthy, all critical dependencies running.");
+            Ok(Json(response))
+        }
+        false => {
+            rest_error!("(health_check) unhealthy, 1+ critical dependencies down.");
+            Err((StatusCode::SERVICE_UNAVAILABLE, Json(response)))
+        }
+    }
+}
+
+/// Reports whether the whole realm is up.
+/// Probes every service with a `*_HOST_GRPC` and `*_PORT_GRPC` pair in the
+/// environment with the standard gRPC health protocol.
+#[utoipa::path(
+    get,
+    path = "/health/realm",
+    tag = "svc-itest",
+    responses(
+        (status = 200, description = "Realm is ready, all services serving.", body = RealmHealthResponse),
+        (status = 503, description = "Realm is not ready, one or more services unavailable.", body = RealmHealthResponse)
+    )
+)]
+pub async fn realm_health(
+    Extension(config): Extension<Config>,
+) -> Result<Json<RealmHealthResponse>, (StatusCode, Json<RealmHealthResponse>)> {
+    rest_debug!("(realm_health) entry.");
+
+    let timeout = Duration::from_millis(config.health_probe_timeout_ms);
+    let response = health::probe_realm(timeout).await;
+
+    match response.ready {
+        true => {
+            rest_debug!("(realm_health) realm ready, all services serving.");
+            Ok(Json(response))
+        }
+        false => {
+            rest_error!("(realm_health) realm not ready, 1+ services down.");
+            Err((StatusCode::SERVICE_UNAVAILABLE, Json(response)))
+        }
+    }
+}
+
+/// Waits until all critical dependencies are serving.
+/// Polls the dependencies with exponential backoff and jitter, reporting
+/// the dependencies which never came up when the timeout passes.
+#[utoipa::path(
+    get,
+    path = "/health/wait",
+    tag = "svc-itest",
+    params(WaitParams),
+    responses(
+        (status = 200, description = "All critical dependencies running.", body = HealthResponse),
+        (status = 503, description = "Critical dependencies still down after the timeout, only these are listed.", body = HealthResponse)
+    )
+)]
+pub async fn wait_for_dependencies(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(config): Extension<Config>,
+    Query(params): Query<WaitParams>,
+) -> Result<Json<HealthResponse>, (StatusCode, Json<HealthResponse>)> {
+    rest_debug!("(wait_for_dependencies) entry [{:?}].", params);
+
+    let timeout = super::wait_timeout(&config, params.timeout_ms);
+    match gate::wait_for_dependencies(&grpc_clients, &config, timeout).await {
+        Ok(dependencies) => Ok(Json(HealthResponse {
+            healthy: true,
+            dependencies,
+        })),
+        Err(e) => {
+            rest_error!("(wait_for_dependencies) {}.", e);
+            Err((
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(HealthResponse {
+                    healthy: false,
+                    dependencies: e.down,
+                }),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::api::rest_types::DependencyStatus;
+
+    #[tokio::test]
+    async fn test_health_check_success() {
+        crate::get_log_handle().await;
+        ut_info!("(test_health_check_success) Start.");
+
+        // Mock the GrpcClients extension
+        // Only svc-storage is stubbed, svc-gis can't be reached in tests
+        let mut config = crate::Config::try_from_env().unwrap_or_default();
+        config.health_critical_dependencies = String::from("svc-storage");
+        let grpc_clients = GrpcClients::default(config.clone()); // Replace with your own mock implementation
+
+        // Call the health_check function
+        let result = health_check(Extension(grpc_clients), Extension(config)).await;
+
+        // Assert the expected result
+        println!("{:?}", result);
+        assert!(result.is_ok());
+
+        ut_info!("(test_health_check_success) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_health_check_unavailable() {
+        crate::get_log_handle().await;
+        ut_info!("(test_health_check_unavailable) Start.");
+
+        // Nothing listens on this address, svc-gis is down
+        let mut config = crate::Config::try_from_env().unwrap_or_default();
+        config.health_critical_dependencies = String::from("svc-gis");
+        config.health_probe_timeout_ms = 500;
+        config.gis_host_grpc = String::from("127.0.0.1");
+        config.gis_port_grpc = 1;
+        let grpc_clients = GrpcClients::default(config.clone());
+
+        let result = health_check(Extension(grpc_clients), Extension(config)).await;
+        let (status, Json(response)) = result.unwrap_err();
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(!response.healthy);
+
+        let gis = response
+            .dependencies
+            .iter()
+            .find(|dependency| dependency.name == "svc-gis")
+            .unwrap();
+        assert!(gis.critical);
+        assert_ne!(gis.status, DependencyStatus::Serving);
+
+        ut_info!("(test_health_check_unavailable) Success.");
+    }
+}
@@ -0,0 +1,159 @@
+//! Run history REST API implementations, listing and comparing the
+//! recorded suite runs
+
+use super::{rest_types::*, seed_error_response};
+use crate::runner::{history, report};
+use crate::Config;
+use axum::{
+    extract::{Extension, Path, Query},
+    response::{IntoResponse, Response},
+    Json,
+};
+use hyper::{
+    header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    StatusCode,
+};
+
+/// List the recent suite runs, newest first
+#[utoipa::path(
+    get,
+    path = "/runs",
+    tag = "svc-itest",
+    params(RunsParams),
+    responses(
+        (status = 200, description = "Request successful.", body = [RunOverview]),
+        (status = 500, description = "Run history not available.", body = ErrorResponse),
+    )
+)]
+pub async fn list_runs(
+    Extension(config): Extension<Config>,
+    Query(params): Query<RunsParams>,
+) -> Result<Json<Vec<RunOverview>>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(list_runs) entry [{:?}].", params);
+
+    history::query(&config, move |connection| {
+        history::list_runs(connection, &params)
+    })
+    .await
+    .map(Json)
+    .map_err(|e| {
+        rest_error!("(list_runs) Error: {}.", e);
+        seed_error_response(e)
+    })
+}
+
+/// Get the result and timing of each step of a recorded run
+#[utoipa::path(
+    get,
+    path = "/runs/{run_id}",
+    tag = "svc-itest",
+    params(
+        ("run_id" = String, Path, description = "The ID of the run")
+    ),
+    responses(
+        (status = 200, description = "Request successful.", body = SuiteResult),
+        (status = 404, description = "Run not found.", body = ErrorResponse),
+        (status = 500, description = "Run history not available.", body = ErrorResponse),
+    )
+)]
+pub async fn get_run(
+    Extension(config): Extension<Config>,
+    Path(run_id): Path<String>,
+) -> Result<Json<SuiteResult>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(get_run) entry [{}].", run_id);
+
+    history::query(&config, move |connection| {
+        history::get_run(connection, &run_id)
+    })
+    .await
+    .map(Json)
+    .map_err(|e| {
+        rest_error!("(get_run) Error: {}.", e);
+        seed_error_response(e)
+    })
+}
+
+/// Download the report of a recorded run as JUnit XML, TAP or JSON
+#[utoipa::path(
+    get,
+    path = "/runs/{run_id}/report",
+    tag = "svc-itest",
+    params(
+        ("run_id" = String, Path, description = "The ID of the run"),
+        ReportParams
+    ),
+    responses(
+        (status = 200, description = "Request successful.", content(
+            ("application/json" = SuiteReport),
+            ("application/xml" = String),
+            ("text/plain" = String)
+        )),
+        (status = 404, description = "Run not found.", body = ErrorResponse),
+        (status = 500, description = "Run history not available.", body = ErrorResponse),
+    )
+)]
+pub async fn get_run_report(
+    Extension(config): Extension<Config>,
+    Path(run_id): Path<String>,
+    Query(params): Query<ReportParams>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(get_run_report) entry [{}] [{:?}].", run_id, params);
+
+    let id = run_id.clone();
+    let result = history::query(&config, move |connection| history::get_run(connection, &id))
+        .await
+        .map_err(|e| {
+            rest_error!("(get_run_report) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    let format = params.format.unwrap_or_default();
+    let disposition = format!(
+        "attachment; filename=\"{}.{}\"",
+        run_id,
+        report::extension(format)
+    );
+    Ok((
+        [
+            (CONTENT_TYPE, report::content_type(format).to_string()),
+            (CONTENT_DISPOSITION, disposition),
+        ],
+        report::render(&result, format),
+    )
+        .into_response())
+}
+
+/// Compare two recorded runs step by step
+///
+/// Steps are matched by name. Each step reports how its outcome changed
+/// from the base run to the other run, and the difference of its duration.
+#[utoipa::path(
+    get,
+    path = "/runs/{run_id}/compare/{other_id}",
+    tag = "svc-itest",
+    params(
+        ("run_id" = String, Path, description = "The ID of the base run"),
+        ("other_id" = String, Path, description = "The ID of the run compared to the base run")
+    ),
+    responses(
+        (status = 200, description = "Request successful.", body = RunComparison),
+        (status = 404, description = "Run not found.", body = ErrorResponse),
+        (status = 500, description = "Run history not available.", body = ErrorResponse),
+    )
+)]
+pub async fn compare_runs(
+    Extension(config): Extension<Config>,
+    Path((run_id, other_id)): Path<(String, String)>,
+) -> Result<Json<RunComparison>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(compare_runs) entry [{}] [{}].", run_id, other_id);
+
+    history::query(&config, move |connection| {
+        history::compare_runs(connection, &run_id, &other_id)
+    })
+    .await
+    .map(Json)
+    .map_err(|e| {
+        rest_error!("(compare_runs) Error: {}.", e);
+        seed_error_response(e)
+    })
+}
@@ -0,0 +1,231 @@
+//! Job REST API implementations, running long seeds and test runs in the
+//! background
+
+use super::{get_run_id, get_wait_timeout, rest_types::*, seed_error_response};
+use crate::grpc::client::GrpcClients;
+use crate::jobs::{self, work, Jobs};
+use crate::runner::report;
+use crate::seed::SeedError;
+use crate::Config;
+use axum::{
+    extract::{Extension, Path, Query},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Json,
+};
+use futures::stream::{self, Stream};
+use hyper::{
+    header::{CONTENT_DISPOSITION, CONTENT_TYPE, LOCATION},
+    HeaderMap, StatusCode,
+};
+
+/// Start a background job creating a scenario or realm, or running a suite
+#[utoipa::path(
+    post,
+    path = "/jobs",
+    tag = "svc-itest",
+    params(
+        ("x-run-id" = Option<String>, Header, description = "The run the resources of a seed job are created for"),
+        ("x-wait-for-dependencies" = Option<u64>, Header, description = "Milliseconds the job waits for the critical dependencies before it starts, at most the readiness gate timeout")
+    ),
+    request_body = JobRequest,
+    responses(
+        (status = 202, description = "Job started.", body = JobResponse),
+        (status = 400, description = "Invalid request.", body = ErrorResponse),
+        (status = 404, description = "Suite not found.", body = ErrorResponse),
+    )
+)]
+pub async fn add_job(
+    Extension(jobs): Extension<Jobs>,
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(config): Extension<Config>,
+    headers: HeaderMap,
+    Json(payload): Json<JobRequest>,
+) -> Result<(StatusCode, HeaderMap, Json<JobResponse>), (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(add_job) entry.");
+
+    let field = match &payload {
+        JobRequest::Scenario { scenario } => scenario.run_id.as_deref(),
+        JobRequest::Realm { realm } => realm.run_id.as_deref(),
+        JobRequest::Suite { .. } => None,
+    };
+    let run_id = get_run_id(&headers, field).map_err(|e| {
+        rest_error!("(add_job) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+    let wait_timeout = get_wait_timeout(&headers, &config).map_err(|e| {
+        rest_error!("(add_job) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    let job =
+        work::start(&jobs, grpc_clients, config, payload, run_id, wait_timeout).map_err(|e| {
+            rest_error!("(add_job) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    let mut response_headers = HeaderMap::new();
+    if let Ok(location) = format!("/jobs/{}", job.id).parse() {
+        response_headers.insert(LOCATION, location);
+    }
+
+    Ok((StatusCode::ACCEPTED, response_headers, Json(job)))
+}
+
+/// Get the status, progress and result of a job
+#[utoipa::path(
+    get,
+    path = "/jobs/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the job")
+    ),
+    responses(
+        (status = 200, description = "Request successful.", body = JobResponse),
+        (status = 404, description = "Job not found.", body = ErrorResponse),
+    )
+)]
+pub async fn get_job(
+    Extension(jobs): Extension<Jobs>,
+    Path(id): Path<String>,
+) -> Result<Json<JobResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(get_job) entry [{}].", id);
+
+    jobs.get(&id).map(Json).ok_or_else(|| {
+        rest_error!("(get_job) job [{}] not found.", id);
+        seed_error_response(SeedError::NotFound(format!("job [{}]", id)))
+    })
+}
+
+/// Cancel a job, rolling back the resources it created
+#[utoipa::path(
+    delete,
+    path = "/jobs/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the job")
+    ),
+    responses(
+        (status = 202, description = "Job cancelled, it finishes once its changes have been rolled back.", body = JobResponse),
+        (status = 404, description = "Job not found.", body = ErrorResponse),
+        (status = 409, description = "Job already finished.", body = ErrorResponse),
+    )
+)]
+pub async fn delete_job(
+    Extension(jobs): Extension<Jobs>,
+    Path(id): Path<String>,
+) -> Result<(StatusCode, Json<JobResponse>), (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(delete_job) entry [{}].", id);
+
+    let job = jobs.cancel(&id).map_err(|e| {
+        rest_error!("(delete_job) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// Follow the progress of a job as Server-Sent Events
+///
+/// Sends a `job` event with the state of the job right away and on each
+/// change. The stream ends once the job finished.
+#[utoipa::path(
+    get,
+    path = "/jobs/{id}/events",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the job")
+    ),
+    responses(
+        (status = 200, description = "Stream of job states.", body = JobResponse, content_type = "text/event-stream"),
+        (status = 404, description = "Job not found.", body = ErrorResponse),
+    )
+)]
+pub async fn job_events(
+    Extension(jobs): Extension<Jobs>,
+    Path(id): Path<String>,
+) -> Result<
+    Sse<impl Stream<Item = Result<Event, serde_json::Error>>>,
+    (StatusCode, Json<ErrorResponse>),
+> {
+    rest_debug!("(job_events) entry [{}].", id);
+
+    let receiver = jobs.subscribe(&id).ok_or_else(|| {
+        rest_error!("(job_events) job [{}] not found.", id);
+        seed_error_response(SeedError::NotFound(format!("job [{}]", id)))
+    })?;
+
+    // Yields the current state, then each change until the job finished
+    let events = stream::unfold(
+        (receiver, true, false),
+        |(mut receiver, first, finished)| async move {
+            if finished || (!first && receiver.changed().await.is_err()) {
+                return None;
+            }
+
+            let job = receiver.borrow_and_update().clone();
+            let finished = jobs::is_finished(job.status);
+            let event = Event::default().event("job").json_data(&job);
+            Some((event, (receiver, false, finished)))
+        },
+    );
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Download the report of a finished suite job as JUnit XML, TAP or JSON
+#[utoipa::path(
+    get,
+    path = "/jobs/{id}/report",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the job"),
+        ReportParams
+    ),
+    responses(
+        (status = 200, description = "Request successful.", content(
+            ("application/json" = SuiteReport),
+            ("application/xml" = String),
+            ("text/plain" = String)
+        )),
+        (status = 404, description = "Job not found.", body = ErrorResponse),
+        (status = 409, description = "The job is not a finished suite job.", body = ErrorResponse),
+    )
+)]
+pub async fn get_job_report(
+    Extension(jobs): Extension<Jobs>,
+    Path(id): Path<String>,
+    Query(params): Query<ReportParams>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(get_job_report) entry [{}] [{:?}].", id, params);
+
+    let job = jobs.get(&id).ok_or_else(|| {
+        rest_error!("(get_job_report) job [{}] not found.", id);
+        seed_error_response(SeedError::NotFound(format!("job [{}]", id)))
+    })?;
+
+    let Some(JobResult::Suite(result)) = job.result else {
+        rest_error!("(get_job_report) job [{}] has no suite result.", id);
+        return Err(seed_error_response(SeedError::Conflict(format!(
+            "job [{}] is not a finished suite job",
+            id
+        ))));
+    };
+
+    let format = params.format.unwrap_or_default();
+    let disposition = format!(
+        "attachment; filename=\"{}.{}\"",
+        id,
+        report::extension(format)
+    );
+    Ok((
+        [
+            (CONTENT_TYPE, report::content_type(format).to_string()),
+            (CONTENT_DISPOSITION, disposition),
+        ],
+        report::render(&result, format),
+    )
+        .into_response())
+}
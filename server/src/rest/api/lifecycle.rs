@@ -0,0 +1,370 @@
+//! Demo REST API implementations, updating and deleting realm resources
+
+use super::{rest_types::*, seed_error_response};
+use crate::grpc::client::GrpcClients;
+use crate::seed::{ledger, lifecycle, Saga};
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use hyper::StatusCode;
+
+/// Update a vertiport in storage and GIS
+#[utoipa::path(
+    patch,
+    path = "/demo/vertiport/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the vertiport")
+    ),
+    request_body = UpdateVertiportRequest,
+    responses(
+        (status = 200, description = "Request successful.", body = VertiportResponse),
+        (status = 400, description = "Invalid request.", body = ErrorResponse),
+        (status = 409, description = "Request conflicts with the current state of the realm.", body = ErrorResponse),
+        (status = 404, description = "Vertiport not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful, changes have been rolled back.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn update_vertiport(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateVertiportRequest>,
+) -> Result<Json<VertiportResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(update_vertiport) entry [{}].", id);
+
+    let mut saga = Saga::default();
+    let result = lifecycle::update_vertiport(&grpc_clients, &id, &payload, &mut saga).await;
+    let vertiport = saga.finish(result).await.map_err(|e| {
+        rest_error!("(update_vertiport) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(vertiport))
+}
+
+/// Delete a vertiport from storage
+#[utoipa::path(
+    delete,
+    path = "/demo/vertiport/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the vertiport")
+    ),
+    responses(
+        (status = 204, description = "Request successful."),
+        (status = 404, description = "Vertiport not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn delete_vertiport(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(delete_vertiport) entry [{}].", id);
+
+    lifecycle::delete_vertiport(&grpc_clients, &id)
+        .await
+        .map_err(|e| {
+            rest_error!("(delete_vertiport) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Update a vertipad in storage and GIS
+#[utoipa::path(
+    patch,
+    path = "/demo/vertipad/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the vertipad")
+    ),
+    request_body = UpdateVertipadRequest,
+    responses(
+        (status = 200, description = "Request successful.", body = VertipadResponse),
+        (status = 400, description = "Invalid request.", body = ErrorResponse),
+        (status = 409, description = "Request conflicts with the current state of the realm.", body = ErrorResponse),
+        (status = 404, description = "Vertipad not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful, changes have been rolled back.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn update_vertipad(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateVertipadRequest>,
+) -> Result<Json<VertipadResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(update_vertipad) entry [{}].", id);
+
+    let mut saga = Saga::default();
+    let result = lifecycle::update_vertipad(&grpc_clients, &id, &payload, &mut saga).await;
+    let vertipad = saga.finish(result).await.map_err(|e| {
+        rest_error!("(update_vertipad) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(vertipad))
+}
+
+/// Delete a vertipad from storage
+#[utoipa::path(
+    delete,
+    path = "/demo/vertipad/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the vertipad")
+    ),
+    responses(
+        (status = 204, description = "Request successful."),
+        (status = 404, description = "Vertipad not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn delete_vertipad(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(delete_vertipad) entry [{}].", id);
+
+    lifecycle::delete_vertipad(&grpc_clients, &id)
+        .await
+        .map_err(|e| {
+            rest_error!("(delete_vertipad) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Update an aircraft in storage
+#[utoipa::path(
+    patch,
+    path = "/demo/aircraft/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the aircraft")
+    ),
+    request_body = UpdateAircraftRequest,
+    responses(
+        (status = 200, description = "Request successful.", body = AircraftResponse),
+        (status = 400, description = "Invalid request.", body = ErrorResponse),
+        (status = 409, description = "Request conflicts with the current state of the realm.", body = ErrorResponse),
+        (status = 404, description = "Aircraft not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful, changes have been rolled back.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn update_aircraft(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateAircraftRequest>,
+) -> Result<Json<AircraftResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(update_aircraft) entry [{}].", id);
+
+    let mut saga = Saga::default();
+    let result = lifecycle::update_aircraft(&grpc_clients, &id, &payload, &mut saga).await;
+    let aircraft = saga.finish(result).await.map_err(|e| {
+        rest_error!("(update_aircraft) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(aircraft))
+}
+
+/// Delete an aircraft from storage
+#[utoipa::path(
+    delete,
+    path = "/demo/aircraft/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the aircraft")
+    ),
+    responses(
+        (status = 204, description = "Request successful."),
+        (status = 404, description = "Aircraft not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn delete_aircraft(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(delete_aircraft) entry [{}].", id);
+
+    lifecycle::delete_aircraft(&grpc_clients, &id)
+        .await
+        .map_err(|e| {
+            rest_error!("(delete_aircraft) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Update a user in storage
+#[utoipa::path(
+    patch,
+    path = "/demo/user/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the user")
+    ),
+    request_body = UpdateUserRequest,
+    responses(
+        (status = 200, description = "Request successful.", body = UserResponse),
+        (status = 400, description = "Invalid request.", body = ErrorResponse),
+        (status = 409, description = "Request conflicts with the current state of the realm.", body = ErrorResponse),
+        (status = 404, description = "User not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn update_user(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateUserRequest>,
+) -> Result<Json<UserResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(update_user) entry [{}].", id);
+
+    let user = lifecycle::update_user(&grpc_clients, &id, &payload)
+        .await
+        .map_err(|e| {
+            rest_error!("(update_user) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    Ok(Json(user))
+}
+
+/// Delete a user from storage
+#[utoipa::path(
+    delete,
+    path = "/demo/user/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the user")
+    ),
+    responses(
+        (status = 204, description = "Request successful."),
+        (status = 404, description = "User not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn delete_user(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(delete_user) entry [{}].", id);
+
+    lifecycle::delete_user(&grpc_clients, &id)
+        .await
+        .map_err(|e| {
+            rest_error!("(delete_user) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Update a scanner in storage
+#[utoipa::path(
+    patch,
+    path = "/demo/scanner/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the scanner")
+    ),
+    request_body = UpdateScannerRequest,
+    responses(
+        (status = 200, description = "Request successful.", body = ScannerResponse),
+        (status = 400, description = "Invalid request.", body = ErrorResponse),
+        (status = 409, description = "Request conflicts with the current state of the realm.", body = ErrorResponse),
+        (status = 404, description = "Scanner not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn update_scanner(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateScannerRequest>,
+) -> Result<Json<ScannerResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(update_scanner) entry [{}].", id);
+
+    let scanner = lifecycle::update_scanner(&grpc_clients, &id, &payload)
+        .await
+        .map_err(|e| {
+            rest_error!("(update_scanner) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    Ok(Json(scanner))
+}
+
+/// Delete a scanner from storage
+#[utoipa::path(
+    delete,
+    path = "/demo/scanner/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the scanner")
+    ),
+    responses(
+        (status = 204, description = "Request successful."),
+        (status = 404, description = "Scanner not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn delete_scanner(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(delete_scanner) entry [{}].", id);
+
+    lifecycle::delete_scanner(&grpc_clients, &id)
+        .await
+        .map_err(|e| {
+            rest_error!("(delete_scanner) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Delete all resources created for a run
+#[utoipa::path(
+    delete,
+    path = "/demo/run/{run_id}",
+    tag = "svc-itest",
+    params(
+        ("run_id" = String, Path, description = "The ID of the run")
+    ),
+    responses(
+        (status = 200, description = "Request processed, see the teardown report.", body = RunTeardownResponse),
+        (status = 404, description = "Run not found.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn delete_run(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Path(run_id): Path<String>,
+) -> Result<Json<RunTeardownResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(delete_run) entry [{}].", run_id);
+
+    let teardown = ledger::teardown(&grpc_clients, &run_id)
+        .await
+        .map_err(|e| {
+            rest_error!("(delete_run) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    Ok(Json(RunTeardownResponse { run_id, teardown }))
+}
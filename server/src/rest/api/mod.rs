@@ -0,0 +1,273 @@
+//! Rest API implementations
+/// openapi generated rest types
+pub mod rest_types {
+    include!("../../../../openapi/types.rs");
+}
+
+pub use rest_types::*;
+
+pub mod bulk;
+pub mod demo;
+pub mod geo;
+pub mod health;
+pub mod history;
+pub mod jobs;
+pub mod lifecycle;
+pub mod query;
+pub mod scenario;
+pub mod upsert;
+pub mod vehicle_model;
+
+use crate::grpc::{client::GrpcClients, gate};
+use crate::seed::{SeedError, SeedFailure};
+use crate::Config;
+use axum::Json;
+use hyper::{HeaderMap, StatusCode};
+use std::time::Duration;
+use tonic::Code;
+
+/// Header carrying the ID of the run a seeding request belongs to
+pub(crate) const RUN_ID_HEADER: &str = "x-run-id";
+
+/// Header asking a seeding request to wait for the critical dependencies,
+/// with the time to wait in milliseconds
+pub(crate) const WAIT_HEADER: &str = "x-wait-for-dependencies";
+
+/// Returns the run ID of a seeding request, provided through the
+/// [`RUN_ID_HEADER`] header or the request body.
+/// Both may be provided as long as they match.
+pub(crate) fn get_run_id(
+    headers: &HeaderMap,
+    field: Option<&str>,
+) -> Result<Option<String>, SeedError> {
+    let header = match headers.get(RUN_ID_HEADER) {
+        Some(value) => Some(value.to_str().map_err(|e| {
+            SeedError::invalid_field("run_id", format!("invalid {} header: {}", RUN_ID_HEADER, e))
+        })?),
+        None => None,
+    };
+
+    let run_id = match (header, field) {
+        (Some(header), Some(field)) if header != field => {
+            return Err(SeedError::invalid_field(
+                "run_id",
+                format!(
+                    "run ID [{}] of the {} header does not match run ID [{}] of the request",
+                    header, RUN_ID_HEADER, field
+                ),
+            ))
+        }
+        (header, field) => header.or(field),
+    };
+
+    match run_id {
+        Some(run_id) if run_id.trim().is_empty() => Err(SeedError::invalid_field(
+            "run_id",
+            "run ID must not be empty",
+        )),
+        run_id => Ok(run_id.map(|run_id| run_id.to_string())),
+    }
+}
+
+/// Returns the time a seeding request waits for the critical dependencies,
+/// provided through the [`WAIT_HEADER`] header. Waits are limited to the
+/// configured readiness gate timeout, see [`wait_timeout`].
+pub(crate) fn get_wait_timeout(
+    headers: &HeaderMap,
+    config: &Config,
+) -> Result<Option<Duration>, SeedError> {
+    let Some(value) = headers.get(WAIT_HEADER) else {
+        return Ok(None);
+    };
+
+    value
+        .to_str()
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(|timeout_ms| Some(wait_timeout(config, Some(timeout_ms))))
+        .ok_or_else(|| {
+            SeedError::InvalidRequest(format!(
+                "invalid {} header, expected a number of milliseconds",
+                WAIT_HEADER
+            ))
+        })
+}
+
+/// Returns the time a request waits for the critical dependencies, at most
+/// the configured readiness gate timeout. Each waiting request holds a slot
+/// of the concurrency limit, so long waits would starve the other requests.
+pub(crate) fn wait_timeout(config: &Config, timeout_ms: Option<u64>) -> Duration {
+    let limit = config.readiness_gate_timeout_ms;
+    Duration::from_millis(timeout_ms.map_or(limit, |timeout_ms| timeout_ms.min(limit)))
+}
+
+/// Passes the readiness gate if the seeding request asks for it through the
+/// [`WAIT_HEADER`] header
+pub(crate) async fn wait_for_dependencies(
+    headers: &HeaderMap,
+    grpc_clients: &GrpcClients,
+    config: &Config,
+) -> Result<(), SeedError> {
+    if let Some(timeout) = get_wait_timeout(headers, config)? {
+        gate::wait_for_dependencies(grpc_clients, config, timeout).await?;
+    }
+
+    Ok(())
+}
+
+/// Returns the HTTP status code for a seed error
+///
+/// Errors of dependencies are mapped based on their gRPC status code.
+/// Other errors which name a rejected field are caused by bad input.
+pub(crate) fn status_code(error: &SeedError) -> StatusCode {
+    match error {
+        SeedError::InvalidRequest(_) | SeedError::InvalidField { .. } => StatusCode::BAD_REQUEST,
+        SeedError::NotFound(_) => StatusCode::NOT_FOUND,
+        SeedError::Conflict(_) | SeedError::Cancelled => StatusCode::CONFLICT,
+        SeedError::NoObject => StatusCode::INTERNAL_SERVER_ERROR,
+        SeedError::Dependency(e) => match e.code {
+            Code::InvalidArgument | Code::OutOfRange => StatusCode::BAD_REQUEST,
+            Code::NotFound => StatusCode::NOT_FOUND,
+            Code::AlreadyExists | Code::Aborted | Code::FailedPrecondition => StatusCode::CONFLICT,
+            Code::Unavailable | Code::DeadlineExceeded | Code::ResourceExhausted => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            _ if e.field.is_some() => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        },
+    }
+}
+
+impl From<SeedFailure> for ErrorResponse {
+    fn from(failure: SeedFailure) -> Self {
+        let status = status_code(&failure.error);
+        let (dependency, field) = match &failure.error {
+            SeedError::InvalidField { field, .. } => (None, Some(field.clone())),
+            SeedError::Dependency(e) => (Some(e.dependency.clone()), e.field.clone()),
+            _ => (None, None),
+        };
+
+        ErrorResponse {
+            title: status.canonical_reason().unwrap_or_default().to_string(),
+            status: status.as_u16(),
+            error: failure.error.to_string(),
+            dependency,
+            field,
+            rollback: failure.rollback,
+        }
+    }
+}
+
+/// Maps a failed seed request to the status code and problem document
+/// returned to the caller, including the outcome of the rollback.
+pub(crate) fn seed_error_response(
+    failure: impl Into<SeedFailure>,
+) -> (StatusCode, Json<ErrorResponse>) {
+    let body: ErrorResponse = failure.into().into();
+    let status = StatusCode::from_u16(body.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    (status, Json(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::header::HeaderValue;
+
+    #[tokio::test]
+    async fn test_get_run_id() {
+        crate::get_log_handle().await;
+        ut_info!("(test_get_run_id) Start.");
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(get_run_id(&headers, None), Ok(None));
+        assert_eq!(
+            get_run_id(&headers, Some("run-1")),
+            Ok(Some("run-1".to_string()))
+        );
+        assert!(get_run_id(&headers, Some(" ")).is_err());
+
+        headers.insert(RUN_ID_HEADER, HeaderValue::from_static("run-1"));
+        assert_eq!(get_run_id(&headers, None), Ok(Some("run-1".to_string())));
+        assert_eq!(
+            get_run_id(&headers, Some("run-1")),
+            Ok(Some("run-1".to_string()))
+        );
+        assert!(get_run_id(&headers, Some("run-2")).is_err());
+
+        ut_info!("(test_get_run_id) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_get_wait_timeout() {
+        crate::get_log_handle().await;
+        ut_info!("(test_get_wait_timeout) Start.");
+
+        let config = Config {
+            readiness_gate_timeout_ms: 60000,
+            ..Config::default()
+        };
+        let mut headers = HeaderMap::new();
+        assert_eq!(get_wait_timeout(&headers, &config), Ok(None));
+
+        headers.insert(WAIT_HEADER, HeaderValue::from_static("30000"));
+        assert_eq!(
+            get_wait_timeout(&headers, &config),
+            Ok(Some(Duration::from_secs(30)))
+        );
+
+        // Waits are limited to the readiness gate timeout
+        headers.insert(WAIT_HEADER, HeaderValue::from_static("3600000"));
+        assert_eq!(
+            get_wait_timeout(&headers, &config),
+            Ok(Some(Duration::from_secs(60)))
+        );
+        assert_eq!(wait_timeout(&config, None), Duration::from_secs(60));
+
+        headers.insert(WAIT_HEADER, HeaderValue::from_static("true"));
+        assert!(get_wait_timeout(&headers, &config).is_err());
+
+        ut_info!("(test_get_wait_timeout) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_seed_error_response() {
+        crate::get_log_handle().await;
+        ut_info!("(test_seed_error_response) Start.");
+
+        let (status, Json(body)) =
+            seed_error_response(SeedError::invalid_field("scanner_type", "unknown"));
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body.title, "Bad Request");
+        assert_eq!(body.status, 400);
+        assert_eq!(body.field, Some("scanner_type".to_string()));
+        assert_eq!(body.dependency, None);
+
+        let cases = [
+            (Code::InvalidArgument, StatusCode::BAD_REQUEST),
+            (Code::NotFound, StatusCode::NOT_FOUND),
+            (Code::AlreadyExists, StatusCode::CONFLICT),
+            (Code::Unavailable, StatusCode::SERVICE_UNAVAILABLE),
+            (Code::Internal, StatusCode::INTERNAL_SERVER_ERROR),
+        ];
+        for (code, expected) in cases {
+            let status = tonic::Status::new(code, "storage says no");
+            let (status, Json(body)) =
+                seed_error_response(SeedError::dependency(crate::seed::STORAGE, status));
+            assert_eq!(status, expected);
+            assert_eq!(body.status, expected.as_u16());
+            assert_eq!(body.dependency, Some("svc-storage".to_string()));
+        }
+
+        // Rejected foreign keys are bad input
+        let status = tonic::Status::internal("Key (vertiport_id)=(1) is not present");
+        let (status, Json(body)) =
+            seed_error_response(SeedError::dependency(crate::seed::STORAGE, status));
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body.field, Some("vertiport_id".to_string()));
+
+        let (status, _) = seed_error_response(SeedError::Conflict("2 users".to_string()));
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        ut_info!("(test_seed_error_response) Success.");
+    }
+}
@@ -0,0 +1,297 @@
+//! Demo REST API implementations, reading back realm resources
+
+use super::{rest_types::*, seed_error_response};
+use crate::grpc::client::GrpcClients;
+use crate::seed::query;
+use axum::{
+    extract::{Extension, Path, Query},
+    Json,
+};
+use hyper::StatusCode;
+
+/// Get a vertiport from storage and GIS
+#[utoipa::path(
+    get,
+    path = "/demo/vertiport/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the vertiport")
+    ),
+    responses(
+        (status = 200, description = "Request successful.", body = VertiportResponse),
+        (status = 404, description = "Vertiport not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn get_vertiport(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Path(id): Path<String>,
+) -> Result<Json<VertiportResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(get_vertiport) entry [{}].", id);
+
+    let vertiport = query::get_vertiport(&grpc_clients, &id)
+        .await
+        .map_err(|e| {
+            rest_error!("(get_vertiport) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    Ok(Json(vertiport))
+}
+
+/// List vertiports from storage and GIS
+#[utoipa::path(
+    get,
+    path = "/demo/vertiport",
+    tag = "svc-itest",
+    params(VertiportFilter),
+    responses(
+        (status = 200, description = "Request successful.", body = [VertiportResponse]),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn list_vertiports(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Query(filter): Query<VertiportFilter>,
+) -> Result<Json<Vec<VertiportResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(list_vertiports) entry.");
+
+    let vertiports = query::list_vertiports(&grpc_clients, &filter)
+        .await
+        .map_err(|e| {
+            rest_error!("(list_vertiports) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    Ok(Json(vertiports))
+}
+
+/// Get a vertipad from storage and GIS
+#[utoipa::path(
+    get,
+    path = "/demo/vertipad/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the vertipad")
+    ),
+    responses(
+        (status = 200, description = "Request successful.", body = VertipadResponse),
+        (status = 404, description = "Vertipad not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn get_vertipad(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Path(id): Path<String>,
+) -> Result<Json<VertipadResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(get_vertipad) entry [{}].", id);
+
+    let vertipad = query::get_vertipad(&grpc_clients, &id).await.map_err(|e| {
+        rest_error!("(get_vertipad) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(vertipad))
+}
+
+/// List vertipads from storage and GIS
+#[utoipa::path(
+    get,
+    path = "/demo/vertipad",
+    tag = "svc-itest",
+    params(VertipadFilter),
+    responses(
+        (status = 200, description = "Request successful.", body = [VertipadResponse]),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn list_vertipads(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Query(filter): Query<VertipadFilter>,
+) -> Result<Json<Vec<VertipadResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(list_vertipads) entry.");
+
+    let vertipads = query::list_vertipads(&grpc_clients, &filter)
+        .await
+        .map_err(|e| {
+            rest_error!("(list_vertipads) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    Ok(Json(vertipads))
+}
+
+/// Get an aircraft from storage
+#[utoipa::path(
+    get,
+    path = "/demo/aircraft/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the aircraft")
+    ),
+    responses(
+        (status = 200, description = "Request successful.", body = AircraftResponse),
+        (status = 404, description = "Aircraft not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn get_aircraft(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Path(id): Path<String>,
+) -> Result<Json<AircraftResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(get_aircraft) entry [{}].", id);
+
+    let aircraft = query::get_aircraft(&grpc_clients, &id).await.map_err(|e| {
+        rest_error!("(get_aircraft) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(aircraft))
+}
+
+/// List aircraft from storage
+#[utoipa::path(
+    get,
+    path = "/demo/aircraft",
+    tag = "svc-itest",
+    params(AircraftFilter),
+    responses(
+        (status = 200, description = "Request successful.", body = [AircraftResponse]),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn list_aircraft(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Query(filter): Query<AircraftFilter>,
+) -> Result<Json<Vec<AircraftResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(list_aircraft) entry.");
+
+    let aircraft_list = query::list_aircraft(&grpc_clients, &filter)
+        .await
+        .map_err(|e| {
+            rest_error!("(list_aircraft) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    Ok(Json(aircraft_list))
+}
+
+/// Get an user from storage
+#[utoipa::path(
+    get,
+    path = "/demo/user/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the user")
+    ),
+    responses(
+        (status = 200, description = "Request successful.", body = UserResponse),
+        (status = 404, description = "User not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn get_user(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Path(id): Path<String>,
+) -> Result<Json<UserResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(get_user) entry [{}].", id);
+
+    let user = query::get_user(&grpc_clients, &id).await.map_err(|e| {
+        rest_error!("(get_user) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(user))
+}
+
+/// List users from storage
+#[utoipa::path(
+    get,
+    path = "/demo/user",
+    tag = "svc-itest",
+    params(UserFilter),
+    responses(
+        (status = 200, description = "Request successful.", body = [UserResponse]),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn list_users(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Query(filter): Query<UserFilter>,
+) -> Result<Json<Vec<UserResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(list_users) entry.");
+
+    let users = query::list_users(&grpc_clients, &filter)
+        .await
+        .map_err(|e| {
+            rest_error!("(list_users) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    Ok(Json(users))
+}
+
+/// Get a scanner from storage
+#[utoipa::path(
+    get,
+    path = "/demo/scanner/{id}",
+    tag = "svc-itest",
+    params(
+        ("id" = String, Path, description = "The ID of the scanner")
+    ),
+    responses(
+        (status = 200, description = "Request successful.", body = ScannerResponse),
+        (status = 404, description = "Scanner not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn get_scanner(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Path(id): Path<String>,
+) -> Result<Json<ScannerResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(get_scanner) entry [{}].", id);
+
+    let scanner = query::get_scanner(&grpc_clients, &id).await.map_err(|e| {
+        rest_error!("(get_scanner) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(scanner))
+}
+
+/// List scanners from storage
+#[utoipa::path(
+    get,
+    path = "/demo/scanner",
+    tag = "svc-itest",
+    params(ScannerFilter),
+    responses(
+        (status = 200, description = "Request successful.", body = [ScannerResponse]),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn list_scanners(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Query(filter): Query<ScannerFilter>,
+) -> Result<Json<Vec<ScannerResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(list_scanners) entry.");
+
+    let scanners = query::list_scanners(&grpc_clients, &filter)
+        .await
+        .map_err(|e| {
+            rest_error!("(list_scanners) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    Ok(Json(scanners))
+}
@@ -0,0 +1,149 @@
+//! Scenario REST API implementations, creating sets of related realm resources
+
+use super::{get_run_id, rest_types::*, seed_error_response, wait_for_dependencies};
+use crate::grpc::client::GrpcClients;
+use crate::seed::{self, scenario::ScenarioFormat, Saga};
+use crate::Config;
+use axum::{extract::Extension, Json};
+use hyper::{header::CONTENT_TYPE, HeaderMap, StatusCode};
+
+/// Determines the scenario format from the request's content type.
+/// Defaults to YAML, which also accepts JSON documents.
+fn get_format(headers: &HeaderMap) -> ScenarioFormat {
+    match headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+    {
+        Some(content_type) if content_type.contains("json") => ScenarioFormat::Json,
+        _ => ScenarioFormat::Yaml,
+    }
+}
+
+/// Create all resources described by a YAML or JSON scenario
+#[utoipa::path(
+    put,
+    path = "/demo/scenario",
+    tag = "svc-itest",
+    params(
+        ("x-run-id" = Option<String>, Header, description = "The run the resources are created for"),
+        ("x-wait-for-dependencies" = Option<u64>, Header, description = "Milliseconds to wait for the critical dependencies before seeding, at most the readiness gate timeout")
+    ),
+    request_body(content = Scenario, content_type = "application/yaml"),
+    responses(
+        (status = 200, description = "Request successful.", body = ScenarioResponse),
+        (status = 400, description = "Invalid scenario.", body = ErrorResponse),
+        (status = 409, description = "Request conflicts with the current state of the realm.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful, changes have been rolled back.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable or did not come up in time.", body = ErrorResponse),
+    )
+)]
+pub async fn add_scenario(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(config): Extension<Config>,
+    headers: HeaderMap,
+    payload: String,
+) -> Result<Json<ScenarioResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(add_scenario) entry.");
+
+    let scenario = seed::scenario::parse(&payload, get_format(&headers)).map_err(|e| {
+        rest_error!("(add_scenario) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    let run_id = get_run_id(&headers, scenario.run_id.as_deref()).map_err(|e| {
+        rest_error!("(add_scenario) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    wait_for_dependencies(&headers, &grpc_clients, &config)
+        .await
+        .map_err(|e| {
+            rest_error!("(add_scenario) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    let mut saga = Saga::for_run(run_id);
+    let result = seed::scenario::apply(&grpc_clients, &scenario, &mut saga).await;
+    let ids = saga.finish(result).await.map_err(|e| {
+        rest_error!("(add_scenario) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(ScenarioResponse { ids }))
+}
+
+/// Generate a synthetic realm and create all of its resources
+#[utoipa::path(
+    put,
+    path = "/demo/realm",
+    tag = "svc-itest",
+    params(
+        ("x-run-id" = Option<String>, Header, description = "The run the resources are created for"),
+        ("x-wait-for-dependencies" = Option<u64>, Header, description = "Milliseconds to wait for the critical dependencies before seeding, at most the readiness gate timeout")
+    ),
+    request_body = GenerateRealmRequest,
+    responses(
+        (status = 200, description = "Request successful.", body = ScenarioResponse),
+        (status = 400, description = "Invalid realm parameters.", body = ErrorResponse),
+        (status = 409, description = "Request conflicts with the current state of the realm.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful, changes have been rolled back.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable or did not come up in time.", body = ErrorResponse),
+    )
+)]
+pub async fn add_realm(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    Extension(config): Extension<Config>,
+    headers: HeaderMap,
+    Json(payload): Json<GenerateRealmRequest>,
+) -> Result<Json<ScenarioResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(add_realm) entry [seed {}].", payload.seed);
+
+    let run_id = get_run_id(&headers, payload.run_id.as_deref()).map_err(|e| {
+        rest_error!("(add_realm) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    let scenario = seed::generator::generate(&payload).map_err(|e| {
+        rest_error!("(add_realm) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    wait_for_dependencies(&headers, &grpc_clients, &config)
+        .await
+        .map_err(|e| {
+            rest_error!("(add_realm) Error: {}.", e);
+            seed_error_response(e)
+        })?;
+
+    let mut saga = Saga::for_run(run_id);
+    let result = seed::scenario::apply(&grpc_clients, &scenario, &mut saga).await;
+    let ids = saga.finish(result).await.map_err(|e| {
+        rest_error!("(add_realm) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(ScenarioResponse { ids }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::header::HeaderValue;
+
+    #[tokio::test]
+    async fn test_get_format() {
+        crate::get_log_handle().await;
+        ut_info!("(test_get_format) Start.");
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(get_format(&headers), ScenarioFormat::Yaml);
+
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        assert_eq!(get_format(&headers), ScenarioFormat::Json);
+
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/yaml"));
+        assert_eq!(get_format(&headers), ScenarioFormat::Yaml);
+
+        ut_info!("(test_get_format) Success.");
+    }
+}
@@ -0,0 +1,164 @@
+//! Demo REST API implementations, creating single realm resources or
+//! updating the existing resource with the same natural key
+
+use super::{get_run_id, rest_types::*, seed_error_response};
+use crate::grpc::client::GrpcClients;
+use crate::seed::{self, Saga};
+use axum::{extract::Extension, Json};
+use hyper::{HeaderMap, StatusCode};
+
+/// Add a vertiport to storage and GIS, or update the vertiport with the same label
+#[utoipa::path(
+    put,
+    path = "/demo/upsert/vertiport",
+    tag = "svc-itest",
+    params(
+        ("x-run-id" = Option<String>, Header, description = "The run the resource is created for")
+    ),
+    request_body = AddVertiportRequest,
+    responses(
+        (status = 200, description = "Request successful.", body = UpsertResponse),
+        (status = 400, description = "Invalid request.", body = ErrorResponse),
+        (status = 409, description = "Request conflicts with the current state of the realm.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful, changes have been rolled back.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn upsert_vertiport(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    headers: HeaderMap,
+    Json(payload): Json<AddVertiportRequest>,
+) -> Result<Json<UpsertResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(upsert_vertiport) entry.");
+
+    let run_id = get_run_id(&headers, payload.run_id.as_deref()).map_err(|e| {
+        rest_error!("(upsert_vertiport) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    let mut saga = Saga::for_run(run_id);
+    let result = seed::upsert::upsert_vertiport(&grpc_clients, &payload, &mut saga).await;
+    let response = saga.finish(result).await.map_err(|e| {
+        rest_error!("(upsert_vertiport) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(response))
+}
+
+/// Add a vertipad to storage and GIS, or update the vertipad with the same label at the same vertiport
+#[utoipa::path(
+    put,
+    path = "/demo/upsert/vertipad",
+    tag = "svc-itest",
+    params(
+        ("x-run-id" = Option<String>, Header, description = "The run the resource is created for")
+    ),
+    request_body = AddVertipadRequest,
+    responses(
+        (status = 200, description = "Request successful.", body = UpsertResponse),
+        (status = 400, description = "Invalid request.", body = ErrorResponse),
+        (status = 409, description = "Request conflicts with the current state of the realm.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful, changes have been rolled back.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn upsert_vertipad(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    headers: HeaderMap,
+    Json(payload): Json<AddVertipadRequest>,
+) -> Result<Json<UpsertResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(upsert_vertipad) entry.");
+
+    let run_id = get_run_id(&headers, payload.run_id.as_deref()).map_err(|e| {
+        rest_error!("(upsert_vertipad) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    let mut saga = Saga::for_run(run_id);
+    let result = seed::upsert::upsert_vertipad(&grpc_clients, &payload, &mut saga).await;
+    let response = saga.finish(result).await.map_err(|e| {
+        rest_error!("(upsert_vertipad) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(response))
+}
+
+/// Add aircraft to storage, or update the aircraft with the same registration number
+#[utoipa::path(
+    put,
+    path = "/demo/upsert/aircraft",
+    tag = "svc-itest",
+    params(
+        ("x-run-id" = Option<String>, Header, description = "The run the resource is created for")
+    ),
+    request_body = AddAircraftRequest,
+    responses(
+        (status = 200, description = "Request successful.", body = UpsertResponse),
+        (status = 400, description = "Invalid request.", body = ErrorResponse),
+        (status = 409, description = "Request conflicts with the current state of the realm.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful, changes have been rolled back.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn upsert_aircraft(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    headers: HeaderMap,
+    Json(payload): Json<AddAircraftRequest>,
+) -> Result<Json<UpsertResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(upsert_aircraft) entry.");
+
+    let run_id = get_run_id(&headers, payload.run_id.as_deref()).map_err(|e| {
+        rest_error!("(upsert_aircraft) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    let mut saga = Saga::for_run(run_id);
+    let result = seed::upsert::upsert_aircraft(&grpc_clients, &payload, &mut saga).await;
+    let response = saga.finish(result).await.map_err(|e| {
+        rest_error!("(upsert_aircraft) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(response))
+}
+
+/// Add a user to storage, or update the user with the same email
+#[utoipa::path(
+    put,
+    path = "/demo/upsert/user",
+    tag = "svc-itest",
+    params(
+        ("x-run-id" = Option<String>, Header, description = "The run the resource is created for")
+    ),
+    request_body = AddUserRequest,
+    responses(
+        (status = 200, description = "Request successful.", body = UpsertResponse),
+        (status = 400, description = "Invalid request.", body = ErrorResponse),
+        (status = 409, description = "Request conflicts with the current state of the realm.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful, changes have been rolled back.", body = ErrorResponse),
+        (status = 503, description = "A dependency is unavailable.", body = ErrorResponse),
+    )
+)]
+pub async fn upsert_user(
+    Extension(grpc_clients): Extension<GrpcClients>,
+    headers: HeaderMap,
+    Json(payload): Json<AddUserRequest>,
+) -> Result<Json<UpsertResponse>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(upsert_user) entry.");
+
+    let run_id = get_run_id(&headers, payload.run_id.as_deref()).map_err(|e| {
+        rest_error!("(upsert_user) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    let mut saga = Saga::for_run(run_id);
+    let result = seed::upsert::upsert_user(&grpc_clients, &payload, &mut saga).await;
+    let response = saga.finish(result).await.map_err(|e| {
+        rest_error!("(upsert_user) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(response))
+}
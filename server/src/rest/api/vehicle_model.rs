@@ -0,0 +1,74 @@
+//! Demo REST API implementations for the vehicle model catalog
+
+use super::{rest_types::*, seed_error_response};
+use crate::seed::vehicle_model;
+use axum::{extract::Path, Json};
+use hyper::StatusCode;
+
+/// List the vehicle models in the catalog
+#[utoipa::path(
+    get,
+    path = "/demo/vehicle_model",
+    tag = "svc-itest",
+    responses(
+        (status = 200, description = "Request successful.", body = [VehicleModel]),
+    )
+)]
+pub async fn list_vehicle_models() -> Json<Vec<VehicleModel>> {
+    rest_debug!("(list_vehicle_models) entry.");
+    Json(vehicle_model::list())
+}
+
+/// Get a vehicle model from the catalog by its name
+#[utoipa::path(
+    get,
+    path = "/demo/vehicle_model/{name}",
+    tag = "svc-itest",
+    params(
+        ("name" = String, Path, description = "The name of the vehicle model")
+    ),
+    responses(
+        (status = 200, description = "Request successful.", body = VehicleModel),
+        (status = 404, description = "Vehicle model not found.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+    )
+)]
+pub async fn get_vehicle_model(
+    Path(name): Path<String>,
+) -> Result<Json<VehicleModel>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(get_vehicle_model) entry [{}].", name);
+
+    let model = vehicle_model::get(&name).map_err(|e| {
+        rest_error!("(get_vehicle_model) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(model))
+}
+
+/// Add a vehicle model to the catalog, replacing the model with the same
+/// name
+#[utoipa::path(
+    put,
+    path = "/demo/vehicle_model",
+    tag = "svc-itest",
+    request_body = VehicleModel,
+    responses(
+        (status = 200, description = "Request successful.", body = VehicleModel),
+        (status = 400, description = "Request body is invalid format.", body = ErrorResponse),
+        (status = 409, description = "The ID is used by another vehicle model.", body = ErrorResponse),
+        (status = 500, description = "Request unsuccessful.", body = ErrorResponse),
+    )
+)]
+pub async fn add_vehicle_model(
+    Json(payload): Json<VehicleModel>,
+) -> Result<Json<VehicleModel>, (StatusCode, Json<ErrorResponse>)> {
+    rest_debug!("(add_vehicle_model) entry [{}].", payload.name);
+
+    let model = vehicle_model::add(payload).map_err(|e| {
+        rest_error!("(add_vehicle_model) Error: {}.", e);
+        seed_error_response(e)
+    })?;
+
+    Ok(Json(model))
+}
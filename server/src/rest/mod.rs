@@ -11,11 +11,59 @@ use utoipa::OpenApi;
 #[derive(OpenApi)]
 #[openapi(
     paths(
-        api::add_vertiport,
-        api::add_vertipad,
-        api::add_aircraft,
-        api::add_user,
-        api::add_scanner
+        api::demo::add_vertiport,
+        api::demo::add_vertipad,
+        api::demo::add_aircraft,
+        api::demo::add_user,
+        api::demo::add_scanner,
+        api::upsert::upsert_vertiport,
+        api::upsert::upsert_vertipad,
+        api::upsert::upsert_aircraft,
+        api::upsert::upsert_user,
+        api::scenario::add_scenario,
+        api::scenario::add_realm,
+        api::geo::import_geojson,
+        api::geo::export_realm,
+        api::query::get_vertiport,
+        api::query::list_vertiports,
+        api::query::get_vertipad,
+        api::query::list_vertipads,
+        api::query::get_aircraft,
+        api::query::list_aircraft,
+        api::query::get_user,
+        api::query::list_users,
+        api::query::get_scanner,
+        api::query::list_scanners,
+        api::lifecycle::update_vertiport,
+        api::lifecycle::delete_vertiport,
+        api::lifecycle::update_vertipad,
+        api::lifecycle::delete_vertipad,
+        api::lifecycle::update_aircraft,
+        api::lifecycle::delete_aircraft,
+        api::lifecycle::update_user,
+        api::lifecycle::delete_user,
+        api::lifecycle::update_scanner,
+        api::lifecycle::delete_scanner,
+        api::lifecycle::delete_run,
+        api::bulk::add_vertiports,
+        api::bulk::add_vertipads,
+        api::bulk::add_aircraft_bulk,
+        api::bulk::add_users,
+        api::bulk::add_scanners,
+        api::vehicle_model::list_vehicle_models,
+        api::vehicle_model::get_vehicle_model,
+        api::vehicle_model::add_vehicle_model,
+        api::health::realm_health,
+        api::health::wait_for_dependencies,
+        api::jobs::add_job,
+        api::jobs::get_job,
+        api::jobs::delete_job,
+        api::jobs::job_events,
+        api::jobs::get_job_report,
+        api::history::list_runs,
+        api::history::get_run,
+        api::history::get_run_report,
+        api::history::compare_runs
     ),
     components(
         schemas(
@@ -24,6 +72,62 @@ use utoipa::OpenApi;
             api::rest_types::AddAircraftRequest,
             api::rest_types::AddUserRequest,
             api::rest_types::AddScannerRequest,
+            api::rest_types::Scenario,
+            api::rest_types::ScenarioVertiport,
+            api::rest_types::ScenarioVertipad,
+            api::rest_types::ScenarioAircraft,
+            api::rest_types::ScenarioUser,
+            api::rest_types::ScenarioScanner,
+            api::rest_types::ScenarioResponse,
+            api::rest_types::RollbackReport,
+            api::rest_types::ErrorResponse,
+            api::rest_types::GisVertiport,
+            api::rest_types::GisWaypoint,
+            api::rest_types::VertiportResponse,
+            api::rest_types::VertipadResponse,
+            api::rest_types::AircraftResponse,
+            api::rest_types::UserResponse,
+            api::rest_types::ScannerResponse,
+            api::rest_types::UpdateVertiportRequest,
+            api::rest_types::UpdateVertipadRequest,
+            api::rest_types::UpdateAircraftRequest,
+            api::rest_types::UpdateUserRequest,
+            api::rest_types::UpdateScannerRequest,
+            api::rest_types::RunTeardownResponse,
+            api::rest_types::UpsertOutcome,
+            api::rest_types::UpsertResponse,
+            api::rest_types::BulkItemResult,
+            api::rest_types::BulkResponse,
+            api::rest_types::VehicleModel,
+            api::rest_types::BoundingBox,
+            api::rest_types::GenerateRealmRequest,
+            api::rest_types::GeoJsonGeometry,
+            api::rest_types::GeoJsonProperties,
+            api::rest_types::GeoJsonFeature,
+            api::rest_types::GeoJsonFeatureCollection,
+            api::rest_types::ExportFormat,
+            api::rest_types::DependencyStatus,
+            api::rest_types::DependencyHealth,
+            api::rest_types::HealthResponse,
+            api::rest_types::ServiceHealth,
+            api::rest_types::RealmHealthResponse,
+            api::rest_types::StepStatus,
+            api::rest_types::StepResult,
+            api::rest_types::SuiteResult,
+            api::rest_types::JobRequest,
+            api::rest_types::JobStatus,
+            api::rest_types::JobProgress,
+            api::rest_types::JobResult,
+            api::rest_types::JobResponse,
+            api::rest_types::CreatedResource,
+            api::rest_types::ReportFormat,
+            api::rest_types::ReportSummary,
+            api::rest_types::StepReport,
+            api::rest_types::SuiteReport,
+            api::rest_types::RunOverview,
+            api::rest_types::StepChange,
+            api::rest_types::StepComparison,
+            api::rest_types::RunComparison,
         )
     ),
     tags(
@@ -2,6 +2,7 @@
 
 use super::api;
 use crate::grpc::client::GrpcClients;
+use crate::jobs::Jobs;
 use crate::shutdown_signal;
 use crate::Config;
 use axum::{
@@ -72,17 +73,130 @@ pub async fn rest_server(
     //
     // GRPC Clients
     let grpc_clients = GrpcClients::default(config.clone());
+    // Background jobs, run on the runtime of the server
+    let jobs = Jobs::default();
 
     //
     // Create Server
     //
     let app = Router::new()
-        .route("/health", routing::get(api::health_check)) // MUST HAVE
-        .route("/demo/vertiport", routing::put(api::add_vertiport))
-        .route("/demo/vertipad", routing::put(api::add_vertipad))
-        .route("/demo/aircraft", routing::put(api::add_aircraft))
-        .route("/demo/user", routing::put(api::add_user))
-        .route("/demo/scanner", routing::put(api::add_scanner))
+        .route("/health", routing::get(api::health::health_check)) // MUST HAVE
+        .route("/health/realm", routing::get(api::health::realm_health))
+        .route(
+            "/health/wait",
+            routing::get(api::health::wait_for_dependencies),
+        )
+        .route(
+            "/demo/vertiport",
+            routing::put(api::demo::add_vertiport).get(api::query::list_vertiports),
+        )
+        .route(
+            "/demo/vertiport/:id",
+            routing::get(api::query::get_vertiport)
+                .patch(api::lifecycle::update_vertiport)
+                .delete(api::lifecycle::delete_vertiport),
+        )
+        .route(
+            "/demo/vertipad",
+            routing::put(api::demo::add_vertipad).get(api::query::list_vertipads),
+        )
+        .route(
+            "/demo/vertipad/:id",
+            routing::get(api::query::get_vertipad)
+                .patch(api::lifecycle::update_vertipad)
+                .delete(api::lifecycle::delete_vertipad),
+        )
+        .route(
+            "/demo/aircraft",
+            routing::put(api::demo::add_aircraft).get(api::query::list_aircraft),
+        )
+        .route(
+            "/demo/aircraft/:id",
+            routing::get(api::query::get_aircraft)
+                .patch(api::lifecycle::update_aircraft)
+                .delete(api::lifecycle::delete_aircraft),
+        )
+        .route(
+            "/demo/user",
+            routing::put(api::demo::add_user).get(api::query::list_users),
+        )
+        .route(
+            "/demo/user/:id",
+            routing::get(api::query::get_user)
+                .patch(api::lifecycle::update_user)
+                .delete(api::lifecycle::delete_user),
+        )
+        .route(
+            "/demo/scanner",
+            routing::put(api::demo::add_scanner).get(api::query::list_scanners),
+        )
+        .route(
+            "/demo/scanner/:id",
+            routing::get(api::query::get_scanner)
+                .patch(api::lifecycle::update_scanner)
+                .delete(api::lifecycle::delete_scanner),
+        )
+        .route("/demo/scenario", routing::put(api::scenario::add_scenario))
+        .route("/demo/realm", routing::put(api::scenario::add_realm))
+        .route("/demo/geojson", routing::put(api::geo::import_geojson))
+        .route("/demo/export", routing::get(api::geo::export_realm))
+        .route(
+            "/demo/run/:run_id",
+            routing::delete(api::lifecycle::delete_run),
+        )
+        .route(
+            "/demo/upsert/vertiport",
+            routing::put(api::upsert::upsert_vertiport),
+        )
+        .route(
+            "/demo/upsert/vertipad",
+            routing::put(api::upsert::upsert_vertipad),
+        )
+        .route(
+            "/demo/upsert/aircraft",
+            routing::put(api::upsert::upsert_aircraft),
+        )
+        .route("/demo/upsert/user", routing::put(api::upsert::upsert_user))
+        .route(
+            "/demo/bulk/vertiport",
+            routing::put(api::bulk::add_vertiports),
+        )
+        .route(
+            "/demo/bulk/vertipad",
+            routing::put(api::bulk::add_vertipads),
+        )
+        .route(
+            "/demo/bulk/aircraft",
+            routing::put(api::bulk::add_aircraft_bulk),
+        )
+        .route("/demo/bulk/user", routing::put(api::bulk::add_users))
+        .route("/demo/bulk/scanner", routing::put(api::bulk::add_scanners))
+        .route(
+            "/demo/vehicle_model",
+            routing::put(api::vehicle_model::add_vehicle_model)
+                .get(api::vehicle_model::list_vehicle_models),
+        )
+        .route(
+            "/demo/vehicle_model/:name",
+            routing::get(api::vehicle_model::get_vehicle_model),
+        )
+        .route("/jobs", routing::post(api::jobs::add_job))
+        .route(
+            "/jobs/:id",
+            routing::get(api::jobs::get_job).delete(api::jobs::delete_job),
+        )
+        .route("/jobs/:id/events", routing::get(api::jobs::job_events))
+        .route("/jobs/:id/report", routing::get(api::jobs::get_job_report))
+        .route("/runs", routing::get(api::history::list_runs))
+        .route("/runs/:run_id", routing::get(api::history::get_run))
+        .route(
+            "/runs/:run_id/report",
+            routing::get(api::history::get_run_report),
+        )
+        .route(
+            "/runs/:run_id/compare/:other_id",
+            routing::get(api::history::compare_runs),
+        )
         .layer(
             CorsLayer::new()
                 .allow_origin(cors_allowed_origin)
@@ -90,14 +204,21 @@ pub async fn rest_server(
                 .allow_methods(Any),
         )
         .layer(limit_middleware)
+        .layer(Extension(config))
+        .layer(Extension(jobs.clone()))
         .layer(Extension(grpc_clients)); // Extension layer must be last
 
     //
     // Bind to address
     //
+    // Unfinished jobs are cancelled on shutdown. The server keeps serving
+    // until they rolled back, so their clients see them finish.
     match axum::Server::bind(&full_rest_addr)
         .serve(app.into_make_service())
-        .with_graceful_shutdown(shutdown_signal("rest", shutdown_rx))
+        .with_graceful_shutdown(async {
+            shutdown_signal("rest", shutdown_rx).await;
+            jobs.shutdown().await;
+        })
         .await
     {
         Ok(_) => {
@@ -0,0 +1,290 @@
+//! Calls of realm services made by the steps of a suite
+//!
+//! Calls go through the configured [`GrpcClients`]. Requests and responses
+//! are JSON documents, so they can be captured and checked. Only the calls
+//! listed in [`METHODS`] are supported.
+
+use crate::grpc::client::GrpcClients;
+use crate::rest::api::rest_types::{GisVertiport, GisWaypoint};
+use crate::seed::{gis_view, query, SeedError};
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use svc_gis_client_grpc::client::{
+    Coordinates, UpdateVertiportsRequest, UpdateWaypointsRequest, Vertiport, Waypoint,
+};
+use svc_gis_client_grpc::prelude::GisServiceClient;
+use svc_storage_client_grpc::prelude::{ReadyRequest, SimpleClient};
+
+/// The calls steps can make, as `(service, method)`
+pub const METHODS: [(&str, &str); 14] = [
+    ("svc-storage", "is_ready"),
+    ("svc-storage", "get_vertiport"),
+    ("svc-storage", "list_vertiports"),
+    ("svc-storage", "get_vertipad"),
+    ("svc-storage", "list_vertipads"),
+    ("svc-storage", "get_aircraft"),
+    ("svc-storage", "list_aircraft"),
+    ("svc-storage", "get_user"),
+    ("svc-storage", "list_users"),
+    ("svc-storage", "get_scanner"),
+    ("svc-storage", "list_scanners"),
+    ("svc-gis", "is_ready"),
+    ("svc-gis", "update_vertiports"),
+    ("svc-gis", "update_waypoints"),
+];
+
+/// Arguments of the get methods
+#[derive(Debug, serde::Deserialize)]
+struct IdRequest {
+    id: String,
+}
+
+/// A vertiport sent to svc-gis
+#[derive(Debug, serde::Deserialize)]
+struct VertiportArgument {
+    identifier: String,
+    #[serde(default)]
+    label: Option<String>,
+    vertices: Vec<(f64, f64)>,
+    #[serde(default)]
+    altitude_meters: f32,
+}
+
+/// Arguments of `update_vertiports`
+#[derive(Debug, serde::Deserialize)]
+struct VertiportsRequest {
+    vertiports: Vec<VertiportArgument>,
+}
+
+/// A waypoint sent to svc-gis
+#[derive(Debug, serde::Deserialize)]
+struct WaypointArgument {
+    identifier: String,
+    latitude: f64,
+    longitude: f64,
+}
+
+/// Arguments of `update_waypoints`
+#[derive(Debug, serde::Deserialize)]
+struct WaypointsRequest {
+    waypoints: Vec<WaypointArgument>,
+}
+
+/// Converts the request document to the arguments of a method. A missing
+/// request is read as an empty object.
+fn arguments<T: DeserializeOwned>(request: &Value) -> Result<T, String> {
+    let request = match request {
+        Value::Null => Value::Object(Default::default()),
+        request => request.clone(),
+    };
+    serde_json::from_value(request).map_err(|e| format!("invalid request: {}", e))
+}
+
+/// Converts the result of a method to a response document
+fn response<T: Serialize>(result: Result<T, SeedError>) -> Result<Value, String> {
+    let response = result.map_err(|e| e.to_string())?;
+    serde_json::to_value(response).map_err(|e| format!("invalid response: {}", e))
+}
+
+/// Calls a method of a realm service, returns the response document
+pub async fn call(
+    grpc_clients: &GrpcClients,
+    service: &str,
+    method: &str,
+    request: &Value,
+) -> Result<Value, String> {
+    match (service, method) {
+        ("svc-storage", "is_ready") => {
+            let ready = grpc_clients
+                .storage
+                .vertiport
+                .is_ready(ReadyRequest {})
+                .await
+                .map_err(|e| e.message().to_string())?
+                .into_inner()
+                .ready;
+            Ok(serde_json::json!({ "ready": ready }))
+        }
+        ("svc-gis", "is_ready") => {
+            let ready = grpc_clients
+                .gis
+                .is_ready(svc_gis_client_grpc::client::ReadyRequest {})
+                .await
+                .map_err(|e| e.message().to_string())?
+                .into_inner()
+                .ready;
+            Ok(serde_json::json!({ "ready": ready }))
+        }
+        ("svc-gis", "update_vertiports") => {
+            let VertiportsRequest { vertiports } = arguments(request)?;
+            let timestamp = Utc::now();
+            let updated = grpc_clients
+                .gis
+                .update_vertiports(UpdateVertiportsRequest {
+                    vertiports: vertiports
+                        .iter()
+                        .map(|vertiport| Vertiport {
+                            identifier: vertiport.identifier.clone(),
+                            label: vertiport.label.clone(),
+                            vertices: vertiport
+                                .vertices
+                                .iter()
+                                .map(|vx| Coordinates {
+                                    latitude: vx.0,
+                                    longitude: vx.1,
+                                })
+                                .collect(),
+                            altitude_meters: vertiport.altitude_meters,
+                            timestamp_network: Some(timestamp.into()),
+                        })
+                        .collect(),
+                })
+                .await
+                .map_err(|e| e.message().to_string())?
+                .into_inner()
+                .updated;
+
+            for vertiport in vertiports {
+                gis_view::record_vertiport(GisVertiport {
+                    identifier: vertiport.identifier,
+                    label: vertiport.label,
+                    vertices: vertiport.vertices,
+                    timestamp,
+                });
+            }
+            Ok(serde_json::json!({ "updated": updated }))
+        }
+        ("svc-gis", "update_waypoints") => {
+            let WaypointsRequest { waypoints } = arguments(request)?;
+            let updated = grpc_clients
+                .gis
+                .update_waypoints(UpdateWaypointsRequest {
+                    waypoints: waypoints
+                        .iter()
+                        .map(|waypoint| Waypoint {
+                            identifier: waypoint.identifier.clone(),
+                            location: Some(Coordinates {
+                                latitude: waypoint.latitude,
+                                longitude: waypoint.longitude,
+                            }),
+                        })
+                        .collect(),
+                })
+                .await
+                .map_err(|e| e.message().to_string())?
+                .into_inner()
+                .updated;
+
+            let timestamp = Utc::now();
+            for waypoint in waypoints {
+                gis_view::record_waypoint(GisWaypoint {
+                    identifier: waypoint.identifier,
+                    latitude: waypoint.latitude,
+                    longitude: waypoint.longitude,
+                    timestamp,
+                });
+            }
+            Ok(serde_json::json!({ "updated": updated }))
+        }
+        ("svc-storage", "get_vertiport") => {
+            let IdRequest { id } = arguments(request)?;
+            response(query::get_vertiport(grpc_clients, &id).await)
+        }
+        ("svc-storage", "list_vertiports") => {
+            response(query::list_vertiports(grpc_clients, &arguments(request)?).await)
+        }
+        ("svc-storage", "get_vertipad") => {
+            let IdRequest { id } = arguments(request)?;
+            response(query::get_vertipad(grpc_clients, &id).await)
+        }
+        ("svc-storage", "list_vertipads") => {
+            response(query::list_vertipads(grpc_clients, &arguments(request)?).await)
+        }
+        ("svc-storage", "get_aircraft") => {
+            let IdRequest { id } = arguments(request)?;
+            response(query::get_aircraft(grpc_clients, &id).await)
+        }
+        ("svc-storage", "list_aircraft") => {
+            response(query::list_aircraft(grpc_clients, &arguments(request)?).await)
+        }
+        ("svc-storage", "get_user") => {
+            let IdRequest { id } = arguments(request)?;
+            response(query::get_user(grpc_clients, &id).await)
+        }
+        ("svc-storage", "list_users") => {
+            response(query::list_users(grpc_clients, &arguments(request)?).await)
+        }
+        ("svc-storage", "get_scanner") => {
+            let IdRequest { id } = arguments(request)?;
+            response(query::get_scanner(grpc_clients, &id).await)
+        }
+        ("svc-storage", "list_scanners") => {
+            response(query::list_scanners(grpc_clients, &arguments(request)?).await)
+        }
+        (service, method) => Err(format!("unsupported call [{}] [{}]", service, method)),
+    }
+}
+
+/// Returns the storage state of a resource, as returned by the matching
+/// get method
+pub async fn get_resource(
+    grpc_clients: &GrpcClients,
+    resource: &str,
+    id: &str,
+) -> Result<Value, String> {
+    let method = format!("get_{}", resource);
+    if !METHODS.contains(&("svc-storage", method.as_str())) {
+        return Err(format!("unsupported resource [{}]", resource));
+    }
+
+    call(
+        grpc_clients,
+        "svc-storage",
+        &method,
+        &serde_json::json!({ "id": id }),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::api::rest_types::VertipadFilter;
+
+    #[tokio::test]
+    async fn test_arguments() {
+        crate::get_log_handle().await;
+        ut_info!("(test_arguments) Start.");
+
+        let filter: VertipadFilter = arguments(&Value::Null).unwrap();
+        assert!(filter.vertiport_id.is_none());
+
+        let filter: VertipadFilter =
+            arguments(&serde_json::json!({ "vertiport_id": "port" })).unwrap();
+        assert_eq!(filter.vertiport_id.as_deref(), Some("port"));
+
+        assert!(arguments::<IdRequest>(&serde_json::json!({})).is_err());
+
+        let request: WaypointsRequest = arguments(&serde_json::json!({
+            "waypoints": [{ "identifier": "pad", "latitude": 52.0, "longitude": 4.0 }]
+        }))
+        .unwrap();
+        assert_eq!(request.waypoints[0].identifier, "pad");
+        assert!(arguments::<VertiportsRequest>(&Value::Null).is_err());
+
+        let config = crate::Config::default();
+        let grpc_clients = GrpcClients::default(config);
+        assert!(
+            call(&grpc_clients, "svc-pricing", "get_pricing", &Value::Null)
+                .await
+                .is_err()
+        );
+        assert!(get_resource(&grpc_clients, "flight_plan", "id")
+            .await
+            .is_err());
+
+        ut_info!("(test_arguments) Success.");
+    }
+}
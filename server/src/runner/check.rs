@@ -0,0 +1,192 @@
+//! Evaluation of the checks of assert steps and `${name}` references
+
+use super::suite::{Check, Condition};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Replaces all `${name}` references in the strings of the value with the
+/// ID of the seeded resource with that name. Unknown names are left as is.
+pub fn substitute(value: &Value, ids: &BTreeMap<String, String>) -> Value {
+    match value {
+        Value::String(text) => Value::String(substitute_str(text, ids)),
+        Value::Array(items) => Value::Array(items.iter().map(|v| substitute(v, ids)).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, v)| (key.clone(), substitute(v, ids)))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+/// Replaces all `${name}` references in the text
+pub fn substitute_str(text: &str, ids: &BTreeMap<String, String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 2..start + end];
+        result.push_str(&rest[..start]);
+        match ids.get(name) {
+            Some(id) => result.push_str(id),
+            None => result.push_str(&rest[start..=start + end]),
+        }
+        rest = &rest[start + end + 1..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Replaces all `${name}` references in the path and expected value of a
+/// check
+pub fn substitute_check(check: &Check, ids: &BTreeMap<String, String>) -> Check {
+    let condition = match &check.condition {
+        Condition::Equals(value) => Condition::Equals(substitute(value, ids)),
+        Condition::NotEquals(value) => Condition::NotEquals(substitute(value, ids)),
+        Condition::Contains(value) => Condition::Contains(substitute(value, ids)),
+        condition => condition.clone(),
+    };
+
+    Check {
+        path: substitute_str(&check.path, ids),
+        condition,
+    }
+}
+
+/// Returns the length of a string, array or object
+fn length(value: &Value) -> Option<usize> {
+    match value {
+        Value::String(text) => Some(text.chars().count()),
+        Value::Array(items) => Some(items.len()),
+        Value::Object(fields) => Some(fields.len()),
+        _ => None,
+    }
+}
+
+/// Evaluates a check on the document, returns why it failed
+pub fn evaluate(check: &Check, document: &Value) -> Result<(), String> {
+    let value = document.pointer(&check.path);
+    let path = match check.path.as_str() {
+        "" => "(document)",
+        path => path,
+    };
+
+    match (&check.condition, value) {
+        (Condition::Exists(true), Some(_)) | (Condition::Exists(false), None) => Ok(()),
+        (Condition::Exists(true), None) => Err(format!("{}: expected a value, found none", path)),
+        (Condition::Exists(false), Some(value)) => {
+            Err(format!("{}: expected no value, found {}", path, value))
+        }
+        (_, None) => Err(format!("{}: no value", path)),
+        (Condition::Equals(expected), Some(value)) => match value == expected {
+            true => Ok(()),
+            false => Err(format!("{}: expected {}, found {}", path, expected, value)),
+        },
+        (Condition::NotEquals(unexpected), Some(value)) => match value != unexpected {
+            true => Ok(()),
+            false => Err(format!("{}: expected a value other than {}", path, value)),
+        },
+        (Condition::Contains(expected), Some(value)) => {
+            let contains = match (value, expected) {
+                (Value::String(text), Value::String(part)) => text.contains(part.as_str()),
+                (Value::Array(items), expected) => items.contains(expected),
+                _ => false,
+            };
+            match contains {
+                true => Ok(()),
+                false => Err(format!(
+                    "{}: expected {} to contain {}",
+                    path, value, expected
+                )),
+            }
+        }
+        (Condition::Length(expected), Some(value)) => match length(value) {
+            Some(length) if length == *expected => Ok(()),
+            Some(length) => Err(format!(
+                "{}: expected length {}, found {}",
+                path, expected, length
+            )),
+            None => Err(format!("{}: {} has no length", path, value)),
+        },
+    }
+}
+
+/// Evaluates all checks on the document, returns the reasons of all
+/// failed checks
+pub fn evaluate_all(checks: &[Check], document: &Value) -> Result<(), String> {
+    let failures: Vec<String> = checks
+        .iter()
+        .filter_map(|check| evaluate(check, document).err())
+        .collect();
+
+    match failures.is_empty() {
+        true => Ok(()),
+        false => Err(failures.join("; ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn check(path: &str, condition: Condition) -> Check {
+        Check {
+            path: path.to_string(),
+            condition,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_substitute() {
+        crate::get_log_handle().await;
+        ut_info!("(test_substitute) Start.");
+
+        let ids = BTreeMap::from([("port_a".to_string(), "1234".to_string())]);
+        assert_eq!(
+            substitute(
+                &json!({ "id": "${port_a}", "path": ["/ports/${port_a}/${pad_a}", 3] }),
+                &ids
+            ),
+            json!({ "id": "1234", "path": ["/ports/1234/${pad_a}", 3] })
+        );
+        assert_eq!(substitute_str("${port_a", &ids), "${port_a");
+
+        ut_info!("(test_substitute) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_evaluate() {
+        crate::get_log_handle().await;
+        ut_info!("(test_evaluate) Start.");
+
+        let document = json!({ "label": "Port A", "vertices": [[1, 2], [3, 4]], "schedule": null });
+        let passing = [
+            check("/label", Condition::Equals(json!("Port A"))),
+            check("/label", Condition::NotEquals(json!("Port B"))),
+            check("/label", Condition::Contains(json!("Port"))),
+            check("/vertices", Condition::Contains(json!([3, 4]))),
+            check("/vertices", Condition::Length(2)),
+            check("/schedule", Condition::Exists(true)),
+            check("/missing", Condition::Exists(false)),
+            check("", Condition::Length(3)),
+        ];
+        assert_eq!(evaluate_all(&passing, &document), Ok(()));
+
+        let failing = [
+            check("/label", Condition::Equals(json!("Port B"))),
+            check("/missing", Condition::Length(1)),
+        ];
+        assert_eq!(
+            evaluate_all(&failing, &document),
+            Err("/label: expected \"Port B\", found \"Port A\"; /missing: no value".to_string())
+        );
+
+        ut_info!("(test_evaluate) Success.");
+    }
+}
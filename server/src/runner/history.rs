@@ -0,0 +1,627 @@
+//! History of suite runs, kept in an embedded SQLite database
+//!
+//! Each run is recorded with the definition of the suite it ran and the
+//! result and timing of each step, so results survive restarts and runs can
+//! be compared over time. The database is created at the configured path on
+//! first use. Only the configured number of most recent runs is kept.
+
+use super::report;
+use super::suite::Suite;
+use crate::rest::api::rest_types::{
+    ReportSummary, RunComparison, RunOverview, RunsParams, StepChange, StepComparison, StepResult,
+    StepStatus, SuiteResult,
+};
+use crate::seed::{DependencyError, SeedError};
+use crate::Config;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, types::Type, Connection, OptionalExtension, Row};
+use serde::de::DeserializeOwned;
+use std::path::Path;
+use std::time::Duration;
+
+/// Name of the history database in errors
+const HISTORY: &str = "run history";
+
+/// Number of runs listed if no limit is provided
+pub const DEFAULT_LIMIT: u32 = 20;
+
+/// Upper limit of the number of runs listed
+pub const MAX_LIMIT: u32 = 500;
+
+/// Tables of the history database
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS suites (
+    name        TEXT PRIMARY KEY,
+    description TEXT,
+    updated_at  TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS runs (
+    run_id        TEXT PRIMARY KEY,
+    suite         TEXT NOT NULL REFERENCES suites (name),
+    passed        INTEGER NOT NULL,
+    started_at    TEXT NOT NULL,
+    duration_ms   INTEGER NOT NULL,
+    total_steps   INTEGER NOT NULL,
+    passed_steps  INTEGER NOT NULL,
+    failed_steps  INTEGER NOT NULL,
+    skipped_steps INTEGER NOT NULL,
+    teardown      TEXT,
+    definition    TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS runs_by_start ON runs (started_at);
+CREATE INDEX IF NOT EXISTS runs_by_suite ON runs (suite, started_at);
+CREATE TABLE IF NOT EXISTS steps (
+    run_id      TEXT NOT NULL REFERENCES runs (run_id) ON DELETE CASCADE,
+    position    INTEGER NOT NULL,
+    name        TEXT NOT NULL,
+    kind        TEXT NOT NULL,
+    status      TEXT NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    request     TEXT,
+    response    TEXT,
+    error       TEXT,
+    created     TEXT NOT NULL,
+    PRIMARY KEY (run_id, position)
+);
+";
+
+/// Columns of the runs table read into a [`RunOverview`]
+const RUN_COLUMNS: &str = "run_id, suite, passed, started_at, duration_ms, total_steps, passed_steps, failed_steps, skipped_steps";
+
+/// Maps a database error to the error of a failed dependency
+fn database_error(e: impl std::fmt::Display) -> SeedError {
+    SeedError::Dependency(DependencyError {
+        dependency: HISTORY.to_string(),
+        code: tonic::Code::Internal,
+        message: e.to_string(),
+        field: None,
+    })
+}
+
+/// Formats a timestamp so the stored texts sort in time order
+fn timestamp(time: &DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+/// Reads a stored timestamp
+fn get_timestamp(row: &Row, index: usize) -> rusqlite::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&row.get::<_, String>(index)?)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(e)))
+}
+
+/// Reads a stored JSON document
+fn get_json<T: DeserializeOwned>(row: &Row, index: usize) -> rusqlite::Result<Option<T>> {
+    row.get::<_, Option<String>>(index)?
+        .map(|text| serde_json::from_str(&text))
+        .transpose()
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(e)))
+}
+
+/// Serializes a value to store as JSON document
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, SeedError> {
+    serde_json::to_string(value).map_err(database_error)
+}
+
+/// Returns the text stored for a step status
+fn status_text(status: StepStatus) -> &'static str {
+    match status {
+        StepStatus::Passed => "passed",
+        StepStatus::Failed => "failed",
+        StepStatus::Skipped => "skipped",
+    }
+}
+
+/// Parses a stored step status
+fn parse_status(text: &str) -> StepStatus {
+    match text {
+        "passed" => StepStatus::Passed,
+        "failed" => StepStatus::Failed,
+        _ => StepStatus::Skipped,
+    }
+}
+
+/// Opens the history database, creating it if needed
+pub fn open(path: impl AsRef<Path>) -> Result<Connection, SeedError> {
+    let path = path.as_ref();
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(database_error)?;
+    }
+
+    let connection = Connection::open(path).map_err(database_error)?;
+    connection
+        .busy_timeout(Duration::from_secs(5))
+        .map_err(database_error)?;
+    connection
+        .execute_batch("PRAGMA foreign_keys = ON;")
+        .map_err(database_error)?;
+    connection.execute_batch(SCHEMA).map_err(database_error)?;
+    Ok(connection)
+}
+
+/// Records the result of a run with the definition of the suite it ran.
+/// Keeps the `max_runs` most recent runs, or all runs if zero.
+pub fn record(
+    connection: &mut Connection,
+    suite: &Suite,
+    result: &SuiteResult,
+    max_runs: u32,
+) -> Result<(), SeedError> {
+    let summary = report::summary(result);
+    let transaction = connection.transaction().map_err(database_error)?;
+
+    transaction
+        .execute(
+            "INSERT INTO suites (name, description, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT (name) DO UPDATE SET description = ?2, updated_at = ?3",
+            params![suite.name, suite.description, timestamp(&Utc::now())],
+        )
+        .map_err(database_error)?;
+
+    transaction
+        .execute("DELETE FROM runs WHERE run_id = ?1", params![result.run_id])
+        .map_err(database_error)?;
+    transaction
+        .execute(
+            &format!(
+                "INSERT INTO runs ({}, teardown, definition) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                RUN_COLUMNS
+            ),
+            params![
+                result.run_id,
+                result.suite,
+                result.passed,
+                timestamp(&result.started_at),
+                result.duration_ms,
+                summary.total,
+                summary.passed,
+                summary.failed,
+                summary.skipped,
+                result.teardown.as_ref().map(to_json).transpose()?,
+                to_json(suite)?
+            ],
+        )
+        .map_err(database_error)?;
+
+    for (position, step) in result.steps.iter().enumerate() {
+        transaction
+            .execute(
+                "INSERT INTO steps (run_id, position, name, kind, status, duration_ms, request, response, error, created)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    result.run_id,
+                    position,
+                    step.name,
+                    step.kind,
+                    status_text(step.status),
+                    step.duration_ms,
+                    step.request.as_ref().map(to_json).transpose()?,
+                    step.response.as_ref().map(to_json).transpose()?,
+                    step.error,
+                    to_json(&step.created)?
+                ],
+            )
+            .map_err(database_error)?;
+    }
+
+    if max_runs > 0 {
+        prune(&transaction, max_runs)?;
+    }
+
+    transaction.commit().map_err(database_error)
+}
+
+/// Removes all but the `max_runs` most recent runs, with their steps, and
+/// the suites left without runs
+fn prune(connection: &Connection, max_runs: u32) -> Result<(), SeedError> {
+    let removed = connection
+        .execute(
+            "DELETE FROM runs WHERE run_id IN (
+                 SELECT run_id FROM runs ORDER BY started_at DESC, run_id DESC LIMIT -1 OFFSET ?1
+             )",
+            params![max_runs],
+        )
+        .map_err(database_error)?;
+    if removed == 0 {
+        return Ok(());
+    }
+
+    connection
+        .execute(
+            "DELETE FROM suites WHERE name NOT IN (SELECT suite FROM runs)",
+            [],
+        )
+        .map_err(database_error)?;
+    runner_debug!("(prune) removed {} runs from the history.", removed);
+    Ok(())
+}
+
+/// Returns the definition of the suite as it was when the run started
+pub fn get_definition(connection: &Connection, run_id: &str) -> Result<Suite, SeedError> {
+    connection
+        .query_row(
+            "SELECT definition FROM runs WHERE run_id = ?1",
+            params![run_id],
+            |row| get_json(row, 0),
+        )
+        .optional()
+        .map_err(database_error)?
+        .flatten()
+        .ok_or_else(|| SeedError::NotFound(format!("run [{}]", run_id)))
+}
+
+/// Reads a row of [`RUN_COLUMNS`]
+fn overview(row: &Row) -> rusqlite::Result<RunOverview> {
+    Ok(RunOverview {
+        run_id: row.get(0)?,
+        suite: row.get(1)?,
+        passed: row.get(2)?,
+        started_at: get_timestamp(row, 3)?,
+        duration_ms: row.get(4)?,
+        summary: ReportSummary {
+            total: row.get(5)?,
+            passed: row.get(6)?,
+            failed: row.get(7)?,
+            skipped: row.get(8)?,
+        },
+    })
+}
+
+/// Lists the most recent runs matching the filters, newest first
+pub fn list_runs(
+    connection: &Connection,
+    params: &RunsParams,
+) -> Result<Vec<RunOverview>, SeedError> {
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let mut statement = connection
+        .prepare(&format!(
+            "SELECT {} FROM runs
+             WHERE (?1 IS NULL OR suite = ?1) AND (?2 IS NULL OR passed = ?2)
+             ORDER BY started_at DESC LIMIT ?3",
+            RUN_COLUMNS
+        ))
+        .map_err(database_error)?;
+
+    let runs = statement
+        .query_map(params![params.suite, params.passed, limit], overview)
+        .map_err(database_error)?
+        .collect::<rusqlite::Result<Vec<RunOverview>>>()
+        .map_err(database_error)?;
+    Ok(runs)
+}
+
+/// Returns the overview of a run result
+fn overview_of(result: &SuiteResult) -> RunOverview {
+    RunOverview {
+        run_id: result.run_id.clone(),
+        suite: result.suite.clone(),
+        passed: result.passed,
+        started_at: result.started_at,
+        duration_ms: result.duration_ms,
+        summary: report::summary(result),
+    }
+}
+
+/// Returns the result of a run, including all its steps
+pub fn get_run(connection: &Connection, run_id: &str) -> Result<SuiteResult, SeedError> {
+    let (run, teardown) = connection
+        .query_row(
+            &format!(
+                "SELECT {}, teardown FROM runs WHERE run_id = ?1",
+                RUN_COLUMNS
+            ),
+            params![run_id],
+            |row| Ok((overview(row)?, get_json(row, 9)?)),
+        )
+        .optional()
+        .map_err(database_error)?
+        .ok_or_else(|| SeedError::NotFound(format!("run [{}]", run_id)))?;
+
+    let mut statement = connection
+        .prepare(
+            "SELECT name, kind, status, duration_ms, request, response, error, created
+             FROM steps WHERE run_id = ?1 ORDER BY position",
+        )
+        .map_err(database_error)?;
+    let steps = statement
+        .query_map(params![run_id], |row| {
+            Ok(StepResult {
+                name: row.get(0)?,
+                kind: row.get(1)?,
+                status: parse_status(&row.get::<_, String>(2)?),
+                duration_ms: row.get(3)?,
+                request: get_json(row, 4)?,
+                response: get_json(row, 5)?,
+                error: row.get(6)?,
+                created: get_json(row, 7)?.unwrap_or_default(),
+            })
+        })
+        .map_err(database_error)?
+        .collect::<rusqlite::Result<Vec<StepResult>>>()
+        .map_err(database_error)?;
+
+    Ok(SuiteResult {
+        suite: run.suite,
+        run_id: run.run_id,
+        passed: run.passed,
+        started_at: run.started_at,
+        duration_ms: run.duration_ms,
+        steps,
+        teardown,
+    })
+}
+
+/// Returns how a step changed from the base run to the other run
+fn change(base: Option<StepStatus>, other: Option<StepStatus>) -> StepChange {
+    match (base, other) {
+        (None, _) => StepChange::Added,
+        (_, None) => StepChange::Removed,
+        (Some(base), Some(other)) if base == other => StepChange::Unchanged,
+        (Some(StepStatus::Failed), Some(StepStatus::Passed)) => StepChange::Fixed,
+        (Some(StepStatus::Passed), Some(StepStatus::Failed)) => StepChange::Broken,
+        _ => StepChange::Changed,
+    }
+}
+
+/// Compares two runs step by step, matching steps by name
+pub fn compare(base: &SuiteResult, other: &SuiteResult) -> Vec<StepComparison> {
+    let find = |result: &SuiteResult, name: &str| -> Option<StepResult> {
+        result.steps.iter().find(|step| step.name == name).cloned()
+    };
+
+    let names = base.steps.iter().map(|step| &step.name).chain(
+        other
+            .steps
+            .iter()
+            .map(|step| &step.name)
+            .filter(|name| find(base, name).is_none()),
+    );
+
+    names
+        .map(|name| {
+            let base_step = find(base, name);
+            let other_step = find(other, name);
+            let base_duration_ms = base_step.as_ref().map(|step| step.duration_ms);
+            let other_duration_ms = other_step.as_ref().map(|step| step.duration_ms);
+
+            StepComparison {
+                name: name.clone(),
+                change: change(
+                    base_step.as_ref().map(|step| step.status),
+                    other_step.as_ref().map(|step| step.status),
+                ),
+                base_status: base_step.as_ref().map(|step| step.status),
+                other_status: other_step.as_ref().map(|step| step.status),
+                base_duration_ms,
+                other_duration_ms,
+                duration_change_ms: base_duration_ms
+                    .zip(other_duration_ms)
+                    .map(|(base, other)| other as i64 - base as i64),
+                error: other_step.and_then(|step| step.error),
+            }
+        })
+        .collect()
+}
+
+/// Returns the step by step comparison of two recorded runs
+pub fn compare_runs(
+    connection: &Connection,
+    base_id: &str,
+    other_id: &str,
+) -> Result<RunComparison, SeedError> {
+    let base = get_run(connection, base_id)?;
+    let other = get_run(connection, other_id)?;
+
+    Ok(RunComparison {
+        base: overview_of(&base),
+        other: overview_of(&other),
+        steps: compare(&base, &other),
+    })
+}
+
+/// Runs a query on the configured history database, off the async runtime
+pub async fn query<T, F>(config: &Config, query: F) -> Result<T, SeedError>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut Connection) -> Result<T, SeedError> + Send + 'static,
+{
+    let path = config.runner_history_db.clone();
+    tokio::task::spawn_blocking(move || query(&mut open(path)?))
+        .await
+        .map_err(database_error)?
+}
+
+/// Records a run in the configured history database. Failures are logged,
+/// they don't affect the run.
+pub async fn save(config: &Config, suite: &Suite, result: &SuiteResult) {
+    let (suite, result) = (suite.clone(), result.clone());
+    let max_runs = config.runner_history_max_runs;
+    match query(config, move |connection| {
+        record(connection, &suite, &result, max_runs)
+    })
+    .await
+    {
+        Ok(()) => runner_debug!("(save) recorded run in the history."),
+        Err(e) => runner_error!("(save) could not record run in the history: {}.", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::api::rest_types::{CreatedResource, RollbackReport};
+    use crate::seed::scenario::ScenarioFormat;
+
+    fn suite() -> Suite {
+        super::super::suite::parse(
+            "name: history\nsteps:\n  - name: pause\n    wait:\n      duration_ms: 1\n",
+            ScenarioFormat::Yaml,
+        )
+        .unwrap()
+    }
+
+    fn step(name: &str, status: StepStatus, duration_ms: u64) -> StepResult {
+        StepResult {
+            name: name.to_string(),
+            kind: "call".to_string(),
+            status,
+            duration_ms,
+            request: Some(serde_json::json!({ "id": "port" })),
+            response: None,
+            error: match status {
+                StepStatus::Failed => Some("failed".to_string()),
+                _ => None,
+            },
+            created: vec![],
+        }
+    }
+
+    fn result(run_id: &str, minutes: i64, steps: Vec<StepResult>) -> SuiteResult {
+        SuiteResult {
+            suite: "history".to_string(),
+            run_id: run_id.to_string(),
+            passed: steps.iter().all(|step| step.status == StepStatus::Passed),
+            started_at: DateTime::parse_from_rfc3339("2024-03-01T12:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc)
+                + chrono::Duration::minutes(minutes),
+            duration_ms: 100,
+            steps,
+            teardown: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record() {
+        crate::get_log_handle().await;
+        ut_info!("(test_record) Start.");
+
+        let path = std::env::temp_dir()
+            .join(format!("test_record_{}", uuid::Uuid::new_v4()))
+            .join("history.db");
+        let mut connection = open(&path).unwrap();
+
+        let mut first = result("run-1", 0, vec![step("read", StepStatus::Passed, 10)]);
+        first.steps[0].created = vec![CreatedResource {
+            kind: "user".to_string(),
+            name: "user_a".to_string(),
+            id: "1".to_string(),
+        }];
+        first.teardown = Some(RollbackReport {
+            complete: true,
+            undone: vec!["delete user 1".to_string()],
+            failed: vec![],
+            not_removable: vec![],
+        });
+        let second = result("run-2", 5, vec![step("read", StepStatus::Failed, 30)]);
+        record(&mut connection, &suite(), &first, 0).unwrap();
+        record(&mut connection, &suite(), &second, 0).unwrap();
+
+        // Recording a run again replaces it
+        record(&mut connection, &suite(), &second, 0).unwrap();
+
+        assert_eq!(get_run(&connection, "run-1").unwrap(), first);
+        assert!(matches!(
+            get_run(&connection, "run-3"),
+            Err(SeedError::NotFound(_))
+        ));
+
+        let runs = list_runs(&connection, &RunsParams::default()).unwrap();
+        let ids: Vec<&str> = runs.iter().map(|run| run.run_id.as_str()).collect();
+        assert_eq!(ids, vec!["run-2", "run-1"]);
+        assert_eq!(runs[0].summary.failed, 1);
+
+        let params = RunsParams {
+            passed: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(list_runs(&connection, &params).unwrap().len(), 1);
+        let params = RunsParams {
+            suite: Some("other".to_string()),
+            ..Default::default()
+        };
+        assert!(list_runs(&connection, &params).unwrap().is_empty());
+
+        let comparison = compare_runs(&connection, "run-1", "run-2").unwrap();
+        assert_eq!(comparison.base.run_id, "run-1");
+        assert_eq!(comparison.steps[0].change, StepChange::Broken);
+        assert_eq!(comparison.steps[0].duration_change_ms, Some(20));
+
+        // Runs keep the definition they ran, even if the suite changed
+        let mut changed = suite();
+        changed.description = Some("changed".to_string());
+        let third = result("run-3", 10, vec![step("read", StepStatus::Passed, 10)]);
+        record(&mut connection, &changed, &third, 0).unwrap();
+        assert!(get_definition(&connection, "run-1")
+            .unwrap()
+            .description
+            .is_none());
+        assert_eq!(
+            get_definition(&connection, "run-3").unwrap().description,
+            Some("changed".to_string())
+        );
+        assert!(matches!(
+            get_definition(&connection, "run-4"),
+            Err(SeedError::NotFound(_))
+        ));
+
+        // Only the most recent runs are kept
+        let fourth = result("run-4", 15, vec![step("read", StepStatus::Passed, 10)]);
+        record(&mut connection, &suite(), &fourth, 2).unwrap();
+        let runs = list_runs(&connection, &RunsParams::default()).unwrap();
+        let ids: Vec<&str> = runs.iter().map(|run| run.run_id.as_str()).collect();
+        assert_eq!(ids, vec!["run-4", "run-3"]);
+        let steps: u32 = connection
+            .query_row("SELECT COUNT(*) FROM steps", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(steps, 2);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        ut_info!("(test_record) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_compare() {
+        crate::get_log_handle().await;
+        ut_info!("(test_compare) Start.");
+
+        let base = result(
+            "base",
+            0,
+            vec![
+                step("seed", StepStatus::Passed, 10),
+                step("read", StepStatus::Failed, 10),
+                step("check", StepStatus::Skipped, 0),
+                step("old", StepStatus::Passed, 10),
+            ],
+        );
+        let other = result(
+            "other",
+            1,
+            vec![
+                step("seed", StepStatus::Passed, 5),
+                step("read", StepStatus::Passed, 10),
+                step("check", StepStatus::Failed, 10),
+                step("new", StepStatus::Passed, 10),
+            ],
+        );
+
+        let changes: Vec<(String, StepChange)> = compare(&base, &other)
+            .into_iter()
+            .map(|step| (step.name, step.change))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("seed".to_string(), StepChange::Unchanged),
+                ("read".to_string(), StepChange::Fixed),
+                ("check".to_string(), StepChange::Changed),
+                ("old".to_string(), StepChange::Removed),
+                ("new".to_string(), StepChange::Added),
+            ]
+        );
+        assert_eq!(compare(&base, &other)[0].duration_change_ms, Some(-5));
+
+        ut_info!("(test_compare) Success.");
+    }
+}
@@ -0,0 +1,623 @@
+//! End-to-end test suites run against the realm
+//!
+//! Suites are loaded from YAML or JSON files, see [`suite`]. The steps of a
+//! suite run in order against the configured [`GrpcClients`]. Each step
+//! gets a pass or fail result with its timing and the captured request and
+//! response. Once a step failed or the run was cancelled, the remaining
+//! steps are skipped.
+//!
+//! The resources seeded by a suite belong to a run of their own, which is
+//! torn down when the suite finished unless the suite asks otherwise.
+
+#[macro_use]
+pub mod macros;
+pub mod call;
+pub mod check;
+pub mod history;
+pub mod report;
+pub mod suite;
+
+use crate::grpc::client::GrpcClients;
+use crate::grpc::gate;
+use crate::rest::api::rest_types::{CreatedResource, Scenario};
+use crate::seed::{ledger, scenario, Saga};
+use crate::Config;
+use chrono::Utc;
+use log::Level;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use suite::{Action, Assert, Call, Step, Suite, Wait};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+pub use crate::rest::api::rest_types::{StepResult, StepStatus, SuiteResult};
+
+/// Progress of a suite run
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunEvent {
+    /// The run started
+    Started {
+        /// The name of the suite
+        suite: String,
+        /// The run the seeded resources belong to
+        run_id: String,
+    },
+    /// A step started
+    StepStarted {
+        /// The position of the step in the suite
+        index: usize,
+        /// The name of the step
+        name: String,
+        /// The kind of step
+        kind: String,
+    },
+    /// A step finished or was skipped
+    StepFinished {
+        /// The position of the step in the suite
+        index: usize,
+        /// The result of the step
+        result: StepResult,
+    },
+    /// A message worth showing to whoever follows the run
+    Log {
+        /// The severity of the message
+        level: Level,
+        /// The message
+        message: String,
+    },
+    /// The run finished
+    Finished(SuiteResult),
+}
+
+/// State shared by the steps of a suite
+#[derive(Debug, Default)]
+struct Context {
+    /// The run the seeded resources belong to
+    run_id: String,
+    /// The IDs of the seeded resources, by symbolic name
+    ids: BTreeMap<String, String>,
+    /// The response of the last call
+    last_response: Option<Value>,
+    /// Cancels the run
+    cancel: CancellationToken,
+    /// The resources created by the current step
+    created: Vec<CreatedResource>,
+}
+
+/// Why a step failed when the run was cancelled
+const CANCELLED: &str = "run cancelled";
+
+/// Captured request and response of a step, and why it failed
+type Outcome = (Option<Value>, Option<Value>, Result<(), String>);
+
+/// Returns the resources of a scenario which have been created, in order
+/// of creation
+fn created(scenario: &Scenario, ids: &BTreeMap<String, String>) -> Vec<CreatedResource> {
+    let names = scenario
+        .vertiports
+        .iter()
+        .map(|entry| ("vertiport", &entry.name))
+        .chain(
+            scenario
+                .vertipads
+                .iter()
+                .map(|entry| ("vertipad", &entry.name)),
+        )
+        .chain(
+            scenario
+                .aircraft
+                .iter()
+                .map(|entry| ("aircraft", &entry.name)),
+        )
+        .chain(scenario.users.iter().map(|entry| ("user", &entry.name)))
+        .chain(
+            scenario
+                .scanners
+                .iter()
+                .map(|entry| ("scanner", &entry.name)),
+        );
+
+    names
+        .filter_map(|(kind, name)| {
+            ids.get(name).map(|id| CreatedResource {
+                kind: kind.to_string(),
+                name: name.clone(),
+                id: id.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Creates the resources of a scenario for the suite's run
+async fn seed(grpc_clients: &GrpcClients, context: &mut Context, scenario: &Scenario) -> Outcome {
+    let request = match serde_json::to_value(scenario) {
+        Ok(value) => check::substitute(&value, &context.ids),
+        Err(e) => return (None, None, Err(format!("invalid scenario: {}", e))),
+    };
+    let mut scenario: Scenario = match serde_json::from_value(request.clone()) {
+        Ok(scenario) => scenario,
+        Err(e) => return (Some(request), None, Err(format!("invalid scenario: {}", e))),
+    };
+    scenario.run_id = None;
+
+    let mut saga = Saga::for_run(Some(context.run_id.clone()));
+    let result = scenario::apply(grpc_clients, &scenario, &mut saga).await;
+    match saga.finish(result).await {
+        Ok(ids) => {
+            let response = serde_json::json!({ "ids": ids });
+            context.created = created(&scenario, &ids);
+            context.ids.extend(ids);
+            (Some(request), Some(response), Ok(()))
+        }
+        Err(e) => (Some(request), None, Err(e.to_string())),
+    }
+}
+
+/// Calls a realm service, keeping the response for following asserts
+async fn call(grpc_clients: &GrpcClients, context: &mut Context, call: &Call) -> Outcome {
+    let request = check::substitute(&call.request, &context.ids);
+    let result = call::call(grpc_clients, &call.service, &call.method, &request).await;
+    let captured = Some(serde_json::json!({
+        "service": call.service,
+        "method": call.method,
+        "request": request,
+    }));
+
+    match result {
+        Ok(response) => {
+            context.last_response = Some(response.clone());
+            (captured, Some(response), Ok(()))
+        }
+        Err(e) => {
+            context.last_response = None;
+            (captured, None, Err(e))
+        }
+    }
+}
+
+/// Waits for the critical dependencies and the provided duration. The
+/// wait ends early when the run is cancelled.
+async fn wait(
+    grpc_clients: &GrpcClients,
+    config: &Config,
+    context: &Context,
+    wait: &Wait,
+) -> Outcome {
+    let request = serde_json::to_value(wait).ok();
+    let mut response = None;
+
+    if wait.dependencies {
+        let timeout =
+            Duration::from_millis(wait.timeout_ms.unwrap_or(config.readiness_gate_timeout_ms));
+        let result = tokio::select! {
+            result = gate::wait_for_dependencies(grpc_clients, config, timeout) => result,
+            _ = context.cancel.cancelled() => return (request, None, Err(CANCELLED.to_string())),
+        };
+        match result {
+            Ok(dependencies) => {
+                response = serde_json::to_value(dependencies).ok();
+            }
+            Err(e) => {
+                return (
+                    request,
+                    serde_json::to_value(&e.down).ok(),
+                    Err(e.to_string()),
+                )
+            }
+        }
+    }
+
+    if let Some(duration_ms) = wait.duration_ms {
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_millis(duration_ms)) => {},
+            _ = context.cancel.cancelled() => return (request, response, Err(CANCELLED.to_string())),
+        }
+    }
+
+    (request, response, Ok(()))
+}
+
+/// Checks the last response or the storage state of a resource
+async fn assert(grpc_clients: &GrpcClients, context: &Context, assert: &Assert) -> Outcome {
+    let checks: Vec<_> = assert
+        .checks
+        .iter()
+        .map(|check| check::substitute_check(check, &context.ids))
+        .collect();
+
+    let (request, document) = match &assert.storage {
+        Some(storage) => {
+            let resource = check::substitute_str(&storage.resource, &context.ids);
+            let id = check::substitute_str(&storage.id, &context.ids);
+            let request = serde_json::json!({ "resource": resource, "id": id });
+            match call::get_resource(grpc_clients, &resource, &id).await {
+                Ok(document) => (Some(request), document),
+                Err(e) => return (Some(request), None, Err(e)),
+            }
+        }
+        None => match &context.last_response {
+            Some(document) => (None, document.clone()),
+            None => return (None, None, Err("no response to check".to_string())),
+        },
+    };
+
+    let result = check::evaluate_all(&checks, &document);
+    (request, Some(document), result)
+}
+
+/// Runs a single step
+async fn run_step(
+    grpc_clients: &GrpcClients,
+    config: &Config,
+    context: &mut Context,
+    step: &Step,
+) -> StepResult {
+    runner_debug!("(run_step) [{}] {}.", step.action.kind(), step.name);
+    let start = Instant::now();
+
+    let (request, response, result) = match &step.action {
+        Action::Seed(scenario) => seed(grpc_clients, context, scenario).await,
+        Action::Call(c) => call(grpc_clients, context, c).await,
+        Action::Wait(w) => wait(grpc_clients, config, context, w).await,
+        Action::Assert(a) => assert(grpc_clients, context, a).await,
+    };
+
+    let (status, error) = match result {
+        Ok(()) => (StepStatus::Passed, None),
+        Err(e) => {
+            runner_warn!("(run_step) step [{}] failed: {}.", step.name, e);
+            (StepStatus::Failed, Some(e))
+        }
+    };
+
+    StepResult {
+        name: step.name.clone(),
+        kind: step.action.kind().to_string(),
+        status,
+        duration_ms: start.elapsed().as_millis() as u64,
+        request,
+        response,
+        error,
+        created: std::mem::take(&mut context.created),
+    }
+}
+
+/// Returns the result of a step which did not run
+fn skipped(step: &Step) -> StepResult {
+    StepResult {
+        name: step.name.clone(),
+        kind: step.action.kind().to_string(),
+        status: StepStatus::Skipped,
+        duration_ms: 0,
+        request: None,
+        response: None,
+        error: None,
+        created: vec![],
+    }
+}
+
+/// Sends the events of a run to the listener, if any. Events are dropped
+/// once the listener went away, the run itself continues.
+#[derive(Debug, Clone, Default)]
+struct Events(Option<UnboundedSender<RunEvent>>);
+
+impl Events {
+    /// Sends an event
+    fn send(&self, event: RunEvent) {
+        if let Some(sender) = &self.0 {
+            let _ = sender.send(event);
+        }
+    }
+
+    /// Sends a log message
+    fn log(&self, level: Level, message: String) {
+        self.send(RunEvent::Log { level, message });
+    }
+}
+
+/// Runs all steps of a suite in order, then removes the seeded resources.
+/// Progress is reported to `events` while the suite runs.
+///
+/// Cancelling the run through `cancel` ends a running wait step and skips
+/// all following steps. Other steps are not interrupted, so the resources
+/// they seed end up in the teardown.
+pub async fn run(
+    grpc_clients: &GrpcClients,
+    config: &Config,
+    suite: &Suite,
+    events: Option<UnboundedSender<RunEvent>>,
+    cancel: CancellationToken,
+) -> SuiteResult {
+    let events = Events(events);
+    let mut context = Context {
+        run_id: Uuid::new_v4().to_string(),
+        cancel,
+        ..Default::default()
+    };
+    runner_info!(
+        "(run) running suite [{}] as run [{}].",
+        suite.name,
+        context.run_id
+    );
+    events.send(RunEvent::Started {
+        suite: suite.name.clone(),
+        run_id: context.run_id.clone(),
+    });
+
+    let started_at = Utc::now();
+    let start = Instant::now();
+    let mut steps: Vec<StepResult> = vec![];
+    for (index, step) in suite.steps.iter().enumerate() {
+        let result = match context.cancel.is_cancelled()
+            || steps.iter().any(|s| s.status == StepStatus::Failed)
+        {
+            true => skipped(step),
+            false => {
+                events.send(RunEvent::StepStarted {
+                    index,
+                    name: step.name.clone(),
+                    kind: step.action.kind().to_string(),
+                });
+                run_step(grpc_clients, config, &mut context, step).await
+            }
+        };
+
+        if let Some(error) = &result.error {
+            events.log(
+                Level::Warn,
+                format!("step [{}] failed: {}", step.name, error),
+            );
+        }
+        events.send(RunEvent::StepFinished {
+            index,
+            result: result.clone(),
+        });
+        steps.push(result);
+    }
+
+    let teardown = match suite.teardown && ledger::resources(&context.run_id).is_some() {
+        true => match ledger::teardown(grpc_clients, &context.run_id).await {
+            Ok(report) => {
+                for failed in &report.failed {
+                    events.log(Level::Warn, format!("teardown: could not {}", failed));
+                }
+                Some(report)
+            }
+            Err(e) => {
+                runner_error!("(run) could not tear down run [{}]: {}.", context.run_id, e);
+                events.log(
+                    Level::Error,
+                    format!("could not tear down run [{}]: {}", context.run_id, e),
+                );
+                None
+            }
+        },
+        false => None,
+    };
+
+    let passed = steps.iter().all(|s| s.status == StepStatus::Passed);
+    runner_info!(
+        "(run) suite [{}] {}.",
+        suite.name,
+        match passed {
+            true => "passed",
+            false => "failed",
+        }
+    );
+
+    let result = SuiteResult {
+        suite: suite.name.clone(),
+        run_id: context.run_id,
+        passed,
+        started_at,
+        duration_ms: start.elapsed().as_millis() as u64,
+        steps,
+        teardown,
+    };
+    events.send(RunEvent::Finished(result.clone()));
+    result
+}
+
+/// Loads the suites of the configured suite directory. A missing directory
+/// has no suites.
+pub fn load_suites(config: &Config) -> Result<Vec<Suite>, crate::seed::SeedError> {
+    let dir = std::path::Path::new(&config.runner_suite_dir);
+    if !dir.exists() {
+        runner_warn!(
+            "(load_suites) suite directory [{}] not found, no suites loaded.",
+            dir.display()
+        );
+        return Ok(vec![]);
+    }
+
+    suite::load_dir(dir)
+}
+
+/// Loads the suites of the configured suite directory with the provided
+/// names, in the order of the names. The name `all` selects all suites.
+pub fn select_suites(
+    config: &Config,
+    names: &[String],
+) -> Result<Vec<Suite>, crate::seed::SeedError> {
+    let suites = load_suites(config)?;
+    if names.iter().any(|name| name == "all") {
+        return Ok(suites);
+    }
+
+    names
+        .iter()
+        .map(|name| {
+            suites
+                .iter()
+                .find(|suite| &suite.name == name)
+                .cloned()
+                .ok_or_else(|| crate::seed::SeedError::NotFound(format!("suite [{}]", name)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed::scenario::ScenarioFormat;
+
+    #[tokio::test]
+    async fn test_run_skips_after_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(test_run_skips_after_failure) Start.");
+
+        let suite = suite::parse(
+            r#"
+name: failing
+steps:
+  - name: pause
+    wait:
+      duration_ms: 1
+  - name: check nothing
+    assert:
+      checks:
+        - path: /ready
+          equals: true
+  - name: pause again
+    wait:
+      duration_ms: 1
+"#,
+            ScenarioFormat::Yaml,
+        )
+        .unwrap();
+
+        let config = Config::default();
+        let grpc_clients = GrpcClients::default(config.clone());
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let result = run(
+            &grpc_clients,
+            &config,
+            &suite,
+            Some(sender),
+            CancellationToken::new(),
+        )
+        .await;
+
+        assert!(!result.passed);
+        assert!(result.teardown.is_none());
+        let statuses: Vec<StepStatus> = result.steps.iter().map(|s| s.status).collect();
+        assert_eq!(
+            statuses,
+            vec![StepStatus::Passed, StepStatus::Failed, StepStatus::Skipped]
+        );
+        assert_eq!(
+            result.steps[1].error.as_deref(),
+            Some("no response to check")
+        );
+        assert_eq!(
+            result.steps[0].request,
+            Some(
+                serde_json::json!({ "dependencies": false, "timeout_ms": null, "duration_ms": 1 })
+            )
+        );
+
+        let mut events = vec![];
+        while let Ok(event) = receiver.try_recv() {
+            events.push(event);
+        }
+        assert!(matches!(events[0], RunEvent::Started { .. }));
+        assert!(matches!(events[1], RunEvent::StepStarted { index: 0, .. }));
+        assert!(matches!(events[2], RunEvent::StepFinished { index: 0, .. }));
+        assert!(matches!(events[3], RunEvent::StepStarted { index: 1, .. }));
+        assert!(matches!(
+            events[4],
+            RunEvent::Log {
+                level: Level::Warn,
+                ..
+            }
+        ));
+        assert!(matches!(events[5], RunEvent::StepFinished { index: 1, .. }));
+        assert!(matches!(events[6], RunEvent::StepFinished { index: 2, .. }));
+        assert_eq!(events[7], RunEvent::Finished(result));
+
+        ut_info!("(test_run_skips_after_failure) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_run_cancel() {
+        crate::get_log_handle().await;
+        ut_info!("(test_run_cancel) Start.");
+
+        let suite = suite::parse(
+            r#"
+name: cancelled
+steps:
+  - name: long pause
+    wait:
+      duration_ms: 60000
+  - name: pause
+    wait:
+      duration_ms: 1
+"#,
+            ScenarioFormat::Yaml,
+        )
+        .unwrap();
+
+        let config = Config::default();
+        let grpc_clients = GrpcClients::default(config.clone());
+        let cancel = CancellationToken::new();
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            canceller.cancel();
+        });
+
+        let result = run(&grpc_clients, &config, &suite, None, cancel).await;
+        assert!(!result.passed);
+        assert!(result.duration_ms < 60000);
+        assert_eq!(result.steps[0].status, StepStatus::Failed);
+        assert_eq!(result.steps[0].error.as_deref(), Some(CANCELLED));
+        assert_eq!(result.steps[1].status, StepStatus::Skipped);
+
+        ut_info!("(test_run_cancel) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_select_suites() {
+        crate::get_log_handle().await;
+        ut_info!("(test_select_suites) Start.");
+
+        let dir = std::env::temp_dir().join(format!("test_select_suites_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["first", "second"] {
+            std::fs::write(
+                dir.join(format!("{}.yaml", name)),
+                format!(
+                    "name: {}\nsteps:\n  - name: pause\n    wait:\n      duration_ms: 1\n",
+                    name
+                ),
+            )
+            .unwrap();
+        }
+        let config = Config {
+            runner_suite_dir: dir.display().to_string(),
+            ..Config::default()
+        };
+
+        let names = |names: &[&str]| -> Vec<String> {
+            let names: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+            select_suites(&config, &names)
+                .unwrap()
+                .into_iter()
+                .map(|suite| suite.name)
+                .collect()
+        };
+        assert_eq!(names(&["second", "first"]), vec!["second", "first"]);
+        assert_eq!(names(&["all"]), vec!["first", "second"]);
+        assert!(select_suites(&config, &["third".to_string()]).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // A missing suite directory has no suites
+        assert!(load_suites(&config).unwrap().is_empty());
+        assert!(names(&["all"]).is_empty());
+
+        ut_info!("(test_select_suites) Success.");
+    }
+}
@@ -0,0 +1,431 @@
+//! Reports of suite runs as JUnit XML, TAP and JSON
+//!
+//! The JSON report follows the versioned [`SuiteReport`] schema. Reports
+//! can be downloaded through the REST API or written to a directory from
+//! the command line.
+
+use crate::rest::api::rest_types::{
+    CreatedResource, ReportFormat, ReportSummary, StepReport, StepStatus, SuiteReport, SuiteResult,
+};
+use crate::seed::export::escape;
+use crate::seed::SeedError;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// The version of the [`SuiteReport`] schema
+pub const SCHEMA_VERSION: u32 = 1;
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.trim().to_lowercase().as_str() {
+            "junit" | "xml" => Ok(ReportFormat::Junit),
+            "tap" => Ok(ReportFormat::Tap),
+            "json" => Ok(ReportFormat::Json),
+            format => Err(format!(
+                "unknown report format [{}], expected junit, tap or json",
+                format
+            )),
+        }
+    }
+}
+
+/// Returns the file extension of a report format
+pub fn extension(format: ReportFormat) -> &'static str {
+    match format {
+        ReportFormat::Junit => "xml",
+        ReportFormat::Tap => "tap",
+        ReportFormat::Json => "json",
+    }
+}
+
+/// Returns the content type of a report format
+pub fn content_type(format: ReportFormat) -> &'static str {
+    match format {
+        ReportFormat::Junit => "application/xml",
+        ReportFormat::Tap => "text/plain; charset=utf-8",
+        ReportFormat::Json => "application/json",
+    }
+}
+
+/// Counts the steps of a suite run by outcome
+pub fn summary(result: &SuiteResult) -> ReportSummary {
+    let count = |status: StepStatus| {
+        result
+            .steps
+            .iter()
+            .filter(|step| step.status == status)
+            .count() as u32
+    };
+
+    ReportSummary {
+        total: result.steps.len() as u32,
+        passed: count(StepStatus::Passed),
+        failed: count(StepStatus::Failed),
+        skipped: count(StepStatus::Skipped),
+    }
+}
+
+impl From<&SuiteResult> for SuiteReport {
+    fn from(result: &SuiteResult) -> Self {
+        SuiteReport {
+            schema_version: SCHEMA_VERSION,
+            suite: result.suite.clone(),
+            run_id: result.run_id.clone(),
+            passed: result.passed,
+            started_at: result.started_at,
+            duration_ms: result.duration_ms,
+            summary: summary(result),
+            steps: result
+                .steps
+                .iter()
+                .map(|step| StepReport {
+                    name: step.name.clone(),
+                    kind: step.kind.clone(),
+                    status: step.status,
+                    duration_ms: step.duration_ms,
+                    error: step.error.clone(),
+                    created: step.created.clone(),
+                })
+                .collect(),
+            teardown: result.teardown.clone(),
+        }
+    }
+}
+
+/// Formats milliseconds as seconds, as used by JUnit
+fn seconds(duration_ms: u64) -> String {
+    format!("{:.3}", duration_ms as f64 / 1000.0)
+}
+
+/// Describes a created resource on a single line
+fn describe(resource: &CreatedResource) -> String {
+    format!(
+        "created {} {}: {}",
+        resource.kind, resource.name, resource.id
+    )
+}
+
+/// Renders a suite run as JUnit XML, with a test case for each step
+pub fn to_junit(result: &SuiteResult) -> String {
+    let summary = summary(result);
+    let suite = escape(&result.suite);
+    let mut xml = format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<testsuites name=\"svc-itest\" tests=\"{tests}\" failures=\"{failures}\" skipped=\"{skipped}\" time=\"{time}\">\n",
+            "  <testsuite name=\"{suite}\" tests=\"{tests}\" failures=\"{failures}\" skipped=\"{skipped}\" time=\"{time}\" timestamp=\"{timestamp}\">\n",
+            "    <properties>\n",
+            "      <property name=\"run_id\" value=\"{run_id}\"/>\n",
+            "    </properties>\n"
+        ),
+        tests = summary.total,
+        failures = summary.failed,
+        skipped = summary.skipped,
+        time = seconds(result.duration_ms),
+        suite = suite,
+        timestamp = result.started_at.format("%Y-%m-%dT%H:%M:%S"),
+        run_id = escape(&result.run_id),
+    );
+
+    for step in &result.steps {
+        xml.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}.{}\" time=\"{}\">\n",
+            escape(&step.name),
+            suite,
+            escape(&step.kind),
+            seconds(step.duration_ms)
+        ));
+
+        match step.status {
+            StepStatus::Failed => {
+                let error = escape(step.error.as_deref().unwrap_or_default());
+                xml.push_str(&format!(
+                    "      <failure message=\"{}\" type=\"{}\">{}</failure>\n",
+                    error,
+                    escape(&step.kind),
+                    error
+                ));
+            }
+            StepStatus::Skipped => xml.push_str("      <skipped/>\n"),
+            StepStatus::Passed => {}
+        }
+
+        if !step.created.is_empty() {
+            let created: Vec<String> = step.created.iter().map(describe).collect();
+            xml.push_str(&format!(
+                "      <system-out>{}</system-out>\n",
+                escape(&created.join("\n"))
+            ));
+        }
+
+        xml.push_str("    </testcase>\n");
+    }
+
+    if let Some(teardown) = result.teardown.as_ref().filter(|t| !t.complete) {
+        xml.push_str(&format!(
+            "    <system-err>{}</system-err>\n",
+            escape(&format!(
+                "teardown incomplete: {}",
+                teardown.failed.join("; ")
+            ))
+        ));
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// Details of a step, as YAML block of a TAP test line
+#[derive(Serialize)]
+struct Diagnostic<'a> {
+    kind: &'a str,
+    duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<&'a str>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    created: &'a [CreatedResource],
+}
+
+/// Renders a suite run as TAP version 13, with a test line for each step
+pub fn to_tap(result: &SuiteResult) -> String {
+    let mut tap = format!(
+        "TAP version 13\n1..{}\n# suite {}, run {}\n",
+        result.steps.len(),
+        result.suite,
+        result.run_id
+    );
+
+    for (index, step) in result.steps.iter().enumerate() {
+        // '#' starts a directive in TAP
+        let name = step.name.replace('#', "\\#");
+        let line = match step.status {
+            StepStatus::Passed => format!("ok {} - {}", index + 1, name),
+            StepStatus::Failed => format!("not ok {} - {}", index + 1, name),
+            StepStatus::Skipped => format!("ok {} - {} # SKIP", index + 1, name),
+        };
+        tap.push_str(&line);
+        tap.push('\n');
+
+        let diagnostic = Diagnostic {
+            kind: &step.kind,
+            duration_ms: step.duration_ms,
+            message: step.error.as_deref(),
+            created: &step.created,
+        };
+        if let Ok(yaml) = serde_yaml::to_string(&diagnostic) {
+            tap.push_str("  ---\n");
+            for line in yaml.lines() {
+                tap.push_str(&format!("  {}\n", line));
+            }
+            tap.push_str("  ...\n");
+        }
+    }
+
+    if let Some(teardown) = &result.teardown {
+        tap.push_str(&format!(
+            "# teardown: {} removed, {} failed\n",
+            teardown.undone.len(),
+            teardown.failed.len()
+        ));
+    }
+
+    tap
+}
+
+/// Renders a suite run as JSON document of the [`SuiteReport`] schema
+pub fn to_json(result: &SuiteResult) -> String {
+    serde_json::to_string_pretty(&SuiteReport::from(result)).unwrap_or_default()
+}
+
+/// Renders a suite run in the provided format
+pub fn render(result: &SuiteResult, format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Junit => to_junit(result),
+        ReportFormat::Tap => to_tap(result),
+        ReportFormat::Json => to_json(result),
+    }
+}
+
+/// Writes the reports of a suite run to the directory, one file per
+/// format named after the suite. Returns the paths of the written files.
+pub fn write(
+    dir: impl AsRef<Path>,
+    result: &SuiteResult,
+    formats: &[ReportFormat],
+) -> Result<Vec<PathBuf>, SeedError> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir).map_err(|e| {
+        SeedError::InvalidRequest(format!(
+            "could not create report directory [{}]: {}",
+            dir.display(),
+            e
+        ))
+    })?;
+
+    let name: String = result
+        .suite
+        .chars()
+        .map(
+            |c| match c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                true => c,
+                false => '_',
+            },
+        )
+        .collect();
+
+    let mut paths = vec![];
+    for format in formats {
+        let path = dir.join(format!("{}.{}", name, extension(*format)));
+        std::fs::write(&path, render(result, *format)).map_err(|e| {
+            SeedError::InvalidRequest(format!(
+                "could not write report [{}]: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        runner_info!("(write) wrote report [{}].", path.display());
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::api::rest_types::{RollbackReport, StepResult};
+
+    fn step(name: &str, status: StepStatus, error: Option<&str>) -> StepResult {
+        StepResult {
+            name: name.to_string(),
+            kind: "seed".to_string(),
+            status,
+            duration_ms: 1500,
+            request: None,
+            response: None,
+            error: error.map(|e| e.to_string()),
+            created: vec![],
+        }
+    }
+
+    fn result() -> SuiteResult {
+        let mut seed = step("seed <ports>", StepStatus::Passed, None);
+        seed.created = vec![CreatedResource {
+            kind: "vertiport".to_string(),
+            name: "port_a".to_string(),
+            id: "1234".to_string(),
+        }];
+
+        SuiteResult {
+            suite: "roundtrip".to_string(),
+            run_id: "run-1".to_string(),
+            passed: false,
+            started_at: chrono::Utc::now(),
+            duration_ms: 3000,
+            steps: vec![
+                seed,
+                step(
+                    "check #1",
+                    StepStatus::Failed,
+                    Some("/label: \"A\" & \"B\""),
+                ),
+                step("pause", StepStatus::Skipped, None),
+            ],
+            teardown: Some(RollbackReport {
+                complete: true,
+                undone: vec!["delete vertiport 1234".to_string()],
+                failed: vec![],
+                not_removable: vec![],
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_to_junit() {
+        crate::get_log_handle().await;
+        ut_info!("(test_to_junit) Start.");
+
+        let xml = to_junit(&result());
+        assert!(xml.contains(
+            "<testsuite name=\"roundtrip\" tests=\"3\" failures=\"1\" skipped=\"1\" time=\"3.000\""
+        ));
+        assert!(xml.contains(
+            "<testcase name=\"seed &lt;ports&gt;\" classname=\"roundtrip.seed\" time=\"1.500\">"
+        ));
+        assert!(xml.contains("<system-out>created vertiport port_a: 1234</system-out>"));
+        assert!(xml.contains("<failure message=\"/label: &quot;A&quot; &amp; &quot;B&quot;\""));
+        assert!(xml.contains("<skipped/>"));
+        assert!(!xml.contains("<system-err>"));
+
+        ut_info!("(test_to_junit) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_to_tap() {
+        crate::get_log_handle().await;
+        ut_info!("(test_to_tap) Start.");
+
+        let tap = to_tap(&result());
+        let lines: Vec<&str> = tap.lines().collect();
+        assert_eq!(lines[0], "TAP version 13");
+        assert_eq!(lines[1], "1..3");
+        assert!(lines.contains(&"ok 1 - seed <ports>"));
+        assert!(lines.contains(&"not ok 2 - check \\#1"));
+        assert!(lines.contains(&"ok 3 - pause # SKIP"));
+        assert!(lines.contains(&"  message: '/label: \"A\" & \"B\"'"));
+        assert!(lines.contains(&"  - kind: vertiport"));
+        assert_eq!(lines.last(), Some(&"# teardown: 1 removed, 0 failed"));
+
+        ut_info!("(test_to_tap) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_to_json() {
+        crate::get_log_handle().await;
+        ut_info!("(test_to_json) Start.");
+
+        let report: SuiteReport = serde_json::from_str(&to_json(&result())).unwrap();
+        assert_eq!(report.schema_version, SCHEMA_VERSION);
+        assert_eq!(
+            report.summary,
+            ReportSummary {
+                total: 3,
+                passed: 1,
+                failed: 1,
+                skipped: 1
+            }
+        );
+        assert_eq!(report.steps[0].created[0].id, "1234");
+        assert_eq!("xml".parse(), Ok(ReportFormat::Junit));
+        assert!("html".parse::<ReportFormat>().is_err());
+
+        ut_info!("(test_to_json) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_write() {
+        crate::get_log_handle().await;
+        ut_info!("(test_write) Start.");
+
+        let dir = std::env::temp_dir().join(format!("test_write_{}", uuid::Uuid::new_v4()));
+        let mut result = result();
+        result.suite = "realm/roundtrip".to_string();
+        let paths = write(&dir, &result, &[ReportFormat::Junit, ReportFormat::Tap]).unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                dir.join("realm_roundtrip.xml"),
+                dir.join("realm_roundtrip.tap")
+            ]
+        );
+        assert!(std::fs::read_to_string(&paths[1])
+            .unwrap()
+            .starts_with("TAP version 13"));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        ut_info!("(test_write) Success.");
+    }
+}
@@ -0,0 +1,437 @@
+//! Declarative test suites
+//!
+//! A suite is a YAML or JSON document with a list of steps. Each step
+//! either seeds a scenario, calls a realm service, waits, or asserts on the
+//! last response or on the state of svc-storage. Strings may reference the
+//! IDs of seeded resources as `${name}`.
+//!
+//! Calls are limited to the methods of [`super::call::METHODS`]: the
+//! svc-storage read-backs, `is_ready` of svc-storage and svc-gis, and the
+//! svc-gis `update_vertiports` and `update_waypoints`. Suites calling any
+//! other method are rejected when they are loaded.
+
+use crate::rest::api::rest_types::Scenario;
+use crate::seed::scenario::ScenarioFormat;
+use crate::seed::SeedError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// A named list of steps, run in order
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Suite {
+    /// The unique name of the suite
+    pub name: String,
+
+    /// What the suite tests
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Remove the resources seeded by the suite once it finished
+    #[serde(default = "default_teardown")]
+    pub teardown: bool,
+
+    /// The steps of the suite
+    pub steps: Vec<Step>,
+}
+
+/// Suites remove their resources unless asked otherwise
+fn default_teardown() -> bool {
+    true
+}
+
+/// A single step of a suite
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Step {
+    /// The name of the step, as shown in the results
+    pub name: String,
+
+    /// What the step does
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+/// The action of a step
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Create the resources of a scenario
+    Seed(Scenario),
+    /// Call a realm service
+    Call(Call),
+    /// Wait a fixed time or for the critical dependencies
+    Wait(Wait),
+    /// Check the last response or a resource in svc-storage
+    Assert(Assert),
+}
+
+impl Action {
+    /// Returns the kind of the action
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Action::Seed(_) => "seed",
+            Action::Call(_) => "call",
+            Action::Wait(_) => "wait",
+            Action::Assert(_) => "assert",
+        }
+    }
+}
+
+/// A call of a realm service, one of [`super::call::METHODS`]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Call {
+    /// The service to call, such as `svc-storage`
+    pub service: String,
+
+    /// The method to call, such as `get_vertiport`
+    pub method: String,
+
+    /// The arguments of the method
+    #[serde(default)]
+    pub request: Value,
+}
+
+/// A pause in the suite
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Wait {
+    /// Wait for the critical dependencies to be serving
+    #[serde(default)]
+    pub dependencies: bool,
+
+    /// Time to wait for the critical dependencies, defaults to the
+    /// configured readiness gate timeout
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+
+    /// Time to wait, after the dependencies are serving
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+}
+
+/// Checks on the last response or on a resource in svc-storage
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Assert {
+    /// The resource to check, checks the last response if not provided
+    #[serde(default)]
+    pub storage: Option<StorageRef>,
+
+    /// The checks which all have to pass
+    pub checks: Vec<Check>,
+}
+
+/// A resource in svc-storage
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StorageRef {
+    /// The kind of resource: vertiport, vertipad, aircraft, user or scanner
+    pub resource: String,
+
+    /// The ID of the resource
+    pub id: String,
+}
+
+/// A check of a single value
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Check {
+    /// JSON pointer to the value, such as `/label`. An empty path checks
+    /// the whole document.
+    #[serde(default)]
+    pub path: String,
+
+    /// What the value has to be
+    #[serde(flatten)]
+    pub condition: Condition,
+}
+
+/// Condition a checked value has to meet
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    /// The value equals the provided value
+    Equals(Value),
+    /// The value does not equal the provided value
+    NotEquals(Value),
+    /// The value exists, or does not exist if false
+    Exists(bool),
+    /// The string contains the provided string, or the array contains the
+    /// provided value
+    Contains(Value),
+    /// The string, array or object has the provided length
+    Length(usize),
+}
+
+/// Parse a suite from the provided document
+pub fn parse(content: &str, format: ScenarioFormat) -> Result<Suite, SeedError> {
+    let suite: Suite = match format {
+        ScenarioFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        ScenarioFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+    }
+    .map_err(|e| SeedError::InvalidRequest(format!("could not parse suite: {}", e)))?;
+
+    validate(&suite)?;
+    Ok(suite)
+}
+
+/// Load a suite from a YAML or JSON file, based on the file extension
+pub fn from_file(path: impl AsRef<Path>) -> Result<Suite, SeedError> {
+    let path = path.as_ref();
+    let format = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => ScenarioFormat::Json,
+        _ => ScenarioFormat::Yaml,
+    };
+
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        SeedError::InvalidRequest(format!(
+            "could not read suite file [{}]: {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    parse(&content, format).map_err(|e| match e {
+        SeedError::InvalidRequest(reason) => {
+            SeedError::InvalidRequest(format!("{}: {}", path.display(), reason))
+        }
+        e => e.within(&path.display().to_string()),
+    })
+}
+
+/// Load all `.yaml`, `.yml` and `.json` suites of a directory, sorted by
+/// name. Suite names have to be unique.
+pub fn load_dir(dir: impl AsRef<Path>) -> Result<Vec<Suite>, SeedError> {
+    let dir = dir.as_ref();
+    let entries = std::fs::read_dir(dir).map_err(|e| {
+        SeedError::InvalidRequest(format!(
+            "could not read suite directory [{}]: {}",
+            dir.display(),
+            e
+        ))
+    })?;
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("yaml" | "yml" | "json")
+            )
+        })
+        .collect();
+    paths.sort();
+
+    let mut suites = paths
+        .iter()
+        .map(from_file)
+        .collect::<Result<Vec<Suite>, SeedError>>()?;
+    suites.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if let Some(pair) = suites.windows(2).find(|pair| pair[0].name == pair[1].name) {
+        return Err(SeedError::Conflict(format!(
+            "suite name [{}] is used by more than one file in [{}]",
+            pair[0].name,
+            dir.display()
+        )));
+    }
+
+    Ok(suites)
+}
+
+/// Checks that a suite can be run
+pub fn validate(suite: &Suite) -> Result<(), SeedError> {
+    if suite.name.trim().is_empty() {
+        return Err(SeedError::invalid_field("name", "name must not be empty"));
+    }
+
+    if suite.steps.is_empty() {
+        return Err(SeedError::invalid_field(
+            "steps",
+            "a suite needs at least one step",
+        ));
+    }
+
+    let mut names: HashSet<&str> = HashSet::new();
+    for (index, step) in suite.steps.iter().enumerate() {
+        let field = |name: &str| format!("steps[{}].{}", index, name);
+        if !names.insert(step.name.as_str()) {
+            return Err(SeedError::invalid_field(
+                field("name"),
+                format!("duplicate step name [{}]", step.name),
+            ));
+        }
+
+        match &step.action {
+            Action::Wait(wait) if !wait.dependencies && wait.duration_ms.is_none() => {
+                return Err(SeedError::invalid_field(
+                    field("wait"),
+                    "a wait needs dependencies or a duration_ms",
+                ));
+            }
+            Action::Call(call)
+                if !super::call::METHODS
+                    .contains(&(call.service.as_str(), call.method.as_str())) =>
+            {
+                return Err(SeedError::invalid_field(
+                    field("call"),
+                    format!(
+                        "unsupported call [{}] [{}], expected one of {}",
+                        call.service,
+                        call.method,
+                        super::call::METHODS
+                            .iter()
+                            .map(|(service, method)| format!("{}.{}", service, method))
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ),
+                ));
+            }
+            Action::Assert(assert) if assert.checks.is_empty() => {
+                return Err(SeedError::invalid_field(
+                    field("assert.checks"),
+                    "an assert needs at least one check",
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUITE: &str = r#"
+name: vertiport_roundtrip
+description: Seeds a vertiport and reads it back
+steps:
+  - name: wait for dependencies
+    wait:
+      dependencies: true
+      timeout_ms: 1000
+  - name: seed vertiport
+    seed:
+      vertiports:
+        - name: port_a
+          label: Port A
+          address: 1 Main Street
+          vertices: [[52.37, 4.89], [52.37, 4.90], [52.38, 4.90], [52.37, 4.89]]
+          altitude: 10.0
+  - name: read vertiport
+    call:
+      service: svc-storage
+      method: get_vertiport
+      request:
+        id: ${port_a}
+  - name: check response
+    assert:
+      checks:
+        - path: /label
+          equals: Port A
+        - path: /vertices
+          length: 4
+"#;
+
+    #[tokio::test]
+    async fn test_parse() {
+        crate::get_log_handle().await;
+        ut_info!("(test_parse) Start.");
+
+        let suite = parse(SUITE, ScenarioFormat::Yaml).unwrap();
+        assert_eq!(suite.name, "vertiport_roundtrip");
+        assert!(suite.teardown);
+        let kinds: Vec<&str> = suite.steps.iter().map(|step| step.action.kind()).collect();
+        assert_eq!(kinds, vec!["wait", "seed", "call", "assert"]);
+
+        let Action::Assert(assert) = &suite.steps[3].action else {
+            panic!("expected an assert step");
+        };
+        assert_eq!(assert.checks[0].path, "/label");
+        assert_eq!(
+            assert.checks[0].condition,
+            Condition::Equals(Value::from("Port A"))
+        );
+        assert_eq!(assert.checks[1].condition, Condition::Length(4));
+
+        ut_info!("(test_parse) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_validate() {
+        crate::get_log_handle().await;
+        ut_info!("(test_validate) Start.");
+
+        let mut suite = parse(SUITE, ScenarioFormat::Yaml).unwrap();
+        suite.steps[1].name = suite.steps[0].name.clone();
+        assert_eq!(
+            validate(&suite).unwrap_err(),
+            SeedError::invalid_field(
+                "steps[1].name",
+                "duplicate step name [wait for dependencies]"
+            )
+        );
+
+        let mut suite = parse(SUITE, ScenarioFormat::Yaml).unwrap();
+        suite.steps[0].action = Action::Wait(Wait::default());
+        assert!(validate(&suite).is_err());
+
+        let mut suite = parse(SUITE, ScenarioFormat::Yaml).unwrap();
+        if let Action::Call(call) = &mut suite.steps[2].action {
+            call.method = "delete_vertiport".to_string();
+        }
+        assert!(validate(&suite).is_err());
+
+        assert!(parse("name: empty\nsteps: []", ScenarioFormat::Yaml).is_err());
+        assert!(parse(
+            "name: unknown\nsteps:\n  - name: fly\n    fly: {}",
+            ScenarioFormat::Yaml
+        )
+        .is_err());
+
+        ut_info!("(test_validate) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_example_suites() {
+        crate::get_log_handle().await;
+        ut_info!("(test_example_suites) Start.");
+
+        let suite = parse(
+            include_str!("../../../suites/realm_smoke.yaml"),
+            ScenarioFormat::Yaml,
+        )
+        .unwrap();
+        assert_eq!(suite.name, "realm_smoke");
+
+        ut_info!("(test_example_suites) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_load_dir() {
+        crate::get_log_handle().await;
+        ut_info!("(test_load_dir) Start.");
+
+        let dir = std::env::temp_dir().join(format!("test_load_dir_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("b.yaml"), SUITE).unwrap();
+        std::fs::write(
+            dir.join("a.json"),
+            r#"{ "name": "pause", "steps": [{ "name": "pause", "wait": { "duration_ms": 1 } }] }"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a suite").unwrap();
+
+        let names: Vec<String> = load_dir(&dir)
+            .unwrap()
+            .into_iter()
+            .map(|suite| suite.name)
+            .collect();
+        assert_eq!(names, vec!["pause", "vertiport_roundtrip"]);
+
+        std::fs::write(dir.join("c.yml"), SUITE).unwrap();
+        assert!(matches!(load_dir(&dir), Err(SeedError::Conflict(_))));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        ut_info!("(test_load_dir) Success.");
+    }
+}
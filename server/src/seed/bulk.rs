@@ -0,0 +1,134 @@
+//! Seeding of many resources in one request, with bounded concurrency
+//!
+//! Each item is seeded on its own: a failing item only rolls back its own
+//! changes and does not affect the other items.
+
+use super::SeedFailure;
+use crate::rest::api::rest_types::{BulkItemResult, BulkResponse};
+use futures::stream::{self, StreamExt};
+use std::future::Future;
+
+/// Returns the number of items to seed at the same time, the requested
+/// concurrency limited to the configured maximum
+pub fn concurrency(requested: Option<u8>, maximum: u8) -> usize {
+    let maximum = maximum.max(1);
+    requested.unwrap_or(maximum).clamp(1, maximum) as usize
+}
+
+/// Seeds all items, running up to `concurrency` items at the same time.
+/// Results are returned in the order of the items.
+pub async fn apply<T, F, Fut>(items: Vec<T>, concurrency: usize, seed: F) -> BulkResponse
+where
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = Result<String, SeedFailure>>,
+{
+    seed_debug!(
+        "(apply) seeding {} items, {} at a time.",
+        items.len(),
+        concurrency
+    );
+
+    let results: Vec<BulkItemResult> = stream::iter(items)
+        .map(seed)
+        .buffered(concurrency.max(1))
+        .enumerate()
+        .map(|(index, result)| match result {
+            Ok(id) => BulkItemResult {
+                index,
+                id: Some(id),
+                error: None,
+            },
+            Err(failure) => {
+                seed_warn!("(apply) item {} failed: {}.", index, failure);
+                BulkItemResult {
+                    index,
+                    id: None,
+                    error: Some(failure.into()),
+                }
+            }
+        })
+        .collect()
+        .await;
+
+    response(results)
+}
+
+/// Returns the response for the results of all items
+pub fn response(results: Vec<BulkItemResult>) -> BulkResponse {
+    let failed = results
+        .iter()
+        .filter(|result| result.error.is_some())
+        .count();
+    BulkResponse {
+        succeeded: results.len() - failed,
+        failed,
+        results,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed::SeedError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_concurrency() {
+        crate::get_log_handle().await;
+        ut_info!("(test_concurrency) Start.");
+
+        assert_eq!(concurrency(None, 10), 10);
+        assert_eq!(concurrency(Some(4), 10), 4);
+        assert_eq!(concurrency(Some(50), 10), 10);
+        assert_eq!(concurrency(Some(0), 10), 1);
+        assert_eq!(concurrency(None, 0), 1);
+
+        ut_info!("(test_concurrency) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_apply() {
+        crate::get_log_handle().await;
+        ut_info!("(test_apply) Start.");
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let items: Vec<u64> = (0..8).collect();
+
+        let response = apply(items, 3, |item| {
+            let running = running.clone();
+            let peak = peak.clone();
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                // finish out of order
+                tokio::time::sleep(Duration::from_millis(10 * (8 - item))).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+
+                match item % 3 {
+                    0 => Err(SeedError::InvalidRequest(format!("item {}", item)).into()),
+                    _ => Ok(format!("id_{}", item)),
+                }
+            }
+        })
+        .await;
+
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        assert_eq!(response.succeeded, 5);
+        assert_eq!(response.failed, 3);
+        for (index, result) in response.results.iter().enumerate() {
+            assert_eq!(result.index, index);
+            match index % 3 {
+                0 => assert_eq!(
+                    result.error.as_ref().unwrap().error,
+                    format!("Invalid request: item {}", index)
+                ),
+                _ => assert_eq!(result.id, Some(format!("id_{}", index))),
+            }
+        }
+
+        ut_info!("(test_apply) Success.");
+    }
+}
@@ -0,0 +1,468 @@
+//! Export of the seeded realm as GeoJSON or KML, for visualization
+//!
+//! Vertiports are exported as polygons, vertipads as points. Aircraft are
+//! exported as points at the position of their hangar bay.
+
+use super::{
+    geometry,
+    ledger::{self, ResourceKind},
+    query, SeedError,
+};
+use crate::grpc::client::GrpcClients;
+use crate::rest::api::rest_types::*;
+use std::collections::HashMap;
+
+/// Resources read from svc-storage for an export
+#[derive(Debug, Clone, Default)]
+pub struct Realm {
+    /// The vertiports to export
+    pub vertiports: Vec<VertiportResponse>,
+    /// The vertipads to export
+    pub vertipads: Vec<VertipadResponse>,
+    /// The aircraft to export
+    pub aircraft: Vec<AircraftResponse>,
+    /// Hangar bays of the aircraft which are not exported themselves
+    pub hangar_bays: Vec<VertipadResponse>,
+}
+
+/// Returns the bounding box of the export options, if any
+pub fn bounding_box(params: &ExportParams) -> Result<Option<BoundingBox>, SeedError> {
+    match (
+        params.min_latitude,
+        params.min_longitude,
+        params.max_latitude,
+        params.max_longitude,
+    ) {
+        (None, None, None, None) => Ok(None),
+        (Some(min_latitude), Some(min_longitude), Some(max_latitude), Some(max_longitude)) => {
+            let bbox = BoundingBox {
+                min_latitude,
+                min_longitude,
+                max_latitude,
+                max_longitude,
+            };
+            geometry::validate_bounding_box("bounding_box", &bbox)?;
+            Ok(Some(bbox))
+        }
+        _ => Err(SeedError::invalid_field(
+            "bounding_box",
+            "provide all of min_latitude, min_longitude, max_latitude and max_longitude",
+        )),
+    }
+}
+
+/// Skips resources which have been removed outside of svc-itest
+fn existing<T>(result: Result<T, SeedError>) -> Result<Option<T>, SeedError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(SeedError::NotFound(id)) => {
+            seed_warn!("(existing) resource [{}] no longer exists.", id);
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Reads the resources created for a run
+async fn run_realm(grpc_clients: &GrpcClients, run_id: &str) -> Result<Realm, SeedError> {
+    let resources =
+        ledger::resources(run_id).ok_or_else(|| SeedError::NotFound(run_id.to_string()))?;
+
+    let mut realm = Realm::default();
+    for (kind, id) in resources {
+        match kind {
+            ResourceKind::Vertiport => realm
+                .vertiports
+                .extend(existing(query::get_vertiport(grpc_clients, &id).await)?),
+            ResourceKind::Vertipad => realm
+                .vertipads
+                .extend(existing(query::get_vertipad(grpc_clients, &id).await)?),
+            ResourceKind::Aircraft => realm
+                .aircraft
+                .extend(existing(query::get_aircraft(grpc_clients, &id).await)?),
+            ResourceKind::User | ResourceKind::Scanner => (),
+        }
+    }
+
+    // Aircraft of the run may be hangared at pads of other runs
+    let hangar_bays: Vec<String> = realm
+        .aircraft
+        .iter()
+        .filter_map(|aircraft| aircraft.hangar_bay_id.clone())
+        .filter(|id| !realm.vertipads.iter().any(|pad| &pad.id == id))
+        .collect();
+    for id in hangar_bays {
+        realm
+            .hangar_bays
+            .extend(existing(query::get_vertipad(grpc_clients, &id).await)?);
+    }
+
+    Ok(realm)
+}
+
+/// Reads all resources from svc-storage
+async fn full_realm(grpc_clients: &GrpcClients) -> Result<Realm, SeedError> {
+    Ok(Realm {
+        vertiports: query::list_vertiports(grpc_clients, &VertiportFilter::default()).await?,
+        vertipads: query::list_vertipads(grpc_clients, &VertipadFilter::default()).await?,
+        aircraft: query::list_aircraft(grpc_clients, &AircraftFilter::default()).await?,
+        hangar_bays: vec![],
+    })
+}
+
+/// Converts a `(latitude, longitude)` pair to a GeoJSON position
+fn position(latitude: f64, longitude: f64, altitude: f64) -> Vec<f64> {
+    vec![longitude, latitude, altitude]
+}
+
+/// Returns a feature with the geometry and properties
+fn feature(geometry: GeoJsonGeometry, properties: GeoJsonProperties) -> GeoJsonFeature {
+    GeoJsonFeature {
+        feature_type: "Feature".to_string(),
+        geometry: Some(geometry),
+        properties: Some(properties),
+    }
+}
+
+/// Builds the feature collection of a realm, limited to the bounding box
+pub fn collection(realm: &Realm, bbox: Option<&BoundingBox>) -> GeoJsonFeatureCollection {
+    let inside = |latitude: f64, longitude: f64| {
+        bbox.map(|bbox| geometry::in_bounding_box(bbox, (latitude, longitude)))
+            .unwrap_or(true)
+    };
+
+    let vertiports = realm
+        .vertiports
+        .iter()
+        .filter(|port| {
+            bbox.map(|bbox| geometry::intersects_bounding_box(bbox, &port.vertices))
+                .unwrap_or(true)
+        })
+        .map(|port| {
+            feature(
+                GeoJsonGeometry::Polygon {
+                    coordinates: vec![port
+                        .vertices
+                        .iter()
+                        .map(|v| position(v.0, v.1, port.altitude))
+                        .collect()],
+                },
+                GeoJsonProperties {
+                    id: Some(port.id.clone()),
+                    kind: Some(ResourceKind::Vertiport.to_string()),
+                    label: Some(port.label.clone()),
+                    address: Some(port.address.clone()),
+                    altitude: Some(port.altitude),
+                    schedule: port.schedule.clone(),
+                    ..Default::default()
+                },
+            )
+        });
+
+    let vertipads = realm
+        .vertipads
+        .iter()
+        .filter(|pad| inside(pad.latitude, pad.longitude))
+        .map(|pad| {
+            feature(
+                GeoJsonGeometry::Point {
+                    coordinates: position(pad.latitude, pad.longitude, pad.altitude),
+                },
+                GeoJsonProperties {
+                    id: Some(pad.id.clone()),
+                    kind: Some(ResourceKind::Vertipad.to_string()),
+                    label: Some(pad.label.clone()),
+                    altitude: Some(pad.altitude),
+                    schedule: pad.schedule.clone(),
+                    vertiport_id: Some(pad.vertiport_id.clone()),
+                    ..Default::default()
+                },
+            )
+        });
+
+    let pads: HashMap<&str, &VertipadResponse> = realm
+        .vertipads
+        .iter()
+        .chain(realm.hangar_bays.iter())
+        .map(|pad| (pad.id.as_str(), pad))
+        .collect();
+    let aircraft = realm.aircraft.iter().filter_map(|aircraft| {
+        let Some(pad) = aircraft
+            .hangar_bay_id
+            .as_deref()
+            .and_then(|id| pads.get(id))
+        else {
+            seed_debug!(
+                "(collection) aircraft [{}] has no known hangar bay.",
+                aircraft.id
+            );
+            return None;
+        };
+
+        inside(pad.latitude, pad.longitude).then(|| {
+            feature(
+                GeoJsonGeometry::Point {
+                    coordinates: position(pad.latitude, pad.longitude, pad.altitude),
+                },
+                GeoJsonProperties {
+                    id: Some(aircraft.id.clone()),
+                    kind: Some(ResourceKind::Aircraft.to_string()),
+                    label: Some(
+                        aircraft
+                            .nickname
+                            .clone()
+                            .unwrap_or_else(|| aircraft.registration_number.clone()),
+                    ),
+                    vertiport_id: aircraft.hangar_id.clone(),
+                    vertipad_id: aircraft.hangar_bay_id.clone(),
+                    registration_number: Some(aircraft.registration_number.clone()),
+                    ..Default::default()
+                },
+            )
+        })
+    });
+
+    GeoJsonFeatureCollection {
+        collection_type: "FeatureCollection".to_string(),
+        features: vertiports.chain(vertipads).chain(aircraft).collect(),
+    }
+}
+
+/// Reads the realm from svc-storage and builds its feature collection.
+/// Only the resources of the run are exported if a run ID is provided.
+pub async fn export(
+    grpc_clients: &GrpcClients,
+    run_id: Option<&str>,
+    bbox: Option<&BoundingBox>,
+) -> Result<GeoJsonFeatureCollection, SeedError> {
+    seed_debug!(
+        "(export) entry [run {:?}, bounding box {:?}].",
+        run_id,
+        bbox
+    );
+
+    let realm = match run_id {
+        Some(run_id) => run_realm(grpc_clients, run_id).await?,
+        None => full_realm(grpc_clients).await?,
+    };
+
+    let collection = collection(&realm, bbox);
+    seed_info!("(export) exported {} features.", collection.features.len());
+    Ok(collection)
+}
+
+/// Escapes text for use in XML
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Formats GeoJSON positions as KML coordinates
+fn kml_coordinates(positions: &[Vec<f64>]) -> String {
+    positions
+        .iter()
+        .map(|position| {
+            position
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Renders a feature collection as KML document, with the feature
+/// properties as extended data
+pub fn to_kml(collection: &GeoJsonFeatureCollection) -> String {
+    let mut kml = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n",
+        "<Document>\n",
+        "<name>svc-itest realm</name>\n"
+    ));
+
+    for feature in &collection.features {
+        let geometry = match &feature.geometry {
+            Some(GeoJsonGeometry::Point { coordinates }) => format!(
+                "<Point><coordinates>{}</coordinates></Point>",
+                kml_coordinates(std::slice::from_ref(coordinates))
+            ),
+            Some(GeoJsonGeometry::Polygon { coordinates }) => format!(
+                "<Polygon><outerBoundaryIs><LinearRing><coordinates>{}</coordinates></LinearRing></outerBoundaryIs></Polygon>",
+                kml_coordinates(coordinates.first().map(Vec::as_slice).unwrap_or_default())
+            ),
+            _ => continue,
+        };
+
+        let properties = feature.properties.clone().unwrap_or_default();
+        let name = properties
+            .label
+            .clone()
+            .or(properties.id.clone())
+            .unwrap_or_default();
+        let data: String = match serde_json::to_value(&properties) {
+            Ok(serde_json::Value::Object(map)) => map
+                .iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        serde_json::Value::String(value) => value.clone(),
+                        value => value.to_string(),
+                    };
+                    format!(
+                        "<Data name=\"{}\"><value>{}</value></Data>",
+                        escape(key),
+                        escape(&value)
+                    )
+                })
+                .collect(),
+            _ => String::new(),
+        };
+
+        kml.push_str(&format!(
+            "<Placemark><name>{}</name><ExtendedData>{}</ExtendedData>{}</Placemark>\n",
+            escape(&name),
+            data,
+            geometry
+        ));
+    }
+
+    kml.push_str("</Document>\n</kml>\n");
+    kml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn realm() -> Realm {
+        Realm {
+            vertiports: vec![VertiportResponse {
+                id: "port_id".to_string(),
+                label: "Port <A>".to_string(),
+                address: "1 Main Street".to_string(),
+                vertices: vec![
+                    (52.0, 4.0),
+                    (52.0, 4.1),
+                    (52.1, 4.1),
+                    (52.1, 4.0),
+                    (52.0, 4.0),
+                ],
+                altitude: 10.0,
+                schedule: None,
+                gis: None,
+            }],
+            vertipads: vec![VertipadResponse {
+                id: "pad_id".to_string(),
+                vertiport_id: "port_id".to_string(),
+                label: "Pad 1".to_string(),
+                latitude: 52.05,
+                longitude: 4.05,
+                altitude: 10.0,
+                enabled: true,
+                occupied: false,
+                schedule: None,
+                gis: None,
+            }],
+            aircraft: vec![AircraftResponse {
+                id: "aircraft_id".to_string(),
+                nickname: None,
+                registration_number: "N12345".to_string(),
+                serial_number: "serial".to_string(),
+                vehicle_model_id: "model".to_string(),
+                hangar_id: Some("port_id".to_string()),
+                hangar_bay_id: Some("pad_id".to_string()),
+                schedule: None,
+            }],
+            hangar_bays: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collection() {
+        crate::get_log_handle().await;
+        ut_info!("(test_collection) Start.");
+
+        let collection = collection(&realm(), None);
+        assert_eq!(collection.features.len(), 3);
+        assert_eq!(
+            collection.features[2].geometry,
+            Some(GeoJsonGeometry::Point {
+                coordinates: vec![4.05, 52.05, 10.0]
+            })
+        );
+        let properties = collection.features[2].properties.clone().unwrap();
+        assert_eq!(properties.kind.as_deref(), Some("aircraft"));
+        assert_eq!(properties.label.as_deref(), Some("N12345"));
+
+        let mut bbox = BoundingBox {
+            min_latitude: 52.08,
+            min_longitude: 4.08,
+            max_latitude: 52.2,
+            max_longitude: 4.2,
+        };
+        let features = super::collection(&realm(), Some(&bbox)).features;
+        assert_eq!(features.len(), 1);
+        assert_eq!(
+            features[0].properties.as_ref().unwrap().id.as_deref(),
+            Some("port_id")
+        );
+
+        // Vertiports crossing the box are exported without a vertex inside
+        let strip = BoundingBox {
+            min_latitude: 52.07,
+            min_longitude: 3.9,
+            max_latitude: 52.08,
+            max_longitude: 4.2,
+        };
+        let features = super::collection(&realm(), Some(&strip)).features;
+        assert_eq!(features.len(), 1);
+
+        bbox.min_latitude = 52.3;
+        assert!(super::collection(&realm(), Some(&bbox)).features.is_empty());
+
+        ut_info!("(test_collection) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_bounding_box() {
+        crate::get_log_handle().await;
+        ut_info!("(test_bounding_box) Start.");
+
+        let mut params = ExportParams::default();
+        assert_eq!(bounding_box(&params), Ok(None));
+
+        params.min_latitude = Some(52.0);
+        assert!(bounding_box(&params).is_err());
+
+        params.min_longitude = Some(4.0);
+        params.max_latitude = Some(53.0);
+        params.max_longitude = Some(5.0);
+        assert!(bounding_box(&params).unwrap().is_some());
+
+        params.max_latitude = Some(51.0);
+        assert!(bounding_box(&params).is_err());
+
+        ut_info!("(test_bounding_box) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_to_kml() {
+        crate::get_log_handle().await;
+        ut_info!("(test_to_kml) Start.");
+
+        let kml = to_kml(&collection(&realm(), None));
+        assert_eq!(kml.matches("<Placemark>").count(), 3);
+        assert!(kml.contains("<name>Port &lt;A&gt;</name>"));
+        assert!(kml.contains("<Data name=\"id\"><value>pad_id</value></Data>"));
+        assert!(kml.contains("<coordinates>4.05,52.05,10</coordinates>"));
+        assert!(kml.contains(
+            "<coordinates>4,52,10 4.1,52,10 4.1,52.1,10 4,52.1,10 4,52,10</coordinates>"
+        ));
+
+        ut_info!("(test_to_kml) Success.");
+    }
+}
@@ -0,0 +1,307 @@
+//! Generation of synthetic realms for load and routing tests
+//!
+//! A realm is generated as a [`Scenario`], which is created through
+//! [`scenario::apply`](super::scenario::apply). All randomness comes from a
+//! ChaCha generator seeded with the request's seed, so the same parameters
+//! always produce the same scenario. The IDs assigned by svc-storage differ
+//! between runs.
+
+use super::{geometry, vehicle_model, SeedError};
+use crate::rest::api::rest_types::*;
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::f64::consts::PI;
+
+/// Maximum number of resources of a single realm
+const MAX_RESOURCES: u64 = 10_000;
+
+/// Range of the vertiport radius, in degrees (roughly 100 to 300 meters)
+const MIN_RADIUS: f64 = 0.001;
+const MAX_RADIUS: f64 = 0.003;
+
+/// Attempts to place a vertiport without overlapping the others
+const PLACEMENT_ATTEMPTS: usize = 100;
+
+const NICKNAMES: [&str; 8] = [
+    "Mantis", "Kestrel", "Heron", "Swift", "Osprey", "Falcon", "Wren", "Ibis",
+];
+const FIRST_NAMES: [&str; 8] = [
+    "Ada", "Alan", "Grace", "Edsger", "Barbara", "Donald", "Frances", "John",
+];
+const LAST_NAMES: [&str; 8] = [
+    "Lovelace", "Turing", "Hopper", "Dijkstra", "Liskov", "Knuth", "Allen", "Backus",
+];
+
+/// Checks that the parameters describe a realm which can be generated
+pub fn validate(request: &GenerateRealmRequest) -> Result<(), SeedError> {
+    geometry::validate_bounding_box("bounding_box", &request.bounding_box)?;
+
+    if request.min_pads_per_vertiport > request.max_pads_per_vertiport {
+        return Err(SeedError::invalid_field(
+            "min_pads_per_vertiport",
+            "must not be larger than max_pads_per_vertiport",
+        ));
+    }
+
+    if request.aircraft > 0 && (request.vertiports == 0 || request.max_pads_per_vertiport == 0) {
+        return Err(SeedError::invalid_field(
+            "aircraft",
+            "aircraft need vertiports with pads to be hangared at",
+        ));
+    }
+
+    let resources = request.vertiports as u64 * (1 + request.max_pads_per_vertiport as u64)
+        + request.aircraft as u64
+        + request.users as u64;
+    if resources > MAX_RESOURCES {
+        return Err(SeedError::InvalidRequest(format!(
+            "realm of up to {} resources exceeds the maximum of {}",
+            resources, MAX_RESOURCES
+        )));
+    }
+
+    Ok(())
+}
+
+/// Places a vertiport center and radius inside the bounding box, away
+/// from the vertiports placed before
+fn place_vertiport(
+    rng: &mut ChaCha8Rng,
+    bbox: &BoundingBox,
+    placed: &[((f64, f64), f64)],
+) -> Result<((f64, f64), f64), SeedError> {
+    for _ in 0..PLACEMENT_ATTEMPTS {
+        let radius = rng.gen_range(MIN_RADIUS..=MAX_RADIUS);
+        let (min_lat, max_lat) = (bbox.min_latitude + radius, bbox.max_latitude - radius);
+        let (min_lon, max_lon) = (bbox.min_longitude + radius, bbox.max_longitude - radius);
+        if min_lat >= max_lat || min_lon >= max_lon {
+            continue;
+        }
+
+        let center = (
+            rng.gen_range(min_lat..max_lat),
+            rng.gen_range(min_lon..max_lon),
+        );
+        let overlaps = placed.iter().any(|(other, other_radius)| {
+            let distance = ((center.0 - other.0).powi(2) + (center.1 - other.1).powi(2)).sqrt();
+            distance < radius + other_radius
+        });
+        if !overlaps {
+            return Ok((center, radius));
+        }
+    }
+
+    Err(SeedError::invalid_field(
+        "bounding_box",
+        format!(
+            "too small to place {} vertiports without overlap",
+            placed.len() + 1
+        ),
+    ))
+}
+
+/// Returns a closed, counter-clockwise ring around the center. The ring is
+/// star shaped, so it never intersects itself.
+fn vertiport_polygon(rng: &mut ChaCha8Rng, center: (f64, f64), radius: f64) -> Vec<(f64, f64)> {
+    let count = rng.gen_range(5..=8);
+    let step = 2.0 * PI / count as f64;
+
+    let mut ring: Vec<(f64, f64)> = (0..count)
+        .map(|index| {
+            let angle = step * (index as f64 + rng.gen_range(-0.2..0.2));
+            let distance = radius * rng.gen_range(0.7..=1.0);
+            (
+                center.0 + distance * angle.sin(),
+                center.1 + distance * angle.cos(),
+            )
+        })
+        .collect();
+    ring.push(ring[0]);
+    ring
+}
+
+/// Returns a random position within the inner part of a vertiport
+fn pad_position(rng: &mut ChaCha8Rng, center: (f64, f64), radius: f64) -> (f64, f64) {
+    let angle = rng.gen_range(0.0..2.0 * PI);
+    let distance = radius * 0.4 * rng.gen_range(0.0..=1.0_f64).sqrt();
+    (
+        center.0 + distance * angle.sin(),
+        center.1 + distance * angle.cos(),
+    )
+}
+
+/// Generates the scenario of a synthetic realm
+pub fn generate(request: &GenerateRealmRequest) -> Result<Scenario, SeedError> {
+    seed_debug!("(generate) entry [seed {}].", request.seed);
+    validate(request)?;
+
+    let mut rng = ChaCha8Rng::seed_from_u64(request.seed);
+    let mut scenario = Scenario {
+        run_id: request.run_id.clone(),
+        ..Default::default()
+    };
+
+    let mut placed: Vec<((f64, f64), f64)> = vec![];
+    for port in 0..request.vertiports {
+        let (center, radius) = place_vertiport(&mut rng, &request.bounding_box, &placed)?;
+        placed.push((center, radius));
+
+        let name = format!("port_{}", port);
+        let altitude = rng.gen_range(0.0..50.0_f64).round();
+        scenario.vertiports.push(ScenarioVertiport {
+            name: name.clone(),
+            vertiport: AddVertiportRequest {
+                label: format!("Realm {} Vertiport {}", request.seed, port),
+                address: format!("{} Synthetic Street", port + 1),
+                vertices: vertiport_polygon(&mut rng, center, radius),
+                altitude,
+                schedule: None,
+                run_id: None,
+            },
+        });
+
+        let pads = rng.gen_range(request.min_pads_per_vertiport..=request.max_pads_per_vertiport);
+        for pad in 0..pads {
+            let (latitude, longitude) = pad_position(&mut rng, center, radius);
+            scenario.vertipads.push(ScenarioVertipad {
+                name: format!("pad_{}_{}", port, pad),
+                vertipad: AddVertipadRequest {
+                    vertiport_id: name.clone(),
+                    latitude,
+                    longitude,
+                    altitude,
+                    label: format!("Pad {}", pad + 1),
+                    schedule: None,
+                    run_id: None,
+                },
+            });
+        }
+    }
+
+    if request.aircraft > 0 && scenario.vertipads.is_empty() {
+        return Err(SeedError::invalid_field(
+            "aircraft",
+            "no pads were generated to hangar the aircraft at",
+        ));
+    }
+
+    let models: Vec<String> = vehicle_model::built_in()
+        .into_iter()
+        .map(|model| model.name)
+        .collect();
+    for index in 0..request.aircraft {
+        let pad = &scenario.vertipads[rng.gen_range(0..scenario.vertipads.len())];
+        let aircraft = AddAircraftRequest {
+            nickname: format!("{} {}", NICKNAMES.choose(&mut rng).unwrap_or(&""), index),
+            registration_number: format!("N{}R{:X}", index + 1, request.seed % 0x10000),
+            hangar_id: pad.vertipad.vertiport_id.clone(),
+            hangar_bay_id: pad.name.clone(),
+            vehicle_model_id: None,
+            vehicle_model: models.choose(&mut rng).cloned(),
+            serial_number: Some(format!("{:016X}", rng.gen::<u64>())),
+            schedule: None,
+            run_id: None,
+        };
+        scenario.aircraft.push(ScenarioAircraft {
+            name: format!("aircraft_{}", index),
+            aircraft,
+        });
+    }
+
+    for index in 0..request.users {
+        let first = FIRST_NAMES.choose(&mut rng).unwrap_or(&"");
+        let last = LAST_NAMES.choose(&mut rng).unwrap_or(&"");
+        scenario.users.push(ScenarioUser {
+            name: format!("user_{}", index),
+            user: AddUserRequest {
+                display_name: format!("{} {}", first, last),
+                email: format!(
+                    "{}.{}.{}@realm-{}.example.com",
+                    first.to_lowercase(),
+                    last.to_lowercase(),
+                    index,
+                    request.seed
+                ),
+                run_id: None,
+            },
+        });
+    }
+
+    seed_info!(
+        "(generate) generated realm [seed {}] with {} vertiports, {} pads, {} aircraft and {} users.",
+        request.seed,
+        scenario.vertiports.len(),
+        scenario.vertipads.len(),
+        scenario.aircraft.len(),
+        scenario.users.len()
+    );
+    Ok(scenario)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed::scenario;
+
+    fn request(seed: u64) -> GenerateRealmRequest {
+        GenerateRealmRequest {
+            seed,
+            bounding_box: BoundingBox {
+                min_latitude: 52.3,
+                min_longitude: 4.8,
+                max_latitude: 52.4,
+                max_longitude: 5.0,
+            },
+            vertiports: 10,
+            min_pads_per_vertiport: 1,
+            max_pads_per_vertiport: 4,
+            aircraft: 20,
+            users: 5,
+            run_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_deterministic() {
+        crate::get_log_handle().await;
+        ut_info!("(test_generate_deterministic) Start.");
+
+        let first = serde_json::to_string(&generate(&request(42)).unwrap()).unwrap();
+        let second = serde_json::to_string(&generate(&request(42)).unwrap()).unwrap();
+        let other = serde_json::to_string(&generate(&request(43)).unwrap()).unwrap();
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+
+        ut_info!("(test_generate_deterministic) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_generate_valid() {
+        crate::get_log_handle().await;
+        ut_info!("(test_generate_valid) Start.");
+
+        for seed in 0..20 {
+            let realm = generate(&request(seed)).unwrap();
+            assert_eq!(realm.vertiports.len(), 10);
+            assert!((10..=40).contains(&realm.vertipads.len()));
+            assert_eq!(realm.aircraft.len(), 20);
+            assert_eq!(realm.users.len(), 5);
+            assert!(scenario::validate(&realm).is_ok(), "seed {}", seed);
+        }
+
+        let mut invalid = request(1);
+        invalid.min_pads_per_vertiport = 5;
+        assert!(generate(&invalid).is_err());
+
+        let mut invalid = request(1);
+        invalid.vertiports = 0;
+        assert!(generate(&invalid).is_err());
+
+        let mut crowded = request(1);
+        crowded.bounding_box.max_latitude = 52.305;
+        crowded.bounding_box.max_longitude = 4.805;
+        assert!(generate(&crowded).is_err());
+
+        ut_info!("(test_generate_valid) Success.");
+    }
+}
@@ -0,0 +1,332 @@
+//! Import of vertiports and vertipads from GeoJSON
+//!
+//! Polygon features become vertiports, Point features become vertipads of
+//! the imported vertiport containing them. Each feature is seeded on its
+//! own: a rejected feature only rolls back its own changes.
+//!
+//! GeoJSON parsers should not reject rings with the wrong winding order
+//! (RFC 7946, section 3.1.6): clockwise rings are reversed before they are
+//! checked with [`geometry::validate_polygon`].
+
+use super::{bulk, geometry, resources, Saga, SeedError, SeedFailure};
+use crate::grpc::client::GrpcClients;
+use crate::rest::api::rest_types::*;
+
+/// Converts a GeoJSON position to a `(latitude, longitude)` pair
+fn position(field: &str, coordinates: &[f64]) -> Result<(f64, f64), SeedError> {
+    let [longitude, latitude, ..] = coordinates else {
+        return Err(SeedError::invalid_field(
+            field,
+            "a position needs a longitude and a latitude",
+        ));
+    };
+
+    geometry::validate_position(field, *latitude, *longitude)?;
+    Ok((*latitude, *longitude))
+}
+
+/// Returns the label of a feature, falling back to its name
+fn label(properties: &GeoJsonProperties) -> Option<String> {
+    properties
+        .label
+        .as_ref()
+        .or(properties.name.as_ref())
+        .filter(|label| !label.trim().is_empty())
+        .cloned()
+}
+
+/// Converts a Polygon feature to a vertiport request. Clockwise rings, as
+/// written by some GIS tools, are reversed.
+pub fn vertiport_request(feature: &GeoJsonFeature) -> Result<AddVertiportRequest, SeedError> {
+    let Some(GeoJsonGeometry::Polygon { coordinates }) = &feature.geometry else {
+        return Err(SeedError::invalid_field("geometry", "expected a Polygon"));
+    };
+
+    let Some(properties) = &feature.properties else {
+        return Err(SeedError::invalid_field(
+            "properties",
+            "vertiport features need properties with a label",
+        ));
+    };
+
+    let label = label(properties).ok_or_else(|| {
+        SeedError::invalid_field(
+            "properties.label",
+            "vertiport features need a label or name",
+        )
+    })?;
+
+    let ring = match coordinates.as_slice() {
+        [ring] => ring,
+        [] => {
+            return Err(SeedError::invalid_field(
+                "geometry.coordinates",
+                "polygon has no rings",
+            ))
+        }
+        _ => {
+            return Err(SeedError::invalid_field(
+                "geometry.coordinates",
+                "polygons with holes are not supported",
+            ))
+        }
+    };
+
+    let mut vertices = ring
+        .iter()
+        .enumerate()
+        .map(|(index, coordinates)| {
+            position(&format!("geometry.coordinates[0][{}]", index), coordinates)
+        })
+        .collect::<Result<Vec<(f64, f64)>, SeedError>>()?;
+    if geometry::is_clockwise(&vertices) {
+        vertices.reverse();
+    }
+    geometry::validate_polygon("geometry.coordinates", &vertices)?;
+
+    Ok(AddVertiportRequest {
+        label,
+        address: properties.address.clone().unwrap_or_default(),
+        vertices,
+        altitude: properties.altitude.unwrap_or_default(),
+        schedule: properties.schedule.clone(),
+        run_id: None,
+    })
+}
+
+/// Converts a Point feature to a vertipad request at the vertiport
+/// containing it
+pub fn vertipad_request(
+    feature: &GeoJsonFeature,
+    index: usize,
+    vertiports: &[(String, Vec<(f64, f64)>)],
+) -> Result<AddVertipadRequest, SeedError> {
+    let Some(GeoJsonGeometry::Point { coordinates }) = &feature.geometry else {
+        return Err(SeedError::invalid_field("geometry", "expected a Point"));
+    };
+
+    let (latitude, longitude) = position("geometry.coordinates", coordinates)?;
+    let containing: Vec<&String> = vertiports
+        .iter()
+        .filter(|(_, ring)| geometry::contains(ring, (latitude, longitude)))
+        .map(|(id, _)| id)
+        .collect();
+
+    let vertiport_id = match containing.as_slice() {
+        [id] => (*id).clone(),
+        [] => {
+            return Err(SeedError::invalid_field(
+                "geometry.coordinates",
+                "point is not within any imported vertiport",
+            ))
+        }
+        _ => {
+            return Err(SeedError::invalid_field(
+                "geometry.coordinates",
+                format!("point is within {} imported vertiports", containing.len()),
+            ))
+        }
+    };
+
+    let properties = feature.properties.clone().unwrap_or_default();
+    Ok(AddVertipadRequest {
+        vertiport_id,
+        latitude,
+        longitude,
+        altitude: properties
+            .altitude
+            .or(coordinates.get(2).copied())
+            .unwrap_or_default(),
+        label: label(&properties).unwrap_or_else(|| format!("Pad {}", index)),
+        schedule: properties.schedule,
+        run_id: None,
+    })
+}
+
+/// Returns the result of a single feature
+fn feature_result(index: usize, result: Result<String, SeedFailure>) -> BulkItemResult {
+    match result {
+        Ok(id) => BulkItemResult {
+            index,
+            id: Some(id),
+            error: None,
+        },
+        Err(mut failure) => {
+            seed_warn!("(import) feature {} rejected: {}.", index, failure);
+            failure.error = failure.error.within(&format!("features[{}]", index));
+            BulkItemResult {
+                index,
+                id: None,
+                error: Some(failure.into()),
+            }
+        }
+    }
+}
+
+/// Creates the vertiports and vertipads of a feature collection. Returns
+/// the result of each feature, in the order of the features.
+pub async fn import(
+    grpc_clients: &GrpcClients,
+    collection: &GeoJsonFeatureCollection,
+    run_id: Option<String>,
+) -> Result<BulkResponse, SeedError> {
+    seed_debug!("(import) entry [{} features].", collection.features.len());
+    if collection.collection_type != "FeatureCollection" {
+        return Err(SeedError::invalid_field(
+            "type",
+            format!(
+                "expected a FeatureCollection, found [{}]",
+                collection.collection_type
+            ),
+        ));
+    }
+
+    let mut results: Vec<Option<BulkItemResult>> = vec![None; collection.features.len()];
+    let mut vertiports: Vec<(String, Vec<(f64, f64)>)> = vec![];
+
+    // Vertiports first, so vertipads can be linked to them
+    for (index, feature) in collection.features.iter().enumerate() {
+        if !matches!(feature.geometry, Some(GeoJsonGeometry::Polygon { .. })) {
+            continue;
+        }
+
+        let mut saga = Saga::for_run(run_id.clone());
+        let result = match vertiport_request(feature) {
+            Ok(request) => resources::create_vertiport(grpc_clients, &request, &mut saga)
+                .await
+                .inspect(|id| vertiports.push((id.clone(), request.vertices))),
+            Err(e) => Err(e),
+        };
+        results[index] = Some(feature_result(index, saga.finish(result).await));
+    }
+
+    for (index, feature) in collection.features.iter().enumerate() {
+        if results[index].is_some() {
+            continue;
+        }
+
+        let mut saga = Saga::for_run(run_id.clone());
+        let result = match feature.geometry {
+            Some(GeoJsonGeometry::Point { .. }) => {
+                match vertipad_request(feature, index, &vertiports) {
+                    Ok(request) => {
+                        resources::create_vertipad(grpc_clients, &request, &mut saga).await
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            _ => Err(SeedError::invalid_field(
+                "geometry",
+                "unsupported geometry, expected a Polygon or a Point",
+            )),
+        };
+        results[index] = Some(feature_result(index, saga.finish(result).await));
+    }
+
+    let response = bulk::response(results.into_iter().flatten().collect());
+    seed_info!(
+        "(import) imported {} features, rejected {}.",
+        response.succeeded,
+        response.failed
+    );
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLLECTION: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "properties": { "name": "Port A", "altitude": 12.5, "fid": 7 },
+                "geometry": {
+                    "type": "Polygon",
+                    "coordinates": [[[4.0, 52.0], [4.1, 52.0], [4.1, 52.1], [4.0, 52.1], [4.0, 52.0]]]
+                }
+            },
+            {
+                "type": "Feature",
+                "properties": null,
+                "geometry": { "type": "Point", "coordinates": [4.05, 52.05, 3.0] }
+            },
+            {
+                "type": "Feature",
+                "properties": { "label": "Road" },
+                "geometry": { "type": "LineString", "coordinates": [[4.0, 52.0], [4.1, 52.1]] }
+            }
+        ]
+    }"#;
+
+    #[tokio::test]
+    async fn test_parse_collection() {
+        crate::get_log_handle().await;
+        ut_info!("(test_parse_collection) Start.");
+
+        let collection: GeoJsonFeatureCollection = serde_json::from_str(COLLECTION).unwrap();
+        assert_eq!(collection.features.len(), 3);
+        assert_eq!(
+            collection.features[2].geometry,
+            Some(GeoJsonGeometry::Unsupported)
+        );
+
+        let vertiport = vertiport_request(&collection.features[0]).unwrap();
+        assert_eq!(vertiport.label, "Port A");
+        assert_eq!(vertiport.altitude, 12.5);
+        assert_eq!(vertiport.vertices[1], (52.0, 4.1));
+
+        // Clockwise rings are reversed
+        let mut clockwise = collection.features[0].clone();
+        if let Some(GeoJsonGeometry::Polygon { coordinates }) = &mut clockwise.geometry {
+            coordinates[0].reverse();
+        }
+        let reversed = vertiport_request(&clockwise).unwrap();
+        assert!(!geometry::is_clockwise(&reversed.vertices));
+        assert_eq!(reversed.vertices, vertiport.vertices);
+
+        let vertiports = vec![("port_id".to_string(), vertiport.vertices)];
+        let vertipad = vertipad_request(&collection.features[1], 1, &vertiports).unwrap();
+        assert_eq!(vertipad.vertiport_id, "port_id");
+        assert_eq!((vertipad.latitude, vertipad.longitude), (52.05, 4.05));
+        assert_eq!(vertipad.altitude, 3.0);
+        assert_eq!(vertipad.label, "Pad 1");
+
+        assert!(vertipad_request(&collection.features[1], 1, &[]).is_err());
+
+        ut_info!("(test_parse_collection) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_vertiport_request_invalid() {
+        crate::get_log_handle().await;
+        ut_info!("(test_vertiport_request_invalid) Start.");
+
+        let collection: GeoJsonFeatureCollection = serde_json::from_str(COLLECTION).unwrap();
+
+        let mut unlabeled = collection.features[0].clone();
+        unlabeled.properties = Some(GeoJsonProperties::default());
+        assert!(vertiport_request(&unlabeled).is_err());
+
+        let mut holes = collection.features[0].clone();
+        if let Some(GeoJsonGeometry::Polygon { coordinates }) = &mut holes.geometry {
+            coordinates.push(coordinates[0].clone());
+        }
+        assert_eq!(
+            vertiport_request(&holes).unwrap_err(),
+            SeedError::invalid_field(
+                "geometry.coordinates",
+                "polygons with holes are not supported"
+            )
+        );
+
+        let mut swapped = collection.features[0].clone();
+        if let Some(GeoJsonGeometry::Polygon { coordinates }) = &mut swapped.geometry {
+            coordinates[0][2] = vec![4.1];
+        }
+        assert!(vertiport_request(&swapped).is_err());
+
+        ut_info!("(test_vertiport_request_invalid) Success.");
+    }
+}
@@ -0,0 +1,371 @@
+//! Validation of vertiport polygons and vertipad positions
+//!
+//! Coordinates are `(latitude, longitude)` pairs in degrees. Polygons are
+//! closed rings: the last vertex repeats the first. Following RFC 7946, the
+//! ring must be wound counter-clockwise.
+
+use super::SeedError;
+use crate::rest::api::rest_types::BoundingBox;
+
+/// Polygons with a smaller area, in square degrees, are degenerate
+const MIN_AREA: f64 = 1e-10;
+
+/// Checks that a latitude is within range
+fn validate_latitude(field: &str, latitude: f64) -> Result<(), SeedError> {
+    match latitude.is_finite() && (-90.0..=90.0).contains(&latitude) {
+        true => Ok(()),
+        false => Err(SeedError::invalid_field(
+            field,
+            format!("latitude [{}] out of range, expected -90 to 90", latitude),
+        )),
+    }
+}
+
+/// Checks that a longitude is within range
+fn validate_longitude(field: &str, longitude: f64) -> Result<(), SeedError> {
+    match longitude.is_finite() && (-180.0..=180.0).contains(&longitude) {
+        true => Ok(()),
+        false => Err(SeedError::invalid_field(
+            field,
+            format!(
+                "longitude [{}] out of range, expected -180 to 180",
+                longitude
+            ),
+        )),
+    }
+}
+
+/// Checks that a latitude and longitude are within range
+pub fn validate_position(field: &str, latitude: f64, longitude: f64) -> Result<(), SeedError> {
+    validate_latitude(field, latitude)?;
+    validate_longitude(field, longitude)
+}
+
+/// Checks that the edges of a bounding box are within range and that the
+/// box encloses an area
+pub fn validate_bounding_box(field: &str, bbox: &BoundingBox) -> Result<(), SeedError> {
+    validate_position(field, bbox.min_latitude, bbox.min_longitude)?;
+    validate_position(field, bbox.max_latitude, bbox.max_longitude)?;
+
+    if bbox.min_latitude >= bbox.max_latitude || bbox.min_longitude >= bbox.max_longitude {
+        return Err(SeedError::invalid_field(
+            field,
+            "the minimum latitude and longitude must be smaller than the maximum",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns true if the point lies inside the bounding box or on its edge
+pub fn in_bounding_box(bbox: &BoundingBox, point: (f64, f64)) -> bool {
+    (bbox.min_latitude..=bbox.max_latitude).contains(&point.0)
+        && (bbox.min_longitude..=bbox.max_longitude).contains(&point.1)
+}
+
+/// Returns twice the signed area of a closed ring, in square degrees.
+/// Positive for counter-clockwise rings.
+fn signed_area(ring: &[(f64, f64)]) -> f64 {
+    ring.windows(2)
+        .map(|edge| edge[0].1 * edge[1].0 - edge[1].1 * edge[0].0)
+        .sum()
+}
+
+/// Returns true if the closed ring is wound clockwise
+pub fn is_clockwise(ring: &[(f64, f64)]) -> bool {
+    signed_area(ring) < 0.0
+}
+
+/// Returns the orientation of the point `c` relative to the line `a`-`b`:
+/// positive if counter-clockwise, negative if clockwise, zero if collinear
+fn orientation(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    (b.1 - a.1) * (c.0 - a.0) - (b.0 - a.0) * (c.1 - a.1)
+}
+
+/// Returns true if `c`, collinear with `a`-`b`, lies on the segment
+fn on_segment(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> bool {
+    c.0 >= a.0.min(b.0) && c.0 <= a.0.max(b.0) && c.1 >= a.1.min(b.1) && c.1 <= a.1.max(b.1)
+}
+
+/// Returns true if the segments `a`-`b` and `c`-`d` touch or cross
+fn segments_intersect(a: (f64, f64), b: (f64, f64), c: (f64, f64), d: (f64, f64)) -> bool {
+    let o1 = orientation(a, b, c);
+    let o2 = orientation(a, b, d);
+    let o3 = orientation(c, d, a);
+    let o4 = orientation(c, d, b);
+
+    if o1 * o2 < 0.0 && o3 * o4 < 0.0 {
+        return true;
+    }
+
+    (o1 == 0.0 && on_segment(a, b, c))
+        || (o2 == 0.0 && on_segment(a, b, d))
+        || (o3 == 0.0 && on_segment(c, d, a))
+        || (o4 == 0.0 && on_segment(c, d, b))
+}
+
+/// Checks that the vertices form a valid vertiport polygon: a closed,
+/// counter-clockwise ring of in-range coordinates, which does not intersect
+/// itself and encloses an area.
+pub fn validate_polygon(field: &str, vertices: &[(f64, f64)]) -> Result<(), SeedError> {
+    for (index, vertex) in vertices.iter().enumerate() {
+        validate_position(&format!("{}[{}]", field, index), vertex.0, vertex.1)?;
+    }
+
+    if vertices.len() < 4 {
+        return Err(SeedError::invalid_field(
+            field,
+            format!(
+                "a closed ring needs at least 4 vertices, found {}",
+                vertices.len()
+            ),
+        ));
+    }
+
+    if vertices.first() != vertices.last() {
+        return Err(SeedError::invalid_field(
+            field,
+            "ring is not closed, the last vertex must equal the first",
+        ));
+    }
+
+    if let Some(index) = vertices.windows(2).position(|edge| edge[0] == edge[1]) {
+        return Err(SeedError::invalid_field(
+            format!("{}[{}]", field, index + 1),
+            "vertex repeats the previous vertex",
+        ));
+    }
+
+    let edges: Vec<((f64, f64), (f64, f64))> =
+        vertices.windows(2).map(|edge| (edge[0], edge[1])).collect();
+    for i in 0..edges.len() {
+        for j in (i + 1)..edges.len() {
+            // adjacent edges share a vertex, including the last and first edge
+            let adjacent = j == i + 1 || (i == 0 && j == edges.len() - 1);
+            if adjacent {
+                continue;
+            }
+
+            if segments_intersect(edges[i].0, edges[i].1, edges[j].0, edges[j].1) {
+                return Err(SeedError::invalid_field(
+                    field,
+                    format!("ring intersects itself, edge {} crosses edge {}", i, j),
+                ));
+            }
+        }
+    }
+
+    let area = signed_area(vertices);
+    if area.abs() / 2.0 < MIN_AREA {
+        return Err(SeedError::invalid_field(
+            field,
+            "ring is degenerate, it encloses no area",
+        ));
+    }
+
+    if area < 0.0 {
+        return Err(SeedError::invalid_field(
+            field,
+            "ring is wound clockwise, expected counter-clockwise",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns true if the point lies inside the ring or on its boundary
+pub fn contains(ring: &[(f64, f64)], point: (f64, f64)) -> bool {
+    let mut inside = false;
+    for edge in ring.windows(2) {
+        let (a, b) = (edge[0], edge[1]);
+        if orientation(a, b, point) == 0.0 && on_segment(a, b, point) {
+            return true;
+        }
+
+        // ray cast along the longitude axis
+        if (a.0 > point.0) != (b.0 > point.0) {
+            let crossing = a.1 + (point.0 - a.0) / (b.0 - a.0) * (b.1 - a.1);
+            if point.1 < crossing {
+                inside = !inside;
+            }
+        }
+    }
+
+    inside
+}
+
+/// Returns true if the closed ring and the bounding box overlap: a vertex
+/// lies inside the box, an edge crosses the box, or the box lies inside the
+/// ring
+pub fn intersects_bounding_box(bbox: &BoundingBox, ring: &[(f64, f64)]) -> bool {
+    if ring.iter().any(|vertex| in_bounding_box(bbox, *vertex)) {
+        return true;
+    }
+
+    let corners = [
+        (bbox.min_latitude, bbox.min_longitude),
+        (bbox.min_latitude, bbox.max_longitude),
+        (bbox.max_latitude, bbox.max_longitude),
+        (bbox.max_latitude, bbox.min_longitude),
+    ];
+    let crosses = ring.windows(2).any(|edge| {
+        (0..corners.len()).any(|i| {
+            segments_intersect(
+                edge[0],
+                edge[1],
+                corners[i],
+                corners[(i + 1) % corners.len()],
+            )
+        })
+    });
+
+    crosses || contains(ring, corners[0])
+}
+
+/// Checks that a vertipad position lies within the polygon of its vertiport.
+/// Range errors name the `latitude` or `longitude` field, a position outside
+/// of the vertiport names the `position`.
+pub fn validate_pad_position(
+    vertiport_id: &str,
+    vertiport: &[(f64, f64)],
+    latitude: f64,
+    longitude: f64,
+) -> Result<(), SeedError> {
+    validate_latitude("latitude", latitude)?;
+    validate_longitude("longitude", longitude)?;
+
+    match contains(vertiport, (latitude, longitude)) {
+        true => Ok(()),
+        false => Err(SeedError::invalid_field(
+            "position",
+            format!(
+                "position ({}, {}) is outside of vertiport [{}]",
+                latitude, longitude, vertiport_id
+            ),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counter-clockwise square around (52.05, 4.05)
+    fn square() -> Vec<(f64, f64)> {
+        vec![
+            (52.0, 4.0),
+            (52.0, 4.1),
+            (52.1, 4.1),
+            (52.1, 4.0),
+            (52.0, 4.0),
+        ]
+    }
+
+    fn reason(error: SeedError) -> String {
+        match error {
+            SeedError::InvalidField { reason, .. } => reason,
+            _ => panic!("expected an invalid field error, got {:?}", error),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_polygon() {
+        crate::get_log_handle().await;
+        ut_info!("(test_validate_polygon) Start.");
+
+        assert!(validate_polygon("vertices", &square()).is_ok());
+
+        let mut open = square();
+        open.pop();
+        open.push((52.0, 4.01));
+        assert!(reason(validate_polygon("vertices", &open).unwrap_err()).contains("not closed"));
+
+        let mut clockwise = square();
+        clockwise.reverse();
+        assert!(
+            reason(validate_polygon("vertices", &clockwise).unwrap_err()).contains("clockwise")
+        );
+
+        let bowtie = vec![
+            (52.0, 4.0),
+            (52.0, 4.1),
+            (52.1, 4.0),
+            (52.1, 4.1),
+            (52.0, 4.0),
+        ];
+        assert!(reason(validate_polygon("vertices", &bowtie).unwrap_err()).contains("intersects"));
+
+        let line = vec![(52.0, 4.0), (52.05, 4.05), (52.1, 4.1), (52.0, 4.0)];
+        let error = validate_polygon("vertices", &line).unwrap_err();
+        assert!(matches!(error, SeedError::InvalidField { .. }));
+
+        let mut out_of_range = square();
+        out_of_range[2] = (92.1, 4.1);
+        assert_eq!(
+            validate_polygon("vertices", &out_of_range).unwrap_err(),
+            SeedError::invalid_field(
+                "vertices[2]",
+                "latitude [92.1] out of range, expected -90 to 90"
+            )
+        );
+
+        ut_info!("(test_validate_polygon) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_intersects_bounding_box() {
+        crate::get_log_handle().await;
+        ut_info!("(test_intersects_bounding_box) Start.");
+
+        let bbox = |min_latitude, min_longitude, max_latitude, max_longitude| BoundingBox {
+            min_latitude,
+            min_longitude,
+            max_latitude,
+            max_longitude,
+        };
+
+        // A vertex inside the box
+        assert!(intersects_bounding_box(
+            &bbox(52.08, 4.08, 52.2, 4.2),
+            &square()
+        ));
+        // The box inside the polygon
+        assert!(intersects_bounding_box(
+            &bbox(52.04, 4.04, 52.06, 4.06),
+            &square()
+        ));
+        // A strip crossing the polygon
+        assert!(intersects_bounding_box(
+            &bbox(52.04, 3.9, 52.06, 4.2),
+            &square()
+        ));
+        assert!(!intersects_bounding_box(
+            &bbox(52.2, 4.2, 52.3, 4.3),
+            &square()
+        ));
+
+        ut_info!("(test_intersects_bounding_box) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_validate_pad_position() {
+        crate::get_log_handle().await;
+        ut_info!("(test_validate_pad_position) Start.");
+
+        let vertiport = square();
+        assert!(validate_pad_position("port", &vertiport, 52.05, 4.05).is_ok());
+        assert!(validate_pad_position("port", &vertiport, 52.0, 4.05).is_ok());
+
+        let field = |latitude, longitude| match validate_pad_position(
+            "port", &vertiport, latitude, longitude,
+        ) {
+            Err(SeedError::InvalidField { field, .. }) => field,
+            result => panic!("expected an invalid field error, got {:?}", result),
+        };
+        assert_eq!(field(52.15, 4.05), "position");
+        assert_eq!(field(92.0, 4.05), "latitude");
+        assert_eq!(field(52.05, 181.0), "longitude");
+
+        ut_info!("(test_validate_pad_position) Success.");
+    }
+}
@@ -0,0 +1,155 @@
+//! Record of the resources svc-itest registered with svc-gis
+//!
+//! svc-gis provides no interface to read back vertiports or waypoints, so
+//! svc-itest keeps track of what it sent. This makes it possible to show
+//! the svc-gis view next to the svc-storage view of a resource.
+//!
+//! The record is kept in memory by this svc-itest instance only: it is lost
+//! on restart and does not include resources sent by anyone else. Entries
+//! are removed once their resource is deleted from svc-storage, and the
+//! oldest entries are dropped when the record is full.
+
+use crate::rest::api::rest_types::{GisVertiport, GisWaypoint};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Maximum number of vertiports and of waypoints kept
+const MAX_ENTRIES: usize = 10_000;
+
+/// Vertiports registered with svc-gis, by identifier
+static VERTIPORTS: Mutex<Option<HashMap<String, GisVertiport>>> = Mutex::new(None);
+
+/// Waypoints registered with svc-gis, by identifier
+static WAYPOINTS: Mutex<Option<HashMap<String, GisWaypoint>>> = Mutex::new(None);
+
+/// Locks a registry. Entries are only inserted or removed as a whole, so a
+/// registry poisoned by a panic is still consistent.
+fn lock<T>(registry: &Mutex<T>) -> MutexGuard<'_, T> {
+    match registry.lock() {
+        Ok(registry) => registry,
+        Err(poisoned) => {
+            seed_warn!("(lock) registry lock poisoned, recovering.");
+            poisoned.into_inner()
+        }
+    }
+}
+
+/// Drops the oldest entry if the map is full and `identifier` is new
+fn make_room<T>(map: &mut HashMap<String, T>, identifier: &str, timestamp: impl Fn(&T) -> i64) {
+    if map.len() < MAX_ENTRIES || map.contains_key(identifier) {
+        return;
+    }
+
+    let oldest = map
+        .iter()
+        .min_by_key(|(_, entry)| timestamp(entry))
+        .map(|(identifier, _)| identifier.clone());
+    if let Some(oldest) = oldest {
+        seed_debug!("(make_room) dropping [{}] from the record.", oldest);
+        map.remove(&oldest);
+    }
+}
+
+/// Records a vertiport which has been sent to svc-gis
+pub fn record_vertiport(vertiport: GisVertiport) {
+    let mut map = lock(&VERTIPORTS);
+    let map = map.get_or_insert_with(HashMap::new);
+    make_room(map, &vertiport.identifier, |entry| {
+        entry.timestamp.timestamp_micros()
+    });
+    map.insert(vertiport.identifier.clone(), vertiport);
+}
+
+/// Records a waypoint which has been sent to svc-gis
+pub fn record_waypoint(waypoint: GisWaypoint) {
+    let mut map = lock(&WAYPOINTS);
+    let map = map.get_or_insert_with(HashMap::new);
+    make_room(map, &waypoint.identifier, |entry| {
+        entry.timestamp.timestamp_micros()
+    });
+    map.insert(waypoint.identifier.clone(), waypoint);
+}
+
+/// Removes a vertiport or waypoint which no longer exists in svc-storage
+pub fn forget(identifier: &str) {
+    if let Some(map) = lock(&VERTIPORTS).as_mut() {
+        map.remove(identifier);
+    }
+
+    if let Some(map) = lock(&WAYPOINTS).as_mut() {
+        map.remove(identifier);
+    }
+}
+
+/// Returns the vertiport as last sent to svc-gis, if any
+pub fn get_vertiport(identifier: &str) -> Option<GisVertiport> {
+    lock(&VERTIPORTS).as_ref()?.get(identifier).cloned()
+}
+
+/// Returns the waypoint as last sent to svc-gis, if any
+pub fn get_waypoint(identifier: &str) -> Option<GisWaypoint> {
+    lock(&WAYPOINTS).as_ref()?.get(identifier).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[tokio::test]
+    async fn test_record_and_get() {
+        crate::get_log_handle().await;
+        ut_info!("(test_record_and_get) Start.");
+
+        let vertiport = GisVertiport {
+            identifier: "test_record_and_get_vertiport".to_string(),
+            label: Some("Port".to_string()),
+            vertices: vec![(52.0, 4.0), (52.1, 4.0), (52.1, 4.1), (52.0, 4.0)],
+            timestamp: Utc::now(),
+        };
+        assert!(get_vertiport(&vertiport.identifier).is_none());
+        record_vertiport(vertiport.clone());
+        assert_eq!(
+            get_vertiport(&vertiport.identifier),
+            Some(vertiport.clone())
+        );
+
+        let waypoint = GisWaypoint {
+            identifier: "test_record_and_get_waypoint".to_string(),
+            latitude: 52.05,
+            longitude: 4.05,
+            timestamp: Utc::now(),
+        };
+        assert!(get_waypoint(&waypoint.identifier).is_none());
+        record_waypoint(waypoint.clone());
+        assert_eq!(get_waypoint(&waypoint.identifier), Some(waypoint.clone()));
+
+        forget(&vertiport.identifier);
+        forget(&waypoint.identifier);
+        assert!(get_vertiport(&vertiport.identifier).is_none());
+        assert!(get_waypoint(&waypoint.identifier).is_none());
+
+        ut_info!("(test_record_and_get) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_make_room() {
+        crate::get_log_handle().await;
+        ut_info!("(test_make_room) Start.");
+
+        let mut map: HashMap<String, i64> = (0..MAX_ENTRIES as i64)
+            .map(|index| (format!("entry_{}", index), index))
+            .collect();
+
+        // Updating an entry needs no room
+        make_room(&mut map, "entry_5", |entry| *entry);
+        assert_eq!(map.len(), MAX_ENTRIES);
+
+        // A new entry replaces the oldest one
+        make_room(&mut map, "new", |entry| *entry);
+        assert_eq!(map.len(), MAX_ENTRIES - 1);
+        assert!(!map.contains_key("entry_0"));
+
+        ut_info!("(test_make_room) Success.");
+    }
+}
@@ -0,0 +1,241 @@
+//! Ledger of the resources created for each run
+//!
+//! Seeding requests may carry a run ID. All resources created for a run are
+//! recorded here once the request succeeded, so they can be removed in one
+//! go when the run is done.
+
+use super::{gis_view, lifecycle, SeedError};
+use crate::grpc::client::GrpcClients;
+use crate::rest::api::rest_types::RollbackReport;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Mutex;
+
+/// Kinds of resources created by svc-itest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    /// A vertiport, in svc-storage and svc-gis
+    Vertiport,
+    /// A vertipad, in svc-storage and as waypoint in svc-gis
+    Vertipad,
+    /// An aircraft, in svc-storage
+    Aircraft,
+    /// A user, in svc-storage
+    User,
+    /// A scanner, in svc-storage
+    Scanner,
+}
+
+impl ResourceKind {
+    /// Position of this kind when removing the resources of a run.
+    /// Resources are removed before the resources they reference.
+    fn teardown_rank(&self) -> u8 {
+        match self {
+            ResourceKind::Scanner | ResourceKind::User => 0,
+            ResourceKind::Aircraft => 1,
+            ResourceKind::Vertipad => 2,
+            ResourceKind::Vertiport => 3,
+        }
+    }
+}
+
+impl Display for ResourceKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceKind::Vertiport => write!(f, "vertiport"),
+            ResourceKind::Vertipad => write!(f, "vertipad"),
+            ResourceKind::Aircraft => write!(f, "aircraft"),
+            ResourceKind::User => write!(f, "user"),
+            ResourceKind::Scanner => write!(f, "scanner"),
+        }
+    }
+}
+
+/// Resources of a run, in order of creation
+type RunResources = Vec<(ResourceKind, String)>;
+
+/// Resources of each run
+static RUNS: Mutex<Option<HashMap<String, RunResources>>> = Mutex::new(None);
+
+/// Records resources which have been created for a run
+pub fn record(run_id: &str, resources: Vec<(ResourceKind, String)>) {
+    if resources.is_empty() {
+        return;
+    }
+
+    match RUNS.lock() {
+        Ok(mut runs) => {
+            seed_debug!(
+                "(record) recorded {} resources for run [{}].",
+                resources.len(),
+                run_id
+            );
+            runs.get_or_insert_with(HashMap::new)
+                .entry(run_id.to_string())
+                .or_default()
+                .extend(resources);
+        }
+        Err(e) => seed_error!("(record) could not lock ledger: {}", e),
+    }
+}
+
+/// Removes a resource which no longer exists from all runs
+pub fn forget(id: &str) {
+    match RUNS.lock() {
+        Ok(mut runs) => {
+            let Some(runs) = runs.as_mut() else {
+                return;
+            };
+
+            runs.values_mut()
+                .for_each(|resources| resources.retain(|(_, resource_id)| resource_id != id));
+            runs.retain(|_, resources| !resources.is_empty());
+        }
+        Err(e) => seed_error!("(forget) could not lock ledger: {}", e),
+    }
+}
+
+/// Returns the resources of a run, in order of creation
+pub fn resources(run_id: &str) -> Option<Vec<(ResourceKind, String)>> {
+    RUNS.lock().ok()?.as_ref()?.get(run_id).cloned()
+}
+
+/// Returns the resources of a run in the order they should be removed:
+/// referencing resources first, most recent first
+fn teardown_order(mut resources: Vec<(ResourceKind, String)>) -> Vec<(ResourceKind, String)> {
+    resources.reverse();
+    resources.sort_by_key(|(kind, _)| kind.teardown_rank());
+    resources
+}
+
+/// Removes all resources created for a run from svc-storage
+///
+/// svc-gis can't remove vertiports or waypoints, these are reported as not
+/// removable without making the teardown incomplete. Resources which could
+/// not be removed from svc-storage remain part of the run.
+pub async fn teardown(
+    grpc_clients: &GrpcClients,
+    run_id: &str,
+) -> Result<RollbackReport, SeedError> {
+    seed_info!("(teardown) removing resources of run [{}].", run_id);
+    let resources = resources(run_id).ok_or_else(|| SeedError::NotFound(run_id.to_string()))?;
+
+    let mut report = RollbackReport {
+        complete: true,
+        undone: vec![],
+        failed: vec![],
+        not_removable: vec![],
+    };
+
+    for (kind, id) in teardown_order(resources) {
+        let result = match kind {
+            ResourceKind::Vertiport => lifecycle::delete_vertiport(grpc_clients, &id).await,
+            ResourceKind::Vertipad => lifecycle::delete_vertipad(grpc_clients, &id).await,
+            ResourceKind::Aircraft => lifecycle::delete_aircraft(grpc_clients, &id).await,
+            ResourceKind::User => lifecycle::delete_user(grpc_clients, &id).await,
+            ResourceKind::Scanner => lifecycle::delete_scanner(grpc_clients, &id).await,
+        };
+
+        let description = format!("delete {} [{}] from svc-storage", kind, id);
+        match result {
+            Ok(()) | Err(SeedError::NotFound(_)) => {
+                forget(&id);
+                gis_view::forget(&id);
+                report.undone.push(description);
+            }
+            Err(e) => {
+                seed_error!("(teardown) could not {}: {}.", description, e);
+                report.complete = false;
+                report.failed.push(format!("{}: {}", description, e));
+            }
+        }
+
+        if matches!(kind, ResourceKind::Vertiport | ResourceKind::Vertipad) {
+            report.not_removable.push(format!(
+                "remove {} [{}] from svc-gis: svc-gis does not support removing {}s",
+                kind, id, kind
+            ));
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_and_forget() {
+        crate::get_log_handle().await;
+        ut_info!("(test_record_and_forget) Start.");
+
+        let run_id = "test_record_and_forget";
+        assert!(resources(run_id).is_none());
+
+        record(run_id, vec![]);
+        assert!(resources(run_id).is_none());
+
+        record(
+            run_id,
+            vec![
+                (
+                    ResourceKind::Vertiport,
+                    "test_record_and_forget_1".to_string(),
+                ),
+                (
+                    ResourceKind::Vertipad,
+                    "test_record_and_forget_2".to_string(),
+                ),
+            ],
+        );
+        forget("test_record_and_forget_1");
+        assert_eq!(
+            resources(run_id),
+            Some(vec![(
+                ResourceKind::Vertipad,
+                "test_record_and_forget_2".to_string()
+            )])
+        );
+
+        forget("test_record_and_forget_2");
+        assert!(resources(run_id).is_none());
+
+        ut_info!("(test_record_and_forget) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_teardown_order() {
+        crate::get_log_handle().await;
+        ut_info!("(test_teardown_order) Start.");
+
+        let resources = vec![
+            (ResourceKind::Vertiport, "port_1".to_string()),
+            (ResourceKind::Vertipad, "pad_1".to_string()),
+            (ResourceKind::Vertiport, "port_2".to_string()),
+            (ResourceKind::Aircraft, "aircraft_1".to_string()),
+            (ResourceKind::User, "user_1".to_string()),
+            (ResourceKind::Vertipad, "pad_2".to_string()),
+            (ResourceKind::Scanner, "scanner_1".to_string()),
+        ];
+
+        let order: Vec<String> = teardown_order(resources)
+            .into_iter()
+            .map(|(_, id)| id)
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                "scanner_1",
+                "user_1",
+                "aircraft_1",
+                "pad_2",
+                "pad_1",
+                "port_2",
+                "port_1"
+            ]
+        );
+
+        ut_info!("(test_teardown_order) Success.");
+    }
+}
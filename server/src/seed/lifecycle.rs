@@ -0,0 +1,758 @@
+//! Updates and removal of realm resources in svc-storage and svc-gis
+//!
+//! Updates only change the provided fields, using a field mask. Vertiport
+//! and vertipad changes which affect their svc-gis representation are pushed
+//! to svc-gis as well. svc-gis has no interface to remove resources, so
+//! deleted vertiports and vertipads remain known to svc-gis.
+
+use super::query::{self, storage_error};
+use super::{
+    geometry, gis_view, ledger, resources, schedule, storage_object, Saga, SeedError, GIS,
+};
+use crate::grpc::client::GrpcClients;
+use crate::rest::api::rest_types::*;
+use chrono::Utc;
+use svc_gis_client_grpc::client::{
+    Coordinates, UpdateVertiportsRequest, UpdateWaypointsRequest, Vertiport, Waypoint,
+};
+use svc_gis_client_grpc::prelude::GisServiceClient;
+use svc_storage_client_grpc::prelude::*;
+
+/// Field paths of a storage update
+#[derive(Debug, Default)]
+struct Mask {
+    paths: Vec<String>,
+}
+
+impl Mask {
+    /// Replaces the field with the provided value and adds its path
+    fn set<T>(&mut self, path: &str, field: &mut T, value: Option<T>) {
+        let Some(value) = value else {
+            return;
+        };
+
+        *field = value;
+        self.add(path);
+    }
+
+    /// Adds a path if not already present
+    fn add(&mut self, path: &str) {
+        if !self.contains(path) {
+            self.paths.push(path.to_string());
+        }
+    }
+
+    /// Returns true if the path is part of the mask
+    fn contains(&self, path: &str) -> bool {
+        self.paths.iter().any(|p| p == path)
+    }
+
+    /// Converts the mask to the storage field mask, fails if there
+    /// is nothing to update
+    fn into_field_mask(self) -> Result<FieldMask, SeedError> {
+        match self.paths.is_empty() {
+            true => Err(SeedError::InvalidRequest(
+                "no fields to update provided".to_string(),
+            )),
+            false => Ok(FieldMask { paths: self.paths }),
+        }
+    }
+}
+
+/// Resolves an optional schedule update
+fn resolve_schedule(schedule: Option<&str>) -> Result<Option<Option<String>>, SeedError> {
+    schedule
+        .map(|schedule| schedule::resolve(Some(schedule)).map(Some))
+        .transpose()
+}
+
+/// Returns the value of a scanner status name
+fn scanner_status_value(name: &str) -> Result<i32, SeedError> {
+    match name {
+        "active" => Ok(scanner::ScannerStatus::Active as i32),
+        "disabled" => Ok(scanner::ScannerStatus::Disabled as i32),
+        _ => Err(SeedError::invalid_field(
+            "scanner_status",
+            format!("[{}] is not one of active or disabled", name),
+        )),
+    }
+}
+
+/// Builds the svc-gis vertiport of a storage vertiport
+fn gis_vertiport(id: &str, data: &vertiport::Data) -> (Vertiport, GisVertiport) {
+    let timestamp = Utc::now();
+    let vertices: Vec<(f64, f64)> = data
+        .geo_location
+        .as_ref()
+        .and_then(|polygon| polygon.exterior.as_ref())
+        .map(|exterior| {
+            exterior
+                .points
+                .iter()
+                .map(|p| (p.latitude, p.longitude))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let vertiport = Vertiport {
+        identifier: id.to_string(),
+        label: Some(data.name.clone()),
+        vertices: vertices
+            .iter()
+            .map(|vx| Coordinates {
+                latitude: vx.0,
+                longitude: vx.1,
+            })
+            .collect(),
+        altitude_meters: 0.0,
+        timestamp_network: Some(timestamp.into()),
+    };
+
+    let view = GisVertiport {
+        identifier: id.to_string(),
+        label: Some(data.name.clone()),
+        vertices,
+        timestamp,
+    };
+
+    (vertiport, view)
+}
+
+/// Builds the svc-gis waypoint of a storage vertipad
+fn gis_waypoint(id: &str, data: &vertipad::Data) -> (Waypoint, GisWaypoint) {
+    let location = data.geo_location.clone().unwrap_or_default();
+    let waypoint = Waypoint {
+        identifier: id.to_string(),
+        location: Some(Coordinates {
+            latitude: location.latitude,
+            longitude: location.longitude,
+        }),
+    };
+
+    let view = GisWaypoint {
+        identifier: id.to_string(),
+        latitude: location.latitude,
+        longitude: location.longitude,
+        timestamp: Utc::now(),
+    };
+
+    (waypoint, view)
+}
+
+/// Sends a vertiport to svc-gis and keeps track of it
+async fn push_vertiport(
+    grpc_clients: &GrpcClients,
+    id: &str,
+    data: &vertiport::Data,
+) -> Result<(), SeedError> {
+    let (vertiport, view) = gis_vertiport(id, data);
+    grpc_clients
+        .gis
+        .update_vertiports(UpdateVertiportsRequest {
+            vertiports: vec![vertiport],
+        })
+        .await
+        .map_err(|e| {
+            seed_error!("(push_vertiport) Error: {}.", e);
+            SeedError::dependency(GIS, e)
+        })?;
+
+    gis_view::record_vertiport(view);
+    Ok(())
+}
+
+/// Sends a vertipad to svc-gis as waypoint and keeps track of it
+async fn push_waypoint(
+    grpc_clients: &GrpcClients,
+    id: &str,
+    data: &vertipad::Data,
+) -> Result<(), SeedError> {
+    let (waypoint, view) = gis_waypoint(id, data);
+    grpc_clients
+        .gis
+        .update_waypoints(UpdateWaypointsRequest {
+            waypoints: vec![waypoint],
+        })
+        .await
+        .map_err(|e| {
+            seed_error!("(push_waypoint) Error: {}.", e);
+            SeedError::dependency(GIS, e)
+        })?;
+
+    gis_view::record_waypoint(view);
+    Ok(())
+}
+
+/// Checks that all vertipads of a vertiport are within its new polygon
+async fn validate_vertipads_within(
+    grpc_clients: &GrpcClients,
+    id: &str,
+    vertices: &[(f64, f64)],
+) -> Result<(), SeedError> {
+    let filter = VertipadFilter {
+        vertiport_id: Some(id.to_string()),
+        label: None,
+    };
+
+    for pad in query::list_vertipads(grpc_clients, &filter).await? {
+        geometry::validate_pad_position(id, vertices, pad.latitude, pad.longitude).map_err(
+            |_| {
+                SeedError::invalid_field(
+                    "vertices",
+                    format!("vertipad [{}] would be outside of the vertiport", pad.id),
+                )
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Update a vertiport in storage, and in GIS if its label or polygon changed
+///
+/// A new polygon must still contain all vertipads of the vertiport.
+pub async fn update_vertiport(
+    grpc_clients: &GrpcClients,
+    id: &str,
+    request: &UpdateVertiportRequest,
+    saga: &mut Saga,
+) -> Result<VertiportResponse, SeedError> {
+    seed_debug!("(update_vertiport) entry [{}].", id);
+
+    if let Some(vertices) = &request.vertices {
+        geometry::validate_polygon("vertices", vertices)?;
+        validate_vertipads_within(grpc_clients, id, vertices).await?;
+    }
+
+    let previous = grpc_clients
+        .storage
+        .vertiport
+        .get_by_id(Id { id: id.to_string() })
+        .await
+        .map_err(|e| storage_error("update_vertiport", Some(id), e))?
+        .into_inner()
+        .data
+        .ok_or(SeedError::NoObject)?;
+
+    let mut data = previous.clone();
+    let mut mask = Mask::default();
+    mask.set("name", &mut data.name, request.label.clone());
+    mask.set(
+        "description",
+        &mut data.description,
+        request.address.clone(),
+    );
+    mask.set(
+        "schedule",
+        &mut data.schedule,
+        resolve_schedule(request.schedule.as_deref())?,
+    );
+
+    if request.vertices.is_some() || request.altitude.is_some() {
+        let points = data
+            .geo_location
+            .as_ref()
+            .and_then(|polygon| polygon.exterior.as_ref())
+            .map(|exterior| exterior.points.clone())
+            .unwrap_or_default();
+        let altitude = request
+            .altitude
+            .unwrap_or_else(|| points.first().map(|p| p.altitude).unwrap_or_default());
+        let vertices = request
+            .vertices
+            .clone()
+            .unwrap_or_else(|| points.iter().map(|p| (p.latitude, p.longitude)).collect());
+
+        data.geo_location = Some(GeoPolygon {
+            exterior: Some(GeoLineString {
+                points: vertices
+                    .iter()
+                    .map(|vx| GeoPoint {
+                        latitude: vx.0,
+                        longitude: vx.1,
+                        altitude,
+                    })
+                    .collect(),
+            }),
+            interiors: vec![],
+        });
+        mask.add("geo_location");
+    }
+
+    let gis_changed = mask.contains("name") || request.vertices.is_some();
+    let mask = mask.into_field_mask()?;
+    let response = grpc_clients
+        .storage
+        .vertiport
+        .update(vertiport::UpdateObject {
+            id: id.to_string(),
+            data: Some(data.clone()),
+            mask: Some(mask.clone()),
+        })
+        .await
+        .map_err(|e| storage_error("update_vertiport", Some(id), e))?
+        .into_inner();
+    let object = storage_object(response.validation_result, response.object)?;
+
+    let clients = grpc_clients.clone();
+    let undo_id = id.to_string();
+    let undo_data = previous.clone();
+    saga.record(
+        format!("restore vertiport [{}] in svc-storage", id),
+        move || async move {
+            clients
+                .storage
+                .vertiport
+                .update(vertiport::UpdateObject {
+                    id: undo_id,
+                    data: Some(undo_data),
+                    mask: Some(mask),
+                })
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        },
+    );
+
+    if gis_changed {
+        push_vertiport(grpc_clients, id, &data).await?;
+
+        let clients = grpc_clients.clone();
+        let undo_id = id.to_string();
+        saga.record(
+            format!("restore vertiport [{}] in svc-gis", id),
+            move || async move {
+                push_vertiport(&clients, &undo_id, &previous)
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+        );
+    }
+
+    query::vertiport_response(object)
+}
+
+/// Update a vertipad in storage, and in GIS if it has been moved
+///
+/// The enabled and occupied flags are only known to svc-storage.
+pub async fn update_vertipad(
+    grpc_clients: &GrpcClients,
+    id: &str,
+    request: &UpdateVertipadRequest,
+    saga: &mut Saga,
+) -> Result<VertipadResponse, SeedError> {
+    seed_debug!("(update_vertipad) entry [{}].", id);
+
+    let previous = grpc_clients
+        .storage
+        .vertipad
+        .get_by_id(Id { id: id.to_string() })
+        .await
+        .map_err(|e| storage_error("update_vertipad", Some(id), e))?
+        .into_inner()
+        .data
+        .ok_or(SeedError::NoObject)?;
+
+    let mut data = previous.clone();
+    let mut mask = Mask::default();
+    mask.set("name", &mut data.name, request.label.clone());
+    mask.set("enabled", &mut data.enabled, request.enabled);
+    mask.set("occupied", &mut data.occupied, request.occupied);
+    mask.set(
+        "schedule",
+        &mut data.schedule,
+        resolve_schedule(request.schedule.as_deref())?,
+    );
+
+    let moved = request.latitude.is_some() || request.longitude.is_some();
+    if moved || request.altitude.is_some() {
+        let location = data.geo_location.clone().unwrap_or_default();
+        data.geo_location = Some(GeoPoint {
+            latitude: request.latitude.unwrap_or(location.latitude),
+            longitude: request.longitude.unwrap_or(location.longitude),
+            altitude: request.altitude.unwrap_or(location.altitude),
+        });
+        mask.add("geo_location");
+    }
+
+    if moved {
+        let location = data.geo_location.clone().unwrap_or_default();
+        let vertiport = resources::vertiport_polygon(grpc_clients, &data.vertiport_id).await?;
+        geometry::validate_pad_position(
+            &data.vertiport_id,
+            &vertiport,
+            location.latitude,
+            location.longitude,
+        )?;
+    }
+
+    let mask = mask.into_field_mask()?;
+    let response = grpc_clients
+        .storage
+        .vertipad
+        .update(vertipad::UpdateObject {
+            id: id.to_string(),
+            data: Some(data.clone()),
+            mask: Some(mask.clone()),
+        })
+        .await
+        .map_err(|e| storage_error("update_vertipad", Some(id), e))?
+        .into_inner();
+    let object = storage_object(response.validation_result, response.object)?;
+
+    let clients = grpc_clients.clone();
+    let undo_id = id.to_string();
+    let undo_data = previous.clone();
+    saga.record(
+        format!("restore vertipad [{}] in svc-storage", id),
+        move || async move {
+            clients
+                .storage
+                .vertipad
+                .update(vertipad::UpdateObject {
+                    id: undo_id,
+                    data: Some(undo_data),
+                    mask: Some(mask),
+                })
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        },
+    );
+
+    if moved {
+        push_waypoint(grpc_clients, id, &data).await?;
+
+        let clients = grpc_clients.clone();
+        let undo_id = id.to_string();
+        saga.record(
+            format!("restore vertipad [{}] in svc-gis", id),
+            move || async move {
+                push_waypoint(&clients, &undo_id, &previous)
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+        );
+    }
+
+    query::vertipad_response(object)
+}
+
+/// Update an aircraft in storage
+///
+/// A changed hangar or hangar bay has to refer to an existing vertiport and
+/// one of its vertipads.
+pub async fn update_aircraft(
+    grpc_clients: &GrpcClients,
+    id: &str,
+    request: &UpdateAircraftRequest,
+    saga: &mut Saga,
+) -> Result<AircraftResponse, SeedError> {
+    seed_debug!("(update_aircraft) entry [{}].", id);
+
+    let previous = grpc_clients
+        .storage
+        .vehicle
+        .get_by_id(Id { id: id.to_string() })
+        .await
+        .map_err(|e| storage_error("update_aircraft", Some(id), e))?
+        .into_inner()
+        .data
+        .ok_or(SeedError::NoObject)?;
+
+    let mut data = previous.clone();
+    let mut mask = Mask::default();
+    mask.set(
+        "description",
+        &mut data.description,
+        request.nickname.clone().map(Some),
+    );
+    mask.set(
+        "registration_number",
+        &mut data.registration_number,
+        request.registration_number.clone(),
+    );
+    mask.set(
+        "hangar_id",
+        &mut data.hangar_id,
+        request.hangar_id.clone().map(Some),
+    );
+    mask.set(
+        "hangar_bay_id",
+        &mut data.hangar_bay_id,
+        request.hangar_bay_id.clone().map(Some),
+    );
+    if request.hangar_id.is_some() || request.hangar_bay_id.is_some() {
+        resources::validate_hangar(
+            grpc_clients,
+            data.hangar_id.as_deref().unwrap_or_default(),
+            data.hangar_bay_id.as_deref().unwrap_or_default(),
+        )
+        .await?;
+    }
+    mask.set(
+        "schedule",
+        &mut data.schedule,
+        resolve_schedule(request.schedule.as_deref())?,
+    );
+
+    let mask = mask.into_field_mask()?;
+    let response = grpc_clients
+        .storage
+        .vehicle
+        .update(vehicle::UpdateObject {
+            id: id.to_string(),
+            data: Some(data),
+            mask: Some(mask.clone()),
+        })
+        .await
+        .map_err(|e| storage_error("update_aircraft", Some(id), e))?
+        .into_inner();
+    let object = storage_object(response.validation_result, response.object)?;
+
+    let clients = grpc_clients.clone();
+    let undo_id = id.to_string();
+    saga.record(
+        format!("restore vehicle [{}] in svc-storage", id),
+        move || async move {
+            clients
+                .storage
+                .vehicle
+                .update(vehicle::UpdateObject {
+                    id: undo_id,
+                    data: Some(previous),
+                    mask: Some(mask),
+                })
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        },
+    );
+
+    query::aircraft_response(object)
+}
+
+/// Update a user in storage
+pub async fn update_user(
+    grpc_clients: &GrpcClients,
+    id: &str,
+    request: &UpdateUserRequest,
+) -> Result<UserResponse, SeedError> {
+    seed_debug!("(update_user) entry [{}].", id);
+
+    let mut data = grpc_clients
+        .storage
+        .user
+        .get_by_id(Id { id: id.to_string() })
+        .await
+        .map_err(|e| storage_error("update_user", Some(id), e))?
+        .into_inner()
+        .data
+        .ok_or(SeedError::NoObject)?;
+
+    let mut mask = Mask::default();
+    mask.set(
+        "display_name",
+        &mut data.display_name,
+        request.display_name.clone(),
+    );
+    mask.set("email", &mut data.email, request.email.clone());
+
+    let response = grpc_clients
+        .storage
+        .user
+        .update(user::UpdateObject {
+            id: id.to_string(),
+            data: Some(data),
+            mask: Some(mask.into_field_mask()?),
+        })
+        .await
+        .map_err(|e| storage_error("update_user", Some(id), e))?
+        .into_inner();
+    let object = storage_object(response.validation_result, response.object)?;
+
+    query::user_response(object)
+}
+
+/// Update a scanner in storage
+pub async fn update_scanner(
+    grpc_clients: &GrpcClients,
+    id: &str,
+    request: &UpdateScannerRequest,
+) -> Result<ScannerResponse, SeedError> {
+    seed_debug!("(update_scanner) entry [{}].", id);
+
+    let scanner_status = request
+        .scanner_status
+        .as_deref()
+        .map(scanner_status_value)
+        .transpose()?;
+
+    let mut data = grpc_clients
+        .storage
+        .scanner
+        .get_by_id(Id { id: id.to_string() })
+        .await
+        .map_err(|e| storage_error("update_scanner", Some(id), e))?
+        .into_inner()
+        .data
+        .ok_or(SeedError::NoObject)?;
+
+    let mut mask = Mask::default();
+    mask.set(
+        "organization_id",
+        &mut data.organization_id,
+        request.organization_id.clone(),
+    );
+    mask.set("scanner_status", &mut data.scanner_status, scanner_status);
+
+    let response = grpc_clients
+        .storage
+        .scanner
+        .update(scanner::UpdateObject {
+            id: id.to_string(),
+            data: Some(data),
+            mask: Some(mask.into_field_mask()?),
+        })
+        .await
+        .map_err(|e| storage_error("update_scanner", Some(id), e))?
+        .into_inner();
+    let object = storage_object(response.validation_result, response.object)?;
+
+    query::scanner_response(object)
+}
+
+/// Delete a vertiport from storage
+///
+/// The vertiport remains known to svc-gis, which can't remove vertiports.
+pub async fn delete_vertiport(grpc_clients: &GrpcClients, id: &str) -> Result<(), SeedError> {
+    seed_debug!("(delete_vertiport) entry [{}].", id);
+    grpc_clients
+        .storage
+        .vertiport
+        .delete(Id { id: id.to_string() })
+        .await
+        .map_err(|e| storage_error("delete_vertiport", Some(id), e))?;
+
+    ledger::forget(id);
+    gis_view::forget(id);
+
+    seed_warn!(
+        "(delete_vertiport) vertiport [{}] remains registered with svc-gis.",
+        id
+    );
+    Ok(())
+}
+
+/// Delete a vertipad from storage
+///
+/// The vertipad remains known to svc-gis, which can't remove waypoints.
+pub async fn delete_vertipad(grpc_clients: &GrpcClients, id: &str) -> Result<(), SeedError> {
+    seed_debug!("(delete_vertipad) entry [{}].", id);
+    grpc_clients
+        .storage
+        .vertipad
+        .delete(Id { id: id.to_string() })
+        .await
+        .map_err(|e| storage_error("delete_vertipad", Some(id), e))?;
+
+    ledger::forget(id);
+    gis_view::forget(id);
+
+    seed_warn!(
+        "(delete_vertipad) vertipad [{}] remains registered with svc-gis.",
+        id
+    );
+    Ok(())
+}
+
+/// Delete an aircraft from storage
+pub async fn delete_aircraft(grpc_clients: &GrpcClients, id: &str) -> Result<(), SeedError> {
+    seed_debug!("(delete_aircraft) entry [{}].", id);
+    grpc_clients
+        .storage
+        .vehicle
+        .delete(Id { id: id.to_string() })
+        .await
+        .map_err(|e| storage_error("delete_aircraft", Some(id), e))?;
+
+    ledger::forget(id);
+
+    Ok(())
+}
+
+/// Delete a user from storage
+pub async fn delete_user(grpc_clients: &GrpcClients, id: &str) -> Result<(), SeedError> {
+    seed_debug!("(delete_user) entry [{}].", id);
+    grpc_clients
+        .storage
+        .user
+        .delete(Id { id: id.to_string() })
+        .await
+        .map_err(|e| storage_error("delete_user", Some(id), e))?;
+
+    ledger::forget(id);
+
+    Ok(())
+}
+
+/// Delete a scanner from storage
+pub async fn delete_scanner(grpc_clients: &GrpcClients, id: &str) -> Result<(), SeedError> {
+    seed_debug!("(delete_scanner) entry [{}].", id);
+    grpc_clients
+        .storage
+        .scanner
+        .delete(Id { id: id.to_string() })
+        .await
+        .map_err(|e| storage_error("delete_scanner", Some(id), e))?;
+
+    ledger::forget(id);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mask() {
+        crate::get_log_handle().await;
+        ut_info!("(test_mask) Start.");
+
+        let mut data = vertipad::Data::default();
+        let mut mask = Mask::default();
+        mask.set("name", &mut data.name, None);
+        assert_eq!(
+            mask.into_field_mask().unwrap_err(),
+            SeedError::InvalidRequest("no fields to update provided".to_string())
+        );
+
+        let mut mask = Mask::default();
+        mask.set("name", &mut data.name, Some("Pad A".to_string()));
+        mask.set("occupied", &mut data.occupied, Some(true));
+        mask.add("name");
+        assert_eq!(data.name, "Pad A");
+        assert!(data.occupied);
+        assert_eq!(
+            mask.into_field_mask().unwrap().paths,
+            vec!["name".to_string(), "occupied".to_string()]
+        );
+
+        ut_info!("(test_mask) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_scanner_status_value() {
+        crate::get_log_handle().await;
+        ut_info!("(test_scanner_status_value) Start.");
+
+        for name in ["active", "disabled"] {
+            let value = scanner_status_value(name).unwrap();
+            assert_eq!(query::scanner_status_name(value), name);
+        }
+        assert!(scanner_status_value("retired").is_err());
+
+        ut_info!("(test_scanner_status_value) Success.");
+    }
+}
@@ -0,0 +1,212 @@
+//! Seeding of realm resources through the gRPC clients
+
+#[macro_use]
+pub mod macros;
+pub mod bulk;
+pub mod export;
+pub mod generator;
+pub mod geojson;
+pub mod geometry;
+pub mod gis_view;
+pub mod ledger;
+pub mod lifecycle;
+pub mod query;
+pub mod resources;
+pub mod saga;
+pub mod scenario;
+pub mod schedule;
+pub mod upsert;
+pub mod vehicle_model;
+
+pub use resources::*;
+pub use saga::{Saga, SeedFailure};
+
+use std::fmt::{self, Display, Formatter};
+use svc_storage_client_grpc::prelude::ValidationResult;
+
+/// Name of the svc-storage dependency
+pub const STORAGE: &str = "svc-storage";
+
+/// Name of the svc-gis dependency
+pub const GIS: &str = "svc-gis";
+
+/// An error returned by a dependency
+#[derive(Debug, Clone, PartialEq)]
+pub struct DependencyError {
+    /// The name of the dependency
+    pub dependency: String,
+    /// The status code returned by the dependency
+    pub code: tonic::Code,
+    /// The error message returned by the dependency
+    pub message: String,
+    /// The field the dependency rejected, if known
+    pub field: Option<String>,
+}
+
+impl DependencyError {
+    /// Creates the error of a dependency from its gRPC status
+    pub fn from_status(dependency: &str, status: &tonic::Status) -> Self {
+        DependencyError {
+            dependency: dependency.to_string(),
+            code: status.code(),
+            message: status.message().to_string(),
+            field: rejected_field(status.message()),
+        }
+    }
+}
+
+/// Finds the field a database constraint violation refers to.
+/// Postgres reports these as `Key (field)=(value) ...`.
+fn rejected_field(message: &str) -> Option<String> {
+    let start = message.find("Key (")? + "Key (".len();
+    let end = message[start..].find(')')? + start;
+    let field = &message[start..end];
+    match field.is_empty() || field.contains(',') {
+        true => None,
+        false => Some(field.to_string()),
+    }
+}
+
+/// Errors that can occur while seeding realm resources
+#[derive(Debug, Clone, PartialEq)]
+pub enum SeedError {
+    /// The request can not be processed as provided
+    InvalidRequest(String),
+    /// A field of the request has an invalid value
+    InvalidField {
+        /// The name of the field
+        field: String,
+        /// Why the value is invalid
+        reason: String,
+    },
+    /// The requested object does not exist
+    NotFound(String),
+    /// The request conflicts with the current state of the realm
+    Conflict(String),
+    /// A dependency returned an error
+    Dependency(DependencyError),
+    /// A dependency did not return the created object
+    NoObject,
+    /// The request was cancelled before it finished
+    Cancelled,
+}
+
+impl SeedError {
+    /// Creates the error for a failed call to a dependency
+    pub fn dependency(dependency: &str, status: tonic::Status) -> Self {
+        SeedError::Dependency(DependencyError::from_status(dependency, &status))
+    }
+
+    /// Creates the error for an invalid field value
+    pub fn invalid_field(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        SeedError::InvalidField {
+            field: field.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Prefixes the name of an invalid field with the path of the object
+    /// it belongs to, such as `vertipads[2]`
+    pub fn within(self, path: &str) -> Self {
+        match self {
+            SeedError::InvalidField { field, reason } => SeedError::InvalidField {
+                field: format!("{}.{}", path, field),
+                reason,
+            },
+            error => error,
+        }
+    }
+}
+
+impl Display for SeedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedError::InvalidRequest(e) => write!(f, "Invalid request: {}", e),
+            SeedError::InvalidField { field, reason } => {
+                write!(f, "Invalid value of field [{}]: {}", field, reason)
+            }
+            SeedError::NotFound(id) => write!(f, "Object not found: {}", id),
+            SeedError::Conflict(e) => write!(f, "Conflict: {}", e),
+            SeedError::Dependency(e) => write!(
+                f,
+                "Dependency error: {} returned {:?}: {}",
+                e.dependency, e.code, e.message
+            ),
+            SeedError::NoObject => write!(f, "No object returned."),
+            SeedError::Cancelled => write!(f, "Request cancelled."),
+        }
+    }
+}
+
+impl std::error::Error for SeedError {}
+
+/// Returns the object returned by svc-storage for an insert or update,
+/// or the first field svc-storage rejected
+pub fn storage_object<T>(
+    validation_result: Option<ValidationResult>,
+    object: Option<T>,
+) -> Result<T, SeedError> {
+    if let Some(error) = validation_result
+        .filter(|result| !result.success)
+        .and_then(|result| result.errors.into_iter().next())
+    {
+        return Err(SeedError::Dependency(DependencyError {
+            dependency: STORAGE.to_string(),
+            code: tonic::Code::InvalidArgument,
+            message: error.error,
+            field: Some(error.field),
+        }));
+    }
+
+    object.ok_or(SeedError::NoObject)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_dependency_error() {
+        crate::get_log_handle().await;
+        ut_info!("(test_dependency_error) Start.");
+
+        let status = tonic::Status::internal(
+            "insert or update on table \"vertipad\" violates foreign key constraint. \
+            Key (vertiport_id)=(0d9c7ab4) is not present in table \"vertiport\".",
+        );
+        let error = DependencyError::from_status(STORAGE, &status);
+        assert_eq!(error.dependency, "svc-storage");
+        assert_eq!(error.code, tonic::Code::Internal);
+        assert_eq!(error.field, Some("vertiport_id".to_string()));
+
+        let status = tonic::Status::unavailable("connection refused");
+        let error = DependencyError::from_status(GIS, &status);
+        assert_eq!(error.code, tonic::Code::Unavailable);
+        assert_eq!(error.field, None);
+
+        ut_info!("(test_dependency_error) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_storage_object() {
+        crate::get_log_handle().await;
+        ut_info!("(test_storage_object) Start.");
+
+        assert_eq!(storage_object(None, Some(1)), Ok(1));
+        assert_eq!(storage_object::<u8>(None, None), Err(SeedError::NoObject));
+
+        let mut validation_result = ValidationResult::default();
+        validation_result.errors.push(Default::default());
+        validation_result.errors[0].field = "registration_number".to_string();
+        validation_result.errors[0].error = "must not be empty".to_string();
+        let SeedError::Dependency(error) =
+            storage_object::<u8>(Some(validation_result), None).unwrap_err()
+        else {
+            panic!("expected a dependency error");
+        };
+        assert_eq!(error.code, tonic::Code::InvalidArgument);
+        assert_eq!(error.field, Some("registration_number".to_string()));
+
+        ut_info!("(test_storage_object) Success.");
+    }
+}
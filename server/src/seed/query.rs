@@ -0,0 +1,428 @@
+//! Read back of realm resources from svc-storage, next to their svc-gis view
+
+use super::{gis_view, SeedError, STORAGE};
+use crate::grpc::client::GrpcClients;
+use crate::rest::api::rest_types::*;
+use svc_storage_client_grpc::prelude::*;
+
+/// Maps a storage error to a [`SeedError`], keeping track of missing objects
+pub(super) fn storage_error(function: &str, id: Option<&str>, status: tonic::Status) -> SeedError {
+    seed_error!("({}) Error: {}.", function, status);
+    match (status.code(), id) {
+        (tonic::Code::NotFound, Some(id)) => SeedError::NotFound(id.to_string()),
+        _ => SeedError::dependency(STORAGE, status),
+    }
+}
+
+/// Builds a search filter matching all provided fields exactly.
+/// Fields without a value are ignored.
+fn search_filter(fields: Vec<(&str, Option<String>)>) -> AdvancedSearchFilter {
+    let mut filter: Option<AdvancedSearchFilter> = None;
+    for (field, value) in fields {
+        let Some(value) = value else {
+            continue;
+        };
+
+        filter = Some(match filter {
+            None => AdvancedSearchFilter::search_equals(field.to_string(), value),
+            Some(filter) => filter.and_equals(field.to_string(), value),
+        });
+    }
+
+    filter.unwrap_or_default()
+}
+
+/// Returns the name of a scanner type as used by [`AddScannerRequest`]
+pub(crate) fn scanner_type_name(scanner_type: i32) -> String {
+    [
+        (scanner::ScannerType::Underbelly, "underbelly"),
+        (scanner::ScannerType::Mobile, "mobile"),
+        (scanner::ScannerType::Locker, "locker"),
+        (scanner::ScannerType::Facility, "facility"),
+    ]
+    .iter()
+    .find(|(value, _)| *value as i32 == scanner_type)
+    .map(|(_, name)| name.to_string())
+    .unwrap_or_else(|| format!("unknown ({})", scanner_type))
+}
+
+/// Returns the name of a scanner status
+pub(crate) fn scanner_status_name(scanner_status: i32) -> String {
+    [
+        (scanner::ScannerStatus::Active, "active"),
+        (scanner::ScannerStatus::Disabled, "disabled"),
+    ]
+    .iter()
+    .find(|(value, _)| *value as i32 == scanner_status)
+    .map(|(_, name)| name.to_string())
+    .unwrap_or_else(|| format!("unknown ({})", scanner_status))
+}
+
+/// Converts a storage vertiport to its REST representation
+pub(super) fn vertiport_response(
+    object: vertiport::Object,
+) -> Result<VertiportResponse, SeedError> {
+    let data = object.data.ok_or(SeedError::NoObject)?;
+    let points = data
+        .geo_location
+        .and_then(|polygon| polygon.exterior)
+        .map(|exterior| exterior.points)
+        .unwrap_or_default();
+
+    Ok(VertiportResponse {
+        gis: gis_view::get_vertiport(&object.id),
+        id: object.id,
+        label: data.name,
+        address: data.description,
+        altitude: points.first().map(|p| p.altitude).unwrap_or_default(),
+        vertices: points.iter().map(|p| (p.latitude, p.longitude)).collect(),
+        schedule: data.schedule,
+    })
+}
+
+/// Converts a storage vertipad to its REST representation
+pub(super) fn vertipad_response(object: vertipad::Object) -> Result<VertipadResponse, SeedError> {
+    let data = object.data.ok_or(SeedError::NoObject)?;
+    let location = data.geo_location.unwrap_or_default();
+
+    Ok(VertipadResponse {
+        gis: gis_view::get_waypoint(&object.id),
+        id: object.id,
+        vertiport_id: data.vertiport_id,
+        label: data.name,
+        latitude: location.latitude,
+        longitude: location.longitude,
+        altitude: location.altitude,
+        enabled: data.enabled,
+        occupied: data.occupied,
+        schedule: data.schedule,
+    })
+}
+
+/// Converts a storage vehicle to its REST representation
+pub(super) fn aircraft_response(object: vehicle::Object) -> Result<AircraftResponse, SeedError> {
+    let data = object.data.ok_or(SeedError::NoObject)?;
+
+    Ok(AircraftResponse {
+        id: object.id,
+        nickname: data.description,
+        registration_number: data.registration_number,
+        serial_number: data.serial_number,
+        vehicle_model_id: data.vehicle_model_id,
+        hangar_id: data.hangar_id,
+        hangar_bay_id: data.hangar_bay_id,
+        schedule: data.schedule,
+    })
+}
+
+/// Converts a storage user to its REST representation
+pub(super) fn user_response(object: user::Object) -> Result<UserResponse, SeedError> {
+    let data = object.data.ok_or(SeedError::NoObject)?;
+
+    Ok(UserResponse {
+        id: object.id,
+        auth_method: user::AuthMethod::try_from(data.auth_method)
+            .ok()
+            .map(|method| method.as_str_name().to_lowercase()),
+        display_name: data.display_name,
+        email: data.email,
+    })
+}
+
+/// Converts a storage scanner to its REST representation
+pub(super) fn scanner_response(object: scanner::Object) -> Result<ScannerResponse, SeedError> {
+    let data = object.data.ok_or(SeedError::NoObject)?;
+
+    Ok(ScannerResponse {
+        id: object.id,
+        organization_id: data.organization_id,
+        scanner_type: scanner_type_name(data.scanner_type),
+        scanner_status: scanner_status_name(data.scanner_status),
+    })
+}
+
+/// Get a vertiport from storage
+pub async fn get_vertiport(
+    grpc_clients: &GrpcClients,
+    id: &str,
+) -> Result<VertiportResponse, SeedError> {
+    seed_debug!("(get_vertiport) entry [{}].", id);
+    let object = grpc_clients
+        .storage
+        .vertiport
+        .get_by_id(Id { id: id.to_string() })
+        .await
+        .map_err(|e| storage_error("get_vertiport", Some(id), e))?
+        .into_inner();
+
+    vertiport_response(object)
+}
+
+/// List vertiports from storage matching the filter
+pub async fn list_vertiports(
+    grpc_clients: &GrpcClients,
+    filter: &VertiportFilter,
+) -> Result<Vec<VertiportResponse>, SeedError> {
+    seed_debug!("(list_vertiports) entry [{:?}].", filter);
+    let filter = search_filter(vec![("name", filter.label.clone())]);
+
+    grpc_clients
+        .storage
+        .vertiport
+        .search(filter)
+        .await
+        .map_err(|e| storage_error("list_vertiports", None, e))?
+        .into_inner()
+        .list
+        .into_iter()
+        .map(vertiport_response)
+        .collect()
+}
+
+/// Get a vertipad from storage
+pub async fn get_vertipad(
+    grpc_clients: &GrpcClients,
+    id: &str,
+) -> Result<VertipadResponse, SeedError> {
+    seed_debug!("(get_vertipad) entry [{}].", id);
+    let object = grpc_clients
+        .storage
+        .vertipad
+        .get_by_id(Id { id: id.to_string() })
+        .await
+        .map_err(|e| storage_error("get_vertipad", Some(id), e))?
+        .into_inner();
+
+    vertipad_response(object)
+}
+
+/// List vertipads from storage matching the filter
+pub async fn list_vertipads(
+    grpc_clients: &GrpcClients,
+    filter: &VertipadFilter,
+) -> Result<Vec<VertipadResponse>, SeedError> {
+    seed_debug!("(list_vertipads) entry [{:?}].", filter);
+    let filter = search_filter(vec![
+        ("vertiport_id", filter.vertiport_id.clone()),
+        ("name", filter.label.clone()),
+    ]);
+
+    grpc_clients
+        .storage
+        .vertipad
+        .search(filter)
+        .await
+        .map_err(|e| storage_error("list_vertipads", None, e))?
+        .into_inner()
+        .list
+        .into_iter()
+        .map(vertipad_response)
+        .collect()
+}
+
+/// Get an aircraft from storage
+pub async fn get_aircraft(
+    grpc_clients: &GrpcClients,
+    id: &str,
+) -> Result<AircraftResponse, SeedError> {
+    seed_debug!("(get_aircraft) entry [{}].", id);
+    let object = grpc_clients
+        .storage
+        .vehicle
+        .get_by_id(Id { id: id.to_string() })
+        .await
+        .map_err(|e| storage_error("get_aircraft", Some(id), e))?
+        .into_inner();
+
+    aircraft_response(object)
+}
+
+/// List aircraft from storage matching the filter
+pub async fn list_aircraft(
+    grpc_clients: &GrpcClients,
+    filter: &AircraftFilter,
+) -> Result<Vec<AircraftResponse>, SeedError> {
+    seed_debug!("(list_aircraft) entry [{:?}].", filter);
+    let filter = search_filter(vec![
+        ("registration_number", filter.registration_number.clone()),
+        ("hangar_id", filter.hangar_id.clone()),
+    ]);
+
+    grpc_clients
+        .storage
+        .vehicle
+        .search(filter)
+        .await
+        .map_err(|e| storage_error("list_aircraft", None, e))?
+        .into_inner()
+        .list
+        .into_iter()
+        .map(aircraft_response)
+        .collect()
+}
+
+/// Get a user from storage
+pub async fn get_user(grpc_clients: &GrpcClients, id: &str) -> Result<UserResponse, SeedError> {
+    seed_debug!("(get_user) entry [{}].", id);
+    let object = grpc_clients
+        .storage
+        .user
+        .get_by_id(Id { id: id.to_string() })
+        .await
+        .map_err(|e| storage_error("get_user", Some(id), e))?
+        .into_inner();
+
+    user_response(object)
+}
+
+/// List users from storage matching the filter
+pub async fn list_users(
+    grpc_clients: &GrpcClients,
+    filter: &UserFilter,
+) -> Result<Vec<UserResponse>, SeedError> {
+    seed_debug!("(list_users) entry [{:?}].", filter);
+    let filter = search_filter(vec![
+        ("email", filter.email.clone()),
+        ("display_name", filter.display_name.clone()),
+    ]);
+
+    grpc_clients
+        .storage
+        .user
+        .search(filter)
+        .await
+        .map_err(|e| storage_error("list_users", None, e))?
+        .into_inner()
+        .list
+        .into_iter()
+        .map(user_response)
+        .collect()
+}
+
+/// Get a scanner from storage
+pub async fn get_scanner(
+    grpc_clients: &GrpcClients,
+    id: &str,
+) -> Result<ScannerResponse, SeedError> {
+    seed_debug!("(get_scanner) entry [{}].", id);
+    let object = grpc_clients
+        .storage
+        .scanner
+        .get_by_id(Id { id: id.to_string() })
+        .await
+        .map_err(|e| storage_error("get_scanner", Some(id), e))?
+        .into_inner();
+
+    scanner_response(object)
+}
+
+/// List scanners from storage matching the filter
+pub async fn list_scanners(
+    grpc_clients: &GrpcClients,
+    filter: &ScannerFilter,
+) -> Result<Vec<ScannerResponse>, SeedError> {
+    seed_debug!("(list_scanners) entry [{:?}].", filter);
+    let filter = search_filter(vec![("organization_id", filter.organization_id.clone())]);
+
+    grpc_clients
+        .storage
+        .scanner
+        .search(filter)
+        .await
+        .map_err(|e| storage_error("list_scanners", None, e))?
+        .into_inner()
+        .list
+        .into_iter()
+        .map(scanner_response)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_search_filter() {
+        crate::get_log_handle().await;
+        ut_info!("(test_search_filter) Start.");
+
+        let filter = search_filter(vec![("name", None), ("vertiport_id", None)]);
+        assert_eq!(filter, AdvancedSearchFilter::default());
+
+        let filter = search_filter(vec![
+            ("name", Some("Pad A".to_string())),
+            ("vertiport_id", None),
+            ("enabled", Some("true".to_string())),
+        ]);
+        assert_eq!(
+            filter,
+            AdvancedSearchFilter::search_equals("name".to_string(), "Pad A".to_string())
+                .and_equals("enabled".to_string(), "true".to_string())
+        );
+
+        ut_info!("(test_search_filter) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_vertiport_response() {
+        crate::get_log_handle().await;
+        ut_info!("(test_vertiport_response) Start.");
+
+        let points = vec![(52.0, 4.0), (52.1, 4.0), (52.1, 4.1), (52.0, 4.0)];
+        let object = vertiport::Object {
+            id: "test_vertiport_response".to_string(),
+            data: Some(vertiport::Data {
+                name: "Port".to_string(),
+                description: "Address".to_string(),
+                geo_location: Some(GeoPolygon {
+                    exterior: Some(GeoLineString {
+                        points: points
+                            .iter()
+                            .map(|p| GeoPoint {
+                                latitude: p.0,
+                                longitude: p.1,
+                                altitude: 12.0,
+                            })
+                            .collect(),
+                    }),
+                    interiors: vec![],
+                }),
+                schedule: None,
+                created_at: None,
+                updated_at: None,
+            }),
+        };
+
+        let response = vertiport_response(object).unwrap();
+        assert_eq!(response.label, "Port");
+        assert_eq!(response.vertices, points);
+        assert_eq!(response.altitude, 12.0);
+        assert!(response.gis.is_none());
+
+        let object = vertiport::Object {
+            id: "test_vertiport_response".to_string(),
+            data: None,
+        };
+        assert_eq!(vertiport_response(object).unwrap_err(), SeedError::NoObject);
+
+        ut_info!("(test_vertiport_response) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_scanner_names() {
+        crate::get_log_handle().await;
+        ut_info!("(test_scanner_names) Start.");
+
+        assert_eq!(
+            scanner_type_name(scanner::ScannerType::Locker as i32),
+            "locker"
+        );
+        assert_eq!(
+            scanner_status_name(scanner::ScannerStatus::Active as i32),
+            "active"
+        );
+        assert_eq!(scanner_type_name(-1), "unknown (-1)");
+
+        ut_info!("(test_scanner_names) Success.");
+    }
+}
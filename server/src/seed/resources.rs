@@ -0,0 +1,546 @@
+//! Creation of single realm resources in svc-storage and svc-gis
+
+use super::{
+    geometry, gis_view, ledger::ResourceKind, query, schedule, storage_object, vehicle_model, Saga,
+    SeedError, GIS, STORAGE,
+};
+use crate::grpc::client::GrpcClients;
+use crate::rest::api::rest_types::*;
+use chrono::Utc;
+use svc_gis_client_grpc::client::{
+    Coordinates, UpdateVertiportsRequest, UpdateWaypointsRequest, Vertiport, Waypoint,
+};
+use svc_gis_client_grpc::prelude::GisServiceClient;
+use svc_storage_client_grpc::prelude::{user::AuthMethod, *};
+use uuid::Uuid;
+
+/// Add a vertiport to storage and GIS, returns the new vertiport's ID
+pub async fn create_vertiport(
+    grpc_clients: &GrpcClients,
+    request: &AddVertiportRequest,
+    saga: &mut Saga,
+) -> Result<String, SeedError> {
+    seed_debug!("(create_vertiport) entry.");
+
+    geometry::validate_polygon("vertices", &request.vertices)?;
+    let schedule = Some(schedule::resolve(request.schedule.as_deref())?);
+    let points: Vec<GeoPoint> = request
+        .vertices
+        .iter()
+        .map(|vx| GeoPoint {
+            latitude: vx.0,
+            longitude: vx.1,
+            altitude: request.altitude,
+        })
+        .collect();
+
+    let data = vertiport::Data {
+        name: request.label.clone(),
+        description: request.address.clone(),
+        geo_location: Some(GeoPolygon {
+            exterior: Some(GeoLineString { points }),
+            interiors: vec![],
+        }),
+        schedule,
+        created_at: None,
+        updated_at: None,
+    };
+
+    let response = grpc_clients
+        .storage
+        .vertiport
+        .insert(data)
+        .await
+        .map_err(|e| {
+            seed_error!("(create_vertiport) Error: {}.", e);
+            SeedError::dependency(STORAGE, e)
+        })?
+        .into_inner();
+    let vertiport_id = storage_object(response.validation_result, response.object)
+        .map_err(|e| {
+            seed_error!("(create_vertiport) Error: {}.", e);
+            e
+        })?
+        .id;
+
+    saga.created(ResourceKind::Vertiport, &vertiport_id);
+
+    let clients = grpc_clients.clone();
+    let id = vertiport_id.clone();
+    saga.record(
+        format!("delete vertiport [{}] from svc-storage", id),
+        move || async move {
+            clients
+                .storage
+                .vertiport
+                .delete(Id { id })
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        },
+    );
+
+    let timestamp = Utc::now();
+    let vertiports = vec![Vertiport {
+        identifier: vertiport_id.clone(),
+        label: Some(request.label.clone()),
+        vertices: request
+            .vertices
+            .iter()
+            .map(|vx| Coordinates {
+                latitude: vx.0,
+                longitude: vx.1,
+            })
+            .collect(),
+        altitude_meters: 0.0,
+        timestamp_network: Some(timestamp.into()),
+    }];
+
+    grpc_clients
+        .gis
+        .update_vertiports(UpdateVertiportsRequest { vertiports })
+        .await
+        .map_err(|e| {
+            seed_error!("(create_vertiport) Error: {}.", e);
+            SeedError::dependency(GIS, e)
+        })?;
+
+    saga.not_removable(format!(
+        "remove vertiport [{}] from svc-gis: svc-gis does not support removing vertiports",
+        vertiport_id
+    ));
+
+    gis_view::record_vertiport(GisVertiport {
+        identifier: vertiport_id.clone(),
+        label: Some(request.label.clone()),
+        vertices: request.vertices.clone(),
+        timestamp,
+    });
+
+    Ok(vertiport_id)
+}
+
+/// Returns the polygon of a vertiport a vertipad is added to
+pub(super) async fn vertiport_polygon(
+    grpc_clients: &GrpcClients,
+    vertiport_id: &str,
+) -> Result<Vec<(f64, f64)>, SeedError> {
+    match query::get_vertiport(grpc_clients, vertiport_id).await {
+        Ok(vertiport) => Ok(vertiport.vertices),
+        Err(SeedError::NotFound(_)) => Err(SeedError::invalid_field(
+            "vertiport_id",
+            format!("vertiport [{}] does not exist", vertiport_id),
+        )),
+        Err(e) => Err(e),
+    }
+}
+
+/// Add a vertipad to storage and GIS, returns the new vertipad's ID
+///
+/// svc-gis knows vertipads as waypoints identified by the vertipad ID. The
+/// GIS waypoint has no altitude or label, those are only kept in svc-storage.
+pub async fn create_vertipad(
+    grpc_clients: &GrpcClients,
+    request: &AddVertipadRequest,
+    saga: &mut Saga,
+) -> Result<String, SeedError> {
+    seed_debug!("(create_vertipad) entry.");
+
+    let vertiport = vertiport_polygon(grpc_clients, &request.vertiport_id).await?;
+    geometry::validate_pad_position(
+        &request.vertiport_id,
+        &vertiport,
+        request.latitude,
+        request.longitude,
+    )?;
+    let schedule = Some(schedule::resolve(request.schedule.as_deref())?);
+    let data = vertipad::Data {
+        vertiport_id: request.vertiport_id.clone(),
+        name: request.label.clone(),
+        geo_location: Some(GeoPoint {
+            latitude: request.latitude,
+            longitude: request.longitude,
+            altitude: request.altitude,
+        }),
+        enabled: true,
+        occupied: false,
+        schedule,
+        created_at: None,
+        updated_at: None,
+    };
+
+    let response = grpc_clients
+        .storage
+        .vertipad
+        .insert(data)
+        .await
+        .map_err(|e| {
+            seed_error!("(create_vertipad) Error: {}.", e);
+            SeedError::dependency(STORAGE, e)
+        })?
+        .into_inner();
+    let vertipad_id = storage_object(response.validation_result, response.object)
+        .map_err(|e| {
+            seed_error!("(create_vertipad) Error: {}.", e);
+            e
+        })?
+        .id;
+
+    saga.created(ResourceKind::Vertipad, &vertipad_id);
+
+    let clients = grpc_clients.clone();
+    let id = vertipad_id.clone();
+    saga.record(
+        format!("delete vertipad [{}] from svc-storage", id),
+        move || async move {
+            clients
+                .storage
+                .vertipad
+                .delete(Id { id })
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        },
+    );
+
+    let waypoints = vec![Waypoint {
+        identifier: vertipad_id.clone(),
+        location: Some(Coordinates {
+            latitude: request.latitude,
+            longitude: request.longitude,
+        }),
+    }];
+
+    grpc_clients
+        .gis
+        .update_waypoints(UpdateWaypointsRequest { waypoints })
+        .await
+        .map_err(|e| {
+            seed_error!("(create_vertipad) Error: {}.", e);
+            SeedError::dependency(GIS, e)
+        })?;
+
+    saga.not_removable(format!(
+        "remove vertipad [{}] from svc-gis: svc-gis does not support removing waypoints",
+        vertipad_id
+    ));
+
+    gis_view::record_waypoint(GisWaypoint {
+        identifier: vertipad_id.clone(),
+        latitude: request.latitude,
+        longitude: request.longitude,
+        timestamp: Utc::now(),
+    });
+
+    Ok(vertipad_id)
+}
+
+/// Checks that the hangar is an existing vertiport, and the hangar bay a
+/// vertipad of that vertiport
+pub(super) async fn validate_hangar(
+    grpc_clients: &GrpcClients,
+    hangar_id: &str,
+    hangar_bay_id: &str,
+) -> Result<(), SeedError> {
+    match query::get_vertiport(grpc_clients, hangar_id).await {
+        Ok(_) => (),
+        Err(SeedError::NotFound(_)) => {
+            return Err(SeedError::invalid_field(
+                "hangar_id",
+                format!("vertiport [{}] does not exist", hangar_id),
+            ))
+        }
+        Err(e) => return Err(e),
+    }
+
+    let vertipad = match query::get_vertipad(grpc_clients, hangar_bay_id).await {
+        Ok(vertipad) => vertipad,
+        Err(SeedError::NotFound(_)) => {
+            return Err(SeedError::invalid_field(
+                "hangar_bay_id",
+                format!("vertipad [{}] does not exist", hangar_bay_id),
+            ))
+        }
+        Err(e) => return Err(e),
+    };
+
+    match vertipad.vertiport_id == hangar_id {
+        true => Ok(()),
+        false => Err(SeedError::invalid_field(
+            "hangar_bay_id",
+            format!(
+                "vertipad [{}] belongs to vertiport [{}], not to hangar [{}]",
+                hangar_bay_id, vertipad.vertiport_id, hangar_id
+            ),
+        )),
+    }
+}
+
+/// Returns the provided ID if it's a valid UUID, or a random ID otherwise
+fn id_or_random(field: &str, id: Option<&str>) -> Result<String, SeedError> {
+    match id {
+        Some(id) => Uuid::parse_str(id)
+            .map(|id| id.to_string())
+            .map_err(|e| SeedError::invalid_field(field, format!("invalid ID [{}]: {}", id, e))),
+        None => Ok(Uuid::new_v4().to_string()),
+    }
+}
+
+/// Add an aircraft to storage, returns the new vehicle's ID
+///
+/// The hangar has to be an existing vertiport, the hangar bay one of its
+/// vertipads. The vehicle model can be referenced by its name in the
+/// catalog. Vehicle model and serial number are random unless provided.
+pub async fn create_aircraft(
+    grpc_clients: &GrpcClients,
+    request: &AddAircraftRequest,
+    saga: &mut Saga,
+) -> Result<String, SeedError> {
+    seed_debug!("(create_aircraft) entry.");
+
+    let schedule = Some(schedule::resolve(request.schedule.as_deref())?);
+    let vehicle_model_id = match (&request.vehicle_model, &request.vehicle_model_id) {
+        (Some(_), Some(_)) => {
+            return Err(SeedError::invalid_field(
+                "vehicle_model",
+                "provide either a vehicle model or a vehicle model ID, not both",
+            ))
+        }
+        (Some(name), None) => {
+            vehicle_model::get(name)
+                .map_err(|e| match e {
+                    SeedError::NotFound(_) => SeedError::invalid_field(
+                        "vehicle_model",
+                        format!("unknown vehicle model [{}]", name),
+                    ),
+                    e => e,
+                })?
+                .id
+        }
+        (None, id) => id_or_random("vehicle_model_id", id.as_deref())?,
+    };
+    let serial_number = match &request.serial_number {
+        Some(serial_number) if serial_number.trim().is_empty() => {
+            return Err(SeedError::invalid_field(
+                "serial_number",
+                "serial number must not be empty",
+            ))
+        }
+        Some(serial_number) => serial_number.clone(),
+        None => Uuid::new_v4().to_string(),
+    };
+    validate_hangar(grpc_clients, &request.hangar_id, &request.hangar_bay_id).await?;
+
+    let response = grpc_clients
+        .storage
+        .vehicle
+        .insert(vehicle::Data {
+            vehicle_model_id,
+            registration_number: request.registration_number.clone(),
+            serial_number,
+            description: Some(request.nickname.clone()),
+            hangar_id: Some(request.hangar_id.clone()),
+            hangar_bay_id: Some(request.hangar_bay_id.clone()),
+            schedule,
+            ..Default::default()
+        })
+        .await
+        .map_err(|e| {
+            seed_error!("(create_aircraft) Error: {}.", e);
+            SeedError::dependency(STORAGE, e)
+        })?
+        .into_inner();
+    let aircraft_id = storage_object(response.validation_result, response.object)
+        .map_err(|e| {
+            seed_error!("(create_aircraft) Error: {}.", e);
+            e
+        })?
+        .id;
+
+    saga.created(ResourceKind::Aircraft, &aircraft_id);
+
+    let clients = grpc_clients.clone();
+    let id = aircraft_id.clone();
+    saga.record(
+        format!("delete vehicle [{}] from svc-storage", id),
+        move || async move {
+            clients
+                .storage
+                .vehicle
+                .delete(Id { id })
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        },
+    );
+
+    Ok(aircraft_id)
+}
+
+/// Add a user to storage, returns the new user's ID
+pub async fn create_user(
+    grpc_clients: &GrpcClients,
+    request: &AddUserRequest,
+    saga: &mut Saga,
+) -> Result<String, SeedError> {
+    seed_debug!("(create_user) entry.");
+
+    let auth_method = AuthMethod::Local as i32;
+    let response = grpc_clients
+        .storage
+        .user
+        .insert(user::Data {
+            auth_method,
+            display_name: request.display_name.clone(),
+            email: request.email.clone(),
+        })
+        .await
+        .map_err(|e| {
+            seed_error!("(create_user) Error: {}.", e);
+            SeedError::dependency(STORAGE, e)
+        })?
+        .into_inner();
+    let user_id = storage_object(response.validation_result, response.object)
+        .map_err(|e| {
+            seed_error!("(create_user) Error: {}.", e);
+            e
+        })?
+        .id;
+
+    saga.created(ResourceKind::User, &user_id);
+
+    let clients = grpc_clients.clone();
+    let id = user_id.clone();
+    saga.record(
+        format!("delete user [{}] from svc-storage", id),
+        move || async move {
+            clients
+                .storage
+                .user
+                .delete(Id { id })
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        },
+    );
+
+    Ok(user_id)
+}
+
+impl TryFrom<AddScannerRequest> for scanner::Data {
+    type Error = String;
+
+    fn try_from(value: AddScannerRequest) -> Result<Self, Self::Error> {
+        let scanner_type = match value.scanner_type.as_str() {
+            "underbelly" => scanner::ScannerType::Underbelly as i32,
+            "mobile" => scanner::ScannerType::Mobile as i32,
+            "locker" => scanner::ScannerType::Locker as i32,
+            "facility" => scanner::ScannerType::Facility as i32,
+            _ => return Err("Invalid scanner type.".to_string()),
+        };
+
+        Ok(scanner::Data {
+            organization_id: value.organization_id,
+            scanner_type,
+            scanner_status: scanner::ScannerStatus::Active as i32,
+        })
+    }
+}
+
+/// Add a scanner to storage, returns the new scanner's ID
+pub async fn create_scanner(
+    grpc_clients: &GrpcClients,
+    request: &AddScannerRequest,
+    saga: &mut Saga,
+) -> Result<String, SeedError> {
+    seed_debug!("(create_scanner) entry.");
+
+    let data: scanner::Data = request.clone().try_into().map_err(|e: String| {
+        seed_error!("(create_scanner) Error: {}.", e);
+        SeedError::invalid_field("scanner_type", e)
+    })?;
+
+    let response = grpc_clients
+        .storage
+        .scanner
+        .insert(data)
+        .await
+        .map_err(|e| {
+            seed_error!("(create_scanner) Error: {}.", e);
+            SeedError::dependency(STORAGE, e)
+        })?
+        .into_inner();
+    let scanner_id = storage_object(response.validation_result, response.object)
+        .map_err(|e| {
+            seed_error!("(create_scanner) Error: {}.", e);
+            e
+        })?
+        .id;
+
+    saga.created(ResourceKind::Scanner, &scanner_id);
+
+    let clients = grpc_clients.clone();
+    let id = scanner_id.clone();
+    saga.record(
+        format!("delete scanner [{}] from svc-storage", id),
+        move || async move {
+            clients
+                .storage
+                .scanner
+                .delete(Id { id })
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        },
+    );
+
+    Ok(scanner_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scanner_data_try_from() {
+        crate::get_log_handle().await;
+        ut_info!("(test_scanner_data_try_from) Start.");
+
+        let request = AddScannerRequest {
+            organization_id: Uuid::new_v4().to_string(),
+            scanner_type: "mobile".to_string(),
+            run_id: None,
+        };
+        let data: scanner::Data = request.clone().try_into().unwrap();
+        assert_eq!(data.organization_id, request.organization_id);
+        assert_eq!(data.scanner_type, scanner::ScannerType::Mobile as i32);
+
+        let request = AddScannerRequest {
+            scanner_type: "invalid".to_string(),
+            ..request
+        };
+        let result: Result<scanner::Data, String> = request.try_into();
+        assert!(result.is_err());
+
+        ut_info!("(test_scanner_data_try_from) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_id_or_random() {
+        crate::get_log_handle().await;
+        ut_info!("(test_id_or_random) Start.");
+
+        let id = Uuid::new_v4().to_string();
+        assert_eq!(id_or_random("vehicle_model_id", Some(&id)), Ok(id));
+        assert!(Uuid::parse_str(&id_or_random("vehicle_model_id", None).unwrap()).is_ok());
+
+        let SeedError::InvalidField { field, .. } =
+            id_or_random("vehicle_model_id", Some("model-x")).unwrap_err()
+        else {
+            panic!("expected an invalid field error");
+        };
+        assert_eq!(field, "vehicle_model_id");
+
+        ut_info!("(test_id_or_random) Success.");
+    }
+}
@@ -0,0 +1,274 @@
+//! Compensation of remote mutations when a multi-service seed partially fails
+//!
+//! Every successful remote mutation records an undo action with the
+//! [`Saga`]. If a later step fails, the recorded undo actions are executed
+//! in reverse order so svc-storage and svc-gis don't drift apart.
+//! Mutations which can't be undone, such as the vertiports and waypoints
+//! sent to svc-gis, are only reported.
+//!
+//! Resources created for a run are added to the run's [`ledger`] once the
+//! request succeeded.
+
+use super::ledger::{self, ResourceKind};
+use super::SeedError;
+use crate::rest::api::rest_types::RollbackReport;
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+
+/// Future returned by an undo action
+type UndoFuture = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+
+/// Boxed undo action
+type UndoAction = Box<dyn FnOnce() -> UndoFuture + Send>;
+
+/// Listener called with the number of resources created so far
+type ProgressListener = Box<dyn Fn(usize) + Send + Sync>;
+
+/// A failed seed request, including the outcome of its rollback
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeedFailure {
+    /// The error which caused the request to fail
+    pub error: SeedError,
+    /// The outcome of the rollback, if any mutations had to be undone
+    pub rollback: Option<RollbackReport>,
+}
+
+impl From<SeedError> for SeedFailure {
+    fn from(error: SeedError) -> Self {
+        SeedFailure {
+            error,
+            rollback: None,
+        }
+    }
+}
+
+impl Display for SeedFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.rollback {
+            Some(rollback) if !rollback.complete => {
+                write!(f, "{} (rollback incomplete)", self.error)
+            }
+            _ => write!(f, "{}", self.error),
+        }
+    }
+}
+
+/// Records undo actions of remote mutations
+#[derive(Default)]
+pub struct Saga {
+    run_id: Option<String>,
+    created: Vec<(ResourceKind, String)>,
+    undo_actions: Vec<(String, UndoAction)>,
+    not_removable: Vec<String>,
+    progress: Option<ProgressListener>,
+}
+
+impl std::fmt::Debug for Saga {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Saga")
+            .field("run_id", &self.run_id)
+            .field("created", &self.created)
+            .field(
+                "undo_actions",
+                &self
+                    .undo_actions
+                    .iter()
+                    .map(|(description, _)| description)
+                    .collect::<Vec<_>>(),
+            )
+            .field("not_removable", &self.not_removable)
+            .finish()
+    }
+}
+
+impl Saga {
+    /// Creates a saga for a request of the provided run, if any
+    pub fn for_run(run_id: Option<String>) -> Self {
+        Saga {
+            run_id,
+            ..Default::default()
+        }
+    }
+
+    /// Calls `listener` with the number of resources created so far each
+    /// time a resource has been created
+    pub fn with_progress(mut self, listener: impl Fn(usize) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Box::new(listener));
+        self
+    }
+
+    /// Records a resource which has been created
+    pub fn created(&mut self, kind: ResourceKind, id: impl Into<String>) {
+        self.created.push((kind, id.into()));
+        if let Some(listener) = &self.progress {
+            listener(self.created.len());
+        }
+    }
+
+    /// Records the undo action for a mutation which has been executed
+    pub fn record<F, Fut>(&mut self, description: impl Into<String>, undo: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        let description = description.into();
+        seed_debug!("(record) recorded undo action: {}.", description);
+        self.undo_actions
+            .push((description, Box::new(move || Box::pin(undo()))));
+    }
+
+    /// Records a mutation which has been executed but can't be undone.
+    /// It is reported by the rollback without making it incomplete.
+    pub fn not_removable(&mut self, description: impl Into<String>) {
+        let description = description.into();
+        seed_debug!("(not_removable) recorded mutation: {}.", description);
+        self.not_removable.push(description);
+    }
+
+    /// Returns the number of recorded undo actions
+    pub fn len(&self) -> usize {
+        self.undo_actions.len()
+    }
+
+    /// Returns true if no undo actions have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.undo_actions.is_empty()
+    }
+
+    /// Executes all recorded undo actions in reverse order
+    pub async fn rollback(self) -> RollbackReport {
+        let mut report = RollbackReport {
+            complete: true,
+            undone: vec![],
+            failed: vec![],
+            not_removable: self.not_removable,
+        };
+
+        for (description, undo) in self.undo_actions.into_iter().rev() {
+            match undo().await {
+                Ok(()) => {
+                    seed_info!("(rollback) undone: {}.", description);
+                    report.undone.push(description);
+                }
+                Err(e) => {
+                    seed_error!("(rollback) could not undo [{}]: {}.", description, e);
+                    report.complete = false;
+                    report.failed.push(format!("{}: {}", description, e));
+                }
+            }
+        }
+
+        report
+    }
+
+    /// Keeps all mutations if the result is ok, rolls them back otherwise.
+    /// Created resources are added to the ledger of the run, if any.
+    pub async fn finish<T>(self, result: Result<T, SeedError>) -> Result<T, SeedFailure> {
+        match result {
+            Ok(value) => {
+                if let Some(run_id) = &self.run_id {
+                    ledger::record(run_id, self.created);
+                }
+                Ok(value)
+            }
+            Err(error) if self.is_empty() && self.not_removable.is_empty() => Err(error.into()),
+            Err(error) => {
+                seed_warn!(
+                    "(finish) rolling back {} mutations after error: {}.",
+                    self.len(),
+                    error
+                );
+                Err(SeedFailure {
+                    error,
+                    rollback: Some(self.rollback().await),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_rollback_reverse_order() {
+        crate::get_log_handle().await;
+        ut_info!("(test_rollback_reverse_order) Start.");
+
+        let executed = Arc::new(Mutex::new(vec![]));
+        let mut saga = Saga::default();
+        for name in ["first", "second", "third"] {
+            let executed = executed.clone();
+            saga.record(name, move || async move {
+                executed.lock().unwrap().push(name);
+                match name {
+                    "second" => Err("unavailable".to_string()),
+                    _ => Ok(()),
+                }
+            });
+        }
+        assert_eq!(saga.len(), 3);
+
+        let report = saga.rollback().await;
+        assert_eq!(*executed.lock().unwrap(), vec!["third", "second", "first"]);
+        assert!(!report.complete);
+        assert_eq!(report.undone, vec!["third", "first"]);
+        assert_eq!(report.failed, vec!["second: unavailable"]);
+
+        ut_info!("(test_rollback_reverse_order) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_finish() {
+        crate::get_log_handle().await;
+        ut_info!("(test_finish) Start.");
+
+        // Successful results keep the mutations
+        let mut saga = Saga::default();
+        saga.record("never", || async { Err("should not run".to_string()) });
+        assert_eq!(saga.finish(Ok(1)).await, Ok(1));
+
+        // Failures without mutations have nothing to roll back
+        let saga = Saga::default();
+        let error = SeedError::NoObject;
+        let result: Result<(), SeedFailure> = saga.finish(Err(error.clone())).await;
+        assert_eq!(result.unwrap_err(), SeedFailure::from(error.clone()));
+
+        // Failures with mutations are rolled back
+        let mut saga = Saga::default();
+        saga.record("delete vertiport", || async { Ok(()) });
+        let failure = saga.finish::<()>(Err(error.clone())).await.unwrap_err();
+        assert_eq!(failure.error, error);
+        let rollback = failure.rollback.unwrap();
+        assert!(rollback.complete);
+        assert_eq!(rollback.undone, vec!["delete vertiport"]);
+
+        // Mutations which can't be undone don't make the rollback incomplete
+        let mut saga = Saga::default();
+        saga.record("delete vertiport", || async { Ok(()) });
+        saga.not_removable("remove vertiport from svc-gis");
+        let failure = saga.finish::<()>(Err(error.clone())).await.unwrap_err();
+        assert_eq!(failure.to_string(), error.to_string());
+        let rollback = failure.rollback.unwrap();
+        assert!(rollback.complete);
+        assert_eq!(rollback.undone, vec!["delete vertiport"]);
+        assert_eq!(
+            rollback.not_removable,
+            vec!["remove vertiport from svc-gis"]
+        );
+
+        // Created resources are added to the ledger of the run
+        let mut saga = Saga::for_run(Some("test_finish".to_string()));
+        saga.created(ResourceKind::User, "test_finish_user");
+        assert_eq!(saga.finish(Ok(())).await, Ok(()));
+        assert_eq!(
+            ledger::resources("test_finish"),
+            Some(vec![(ResourceKind::User, "test_finish_user".to_string())])
+        );
+
+        ut_info!("(test_finish) Success.");
+    }
+}
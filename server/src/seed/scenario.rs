@@ -0,0 +1,283 @@
+//! Declarative scenarios describing a set of related realm resources
+//!
+//! A scenario lists vertiports, vertipads, aircraft, users and scanners.
+//! Entries reference each other by symbolic name, which gets replaced by
+//! the ID of the created resource once it exists.
+
+use super::{geometry, resources, Saga, SeedError};
+use crate::grpc::client::GrpcClients;
+use crate::rest::api::rest_types::Scenario;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use uuid::Uuid;
+
+/// Supported scenario file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScenarioFormat {
+    /// YAML document
+    Yaml,
+    /// JSON document
+    Json,
+}
+
+/// Parse a scenario from the provided document
+pub fn parse(content: &str, format: ScenarioFormat) -> Result<Scenario, SeedError> {
+    match format {
+        ScenarioFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        ScenarioFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+    }
+    .map_err(|e| SeedError::InvalidRequest(format!("could not parse scenario: {}", e)))
+}
+
+/// Load a scenario from a YAML or JSON file, based on the file extension
+pub fn from_file(path: impl AsRef<Path>) -> Result<Scenario, SeedError> {
+    let path = path.as_ref();
+    let format = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => ScenarioFormat::Json,
+        _ => ScenarioFormat::Yaml,
+    };
+
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        SeedError::InvalidRequest(format!(
+            "could not read scenario file [{}]: {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    parse(&content, format)
+}
+
+/// Checks that all symbolic names are unique and all references can be
+/// resolved, either to an entry of the right kind or to an existing ID.
+pub fn validate(scenario: &Scenario) -> Result<(), SeedError> {
+    let mut names: HashSet<&str> = HashSet::new();
+    let all_names = scenario
+        .vertiports
+        .iter()
+        .map(|e| &e.name)
+        .chain(scenario.vertipads.iter().map(|e| &e.name))
+        .chain(scenario.aircraft.iter().map(|e| &e.name))
+        .chain(scenario.users.iter().map(|e| &e.name))
+        .chain(scenario.scanners.iter().map(|e| &e.name));
+
+    for name in all_names {
+        if !names.insert(name.as_str()) {
+            return Err(SeedError::InvalidRequest(format!(
+                "duplicate scenario name [{}]",
+                name
+            )));
+        }
+    }
+
+    let vertiports: HashSet<&str> = scenario
+        .vertiports
+        .iter()
+        .map(|e| e.name.as_str())
+        .collect();
+    let vertipads: HashSet<&str> = scenario.vertipads.iter().map(|e| e.name.as_str()).collect();
+
+    for (index, entry) in scenario.vertiports.iter().enumerate() {
+        geometry::validate_polygon("vertices", &entry.vertiport.vertices)
+            .map_err(|e| e.within(&format!("vertiports[{}]", index)))?;
+    }
+
+    for (index, entry) in scenario.vertipads.iter().enumerate() {
+        check_reference(&entry.name, &entry.vertipad.vertiport_id, &vertiports)?;
+
+        // Pads of existing vertiports are checked when they are created
+        let Some(vertiport) = scenario
+            .vertiports
+            .iter()
+            .find(|vertiport| vertiport.name == entry.vertipad.vertiport_id)
+        else {
+            continue;
+        };
+
+        geometry::validate_pad_position(
+            &vertiport.name,
+            &vertiport.vertiport.vertices,
+            entry.vertipad.latitude,
+            entry.vertipad.longitude,
+        )
+        .map_err(|e| e.within(&format!("vertipads[{}]", index)))?;
+    }
+
+    for entry in &scenario.aircraft {
+        check_reference(&entry.name, &entry.aircraft.hangar_id, &vertiports)?;
+        check_reference(&entry.name, &entry.aircraft.hangar_bay_id, &vertipads)?;
+    }
+
+    Ok(())
+}
+
+/// A reference is valid if it names an entry of the expected kind, or if
+/// it is the ID of an existing resource.
+fn check_reference(entry: &str, reference: &str, known: &HashSet<&str>) -> Result<(), SeedError> {
+    if known.contains(reference) || Uuid::parse_str(reference).is_ok() {
+        return Ok(());
+    }
+
+    Err(SeedError::InvalidRequest(format!(
+        "unresolved reference [{}] in scenario entry [{}]",
+        reference, entry
+    )))
+}
+
+/// Replaces a symbolic name with the ID of the created resource
+fn resolve(ids: &BTreeMap<String, String>, reference: &str) -> String {
+    ids.get(reference)
+        .cloned()
+        .unwrap_or_else(|| reference.to_string())
+}
+
+/// Creates all resources of the scenario in dependency order, recording
+/// the undo actions of each created resource with the provided [`Saga`].
+/// Returns the created IDs by symbolic name.
+pub async fn apply(
+    grpc_clients: &GrpcClients,
+    scenario: &Scenario,
+    saga: &mut Saga,
+) -> Result<BTreeMap<String, String>, SeedError> {
+    seed_debug!("(apply) entry.");
+    validate(scenario)?;
+
+    let mut ids: BTreeMap<String, String> = BTreeMap::new();
+
+    for entry in &scenario.vertiports {
+        let id = resources::create_vertiport(grpc_clients, &entry.vertiport, saga).await?;
+        seed_debug!("(apply) created vertiport [{}]: {}.", entry.name, id);
+        ids.insert(entry.name.clone(), id);
+    }
+
+    for entry in &scenario.vertipads {
+        let mut request = entry.vertipad.clone();
+        request.vertiport_id = resolve(&ids, &request.vertiport_id);
+
+        let id = resources::create_vertipad(grpc_clients, &request, saga).await?;
+        seed_debug!("(apply) created vertipad [{}]: {}.", entry.name, id);
+        ids.insert(entry.name.clone(), id);
+    }
+
+    for entry in &scenario.aircraft {
+        let mut request = entry.aircraft.clone();
+        request.hangar_id = resolve(&ids, &request.hangar_id);
+        request.hangar_bay_id = resolve(&ids, &request.hangar_bay_id);
+
+        let id = resources::create_aircraft(grpc_clients, &request, saga).await?;
+        seed_debug!("(apply) created aircraft [{}]: {}.", entry.name, id);
+        ids.insert(entry.name.clone(), id);
+    }
+
+    for entry in &scenario.users {
+        let id = resources::create_user(grpc_clients, &entry.user, saga).await?;
+        seed_debug!("(apply) created user [{}]: {}.", entry.name, id);
+        ids.insert(entry.name.clone(), id);
+    }
+
+    for entry in &scenario.scanners {
+        let id = resources::create_scanner(grpc_clients, &entry.scanner, saga).await?;
+        seed_debug!("(apply) created scanner [{}]: {}.", entry.name, id);
+        ids.insert(entry.name.clone(), id);
+    }
+
+    seed_info!("(apply) created {} resources.", ids.len());
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENARIO: &str = r#"
+vertiports:
+  - name: port_a
+    label: Port A
+    address: 1 Main Street
+    vertices: [[52.37, 4.89], [52.38, 4.90], [52.38, 4.89], [52.37, 4.89]]
+    altitude: 10.0
+vertipads:
+  - name: pad_a1
+    vertiport_id: port_a
+    latitude: 52.375
+    longitude: 4.893
+    altitude: 10.0
+    label: Pad A1
+aircraft:
+  - name: aircraft_1
+    nickname: Mantis
+    registration_number: N12345
+    hangar_id: port_a
+    hangar_bay_id: pad_a1
+"#;
+
+    #[tokio::test]
+    async fn test_parse_and_validate() {
+        crate::get_log_handle().await;
+        ut_info!("(test_parse_and_validate) Start.");
+
+        let scenario = parse(SCENARIO, ScenarioFormat::Yaml).unwrap();
+        assert_eq!(scenario.vertiports.len(), 1);
+        assert_eq!(scenario.vertipads.len(), 1);
+        assert_eq!(scenario.aircraft.len(), 1);
+        assert!(scenario.users.is_empty());
+        assert_eq!(scenario.vertipads[0].vertipad.vertiport_id, "port_a");
+        assert!(validate(&scenario).is_ok());
+
+        let json = serde_json::to_string(&scenario).unwrap();
+        let scenario = parse(&json, ScenarioFormat::Json).unwrap();
+        assert!(validate(&scenario).is_ok());
+
+        ut_info!("(test_parse_and_validate) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_validate_references() {
+        crate::get_log_handle().await;
+        ut_info!("(test_validate_references) Start.");
+
+        let mut scenario = parse(SCENARIO, ScenarioFormat::Yaml).unwrap();
+
+        // Existing resources can be referenced by ID
+        scenario.aircraft[0].aircraft.hangar_bay_id = Uuid::new_v4().to_string();
+        assert!(validate(&scenario).is_ok());
+
+        // References have to point to an entry of the right kind
+        scenario.aircraft[0].aircraft.hangar_bay_id = "port_a".to_string();
+        assert!(validate(&scenario).is_err());
+
+        scenario.aircraft[0].aircraft.hangar_bay_id = "unknown".to_string();
+        assert!(validate(&scenario).is_err());
+
+        // Pads have to be within their vertiport
+        let mut scenario = parse(SCENARIO, ScenarioFormat::Yaml).unwrap();
+        scenario.vertipads[0].vertipad.latitude = 52.39;
+        assert_eq!(
+            validate(&scenario).unwrap_err(),
+            SeedError::invalid_field(
+                "vertipads[0].position",
+                "position (52.39, 4.893) is outside of vertiport [port_a]"
+            )
+        );
+
+        // Names have to be unique
+        let mut scenario = parse(SCENARIO, ScenarioFormat::Yaml).unwrap();
+        scenario.vertipads[0].name = "port_a".to_string();
+        assert!(validate(&scenario).is_err());
+
+        ut_info!("(test_validate_references) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_resolve() {
+        crate::get_log_handle().await;
+        ut_info!("(test_resolve) Start.");
+
+        let mut ids = BTreeMap::new();
+        ids.insert("port_a".to_string(), "some-id".to_string());
+        assert_eq!(resolve(&ids, "port_a"), "some-id");
+        assert_eq!(resolve(&ids, "other-id"), "other-id");
+
+        ut_info!("(test_resolve) Success.");
+    }
+}
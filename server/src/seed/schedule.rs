@@ -0,0 +1,379 @@
+//! Parsing and validation of resource schedules
+//!
+//! A schedule is a list of recurring events, using the calendar format
+//! understood by svc-storage and svc-scheduler:
+//!
+//! ```text
+//! DTSTART:20221020T180000Z;DURATION:PT24H
+//! RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR,SA,SU
+//! ```
+//!
+//! Each event starts with a `DTSTART` and `DURATION` header line, followed
+//! by one or more `RRULE`, `EXRULE`, `RDATE` or `EXDATE` lines.
+
+use super::SeedError;
+use chrono::{NaiveDate, NaiveDateTime};
+use std::collections::HashSet;
+
+/// Schedule used when a request doesn't provide one: always open
+pub const DEFAULT_SCHEDULE: &str = "DTSTART:20221020T180000Z;DURATION:PT24H
+RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR,SA,SU";
+
+/// Valid values for the FREQ rule part
+const FREQUENCIES: [&str; 7] = [
+    "SECONDLY", "MINUTELY", "HOURLY", "DAILY", "WEEKLY", "MONTHLY", "YEARLY",
+];
+
+/// Valid week days for the BYDAY and WKST rule parts
+const WEEKDAYS: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+/// Returns the normalized schedule for the provided optional schedule text,
+/// falling back to the [`DEFAULT_SCHEDULE`] if none was provided.
+pub fn resolve(schedule: Option<&str>) -> Result<String, SeedError> {
+    parse(schedule.unwrap_or(DEFAULT_SCHEDULE)).map_err(|e| SeedError::invalid_field("schedule", e))
+}
+
+/// Validates the schedule text and returns it in normalized form, with
+/// surrounding whitespace and empty lines removed.
+pub fn parse(schedule: &str) -> Result<String, String> {
+    let lines: Vec<&str> = schedule
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    if lines.is_empty() {
+        return Err("schedule is empty".to_string());
+    }
+
+    // line number of the current event's header and if it has a recurrence
+    let mut event_line = 1;
+    let mut has_rule = false;
+    for (index, line) in lines.iter().enumerate() {
+        let result = if line.starts_with("DTSTART") {
+            if index > 0 && !has_rule {
+                break;
+            }
+            event_line = index + 1;
+            has_rule = false;
+            parse_header(line)
+        } else if index == 0 {
+            Err("schedule must start with a DTSTART".to_string())
+        } else {
+            has_rule |= line.starts_with("RRULE:") || line.starts_with("RDATE:");
+            parse_rule_line(line)
+        };
+
+        result.map_err(|e| format!("line {}: {}", index + 1, e))?;
+    }
+
+    if !has_rule {
+        return Err(format!("line {}: event has no RRULE or RDATE", event_line));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+/// Parses an event header: `DTSTART:<date-time>;DURATION:<duration>`
+fn parse_header(line: &str) -> Result<(), String> {
+    let (dtstart, duration) = line
+        .split_once(';')
+        .ok_or_else(|| "expected DTSTART:<date-time>;DURATION:<duration>".to_string())?;
+
+    let dtstart = dtstart
+        .strip_prefix("DTSTART:")
+        .ok_or_else(|| "expected DTSTART:<date-time>".to_string())?;
+    parse_datetime(dtstart).map_err(|e| format!("DTSTART: {}", e))?;
+
+    let duration = duration
+        .strip_prefix("DURATION:")
+        .ok_or_else(|| "expected DURATION:<duration> after DTSTART".to_string())?;
+    parse_duration(duration).map_err(|e| format!("DURATION: {}", e))?;
+
+    Ok(())
+}
+
+/// Parses a recurrence line of an event
+fn parse_rule_line(line: &str) -> Result<(), String> {
+    let (name, value) = line
+        .split_once(':')
+        .ok_or_else(|| format!("expected <PROPERTY>:<value>, found [{}]", line))?;
+
+    match name {
+        "RRULE" | "EXRULE" => parse_rrule(value).map_err(|e| format!("{}: {}", name, e)),
+        "RDATE" | "EXDATE" => value
+            .split(',')
+            .try_for_each(|date| parse_datetime(date).map(|_| ()))
+            .map_err(|e| format!("{}: {}", name, e)),
+        _ => Err(format!(
+            "unsupported property [{}], expected RRULE, EXRULE, RDATE or EXDATE",
+            name
+        )),
+    }
+}
+
+/// Parses a UTC date-time (`20221020T180000Z`) or a date (`20221020`)
+fn parse_datetime(value: &str) -> Result<NaiveDateTime, String> {
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ") {
+        return Ok(datetime);
+    }
+
+    NaiveDate::parse_from_str(value, "%Y%m%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .ok_or_else(|| {
+            format!(
+                "invalid date-time [{}], expected YYYYMMDDTHHMMSSZ or YYYYMMDD",
+                value
+            )
+        })
+}
+
+/// Parses an ISO 8601 duration such as `PT24H`, `P1D` or `PT1H30M`.
+/// Returns the duration in seconds, months and years are not allowed since
+/// their length is ambiguous. Units must be given in W, D, H, M, S order,
+/// each at most once.
+fn parse_duration(value: &str) -> Result<u64, String> {
+    let error = || {
+        format!(
+            "invalid duration [{}], expected ISO 8601 duration like PT24H",
+            value
+        )
+    };
+
+    let mut rest = value.strip_prefix('P').ok_or_else(error)?;
+    let mut seconds: u64 = 0;
+    let mut in_time = false;
+    let mut time_units = 0;
+    let mut last_unit: Option<usize> = None;
+
+    while !rest.is_empty() {
+        if let Some(time) = rest.strip_prefix('T') {
+            if in_time {
+                return Err(error());
+            }
+            in_time = true;
+            rest = time;
+            continue;
+        }
+
+        let digits = rest.chars().take_while(|c| c.is_ascii_digit()).count();
+        let (number, unit) = rest.split_at(digits);
+        let number: u64 = number.parse().map_err(|_| error())?;
+        let mut unit_chars = unit.chars();
+        let unit = unit_chars.next().ok_or_else(error)?;
+        let (index, factor): (usize, u64) = match (in_time, unit) {
+            (false, 'W') => (0, 7 * 24 * 3600),
+            (false, 'D') => (1, 24 * 3600),
+            (true, 'H') => (2, 3600),
+            (true, 'M') => (3, 60),
+            (true, 'S') => (4, 1),
+            _ => return Err(error()),
+        };
+
+        if last_unit.is_some_and(|last| index <= last) {
+            return Err(error());
+        }
+        last_unit = Some(index);
+        if in_time {
+            time_units += 1;
+        }
+
+        seconds = number
+            .checked_mul(factor)
+            .and_then(|value| seconds.checked_add(value))
+            .ok_or_else(error)?;
+        rest = unit_chars.as_str();
+    }
+
+    // A T must be followed by at least one time unit
+    if last_unit.is_none() || (in_time && time_units == 0) {
+        return Err(error());
+    }
+
+    match seconds {
+        0 => Err("duration must be longer than 0 seconds".to_string()),
+        seconds => Ok(seconds),
+    }
+}
+
+/// Parses the rule parts of an RRULE or EXRULE as defined by RFC 5545
+fn parse_rrule(value: &str) -> Result<(), String> {
+    let mut parts: HashSet<&str> = HashSet::new();
+
+    for part in value.split(';') {
+        let (name, value) = part
+            .split_once('=')
+            .ok_or_else(|| format!("invalid rule part [{}], expected <NAME>=<value>", part))?;
+
+        if !parts.insert(name) {
+            return Err(format!("duplicate rule part [{}]", name));
+        }
+
+        match name {
+            "FREQ" => {
+                if !FREQUENCIES.contains(&value) {
+                    return Err(format!(
+                        "invalid FREQ [{}], expected one of {}",
+                        value,
+                        FREQUENCIES.join(", ")
+                    ));
+                }
+            }
+            "INTERVAL" | "COUNT" => {
+                parse_list(name, value, 1, u32::MAX as i64, false)?;
+            }
+            "UNTIL" => {
+                parse_datetime(value).map_err(|e| format!("UNTIL: {}", e))?;
+            }
+            "BYSECOND" => parse_list(name, value, 0, 60, false)?,
+            "BYMINUTE" => parse_list(name, value, 0, 59, false)?,
+            "BYHOUR" => parse_list(name, value, 0, 23, false)?,
+            "BYMONTHDAY" => parse_list(name, value, 1, 31, true)?,
+            "BYYEARDAY" | "BYSETPOS" => parse_list(name, value, 1, 366, true)?,
+            "BYWEEKNO" => parse_list(name, value, 1, 53, true)?,
+            "BYMONTH" => parse_list(name, value, 1, 12, false)?,
+            "BYDAY" => {
+                for day in value.split(',') {
+                    let weekday = day
+                        .trim_start_matches(|c: char| c.is_ascii_digit() || c == '+' || c == '-');
+                    let ordinal = &day[..day.len() - weekday.len()];
+                    if !WEEKDAYS.contains(&weekday) {
+                        return Err(format!("invalid BYDAY [{}], expected e.g. MO or -1FR", day));
+                    }
+                    if !ordinal.is_empty() {
+                        parse_list(name, ordinal, 1, 53, true)?;
+                    }
+                }
+            }
+            "WKST" => {
+                if !WEEKDAYS.contains(&value) {
+                    return Err(format!("invalid WKST [{}]", value));
+                }
+            }
+            _ => return Err(format!("unsupported rule part [{}]", name)),
+        }
+    }
+
+    if !parts.contains("FREQ") {
+        return Err("missing the required FREQ rule part".to_string());
+    }
+
+    if parts.contains("COUNT") && parts.contains("UNTIL") {
+        return Err("COUNT and UNTIL can not be combined".to_string());
+    }
+
+    Ok(())
+}
+
+/// Parses a comma separated list of integers within the provided range.
+/// If `signed` is set, negative values within the range are allowed too.
+fn parse_list(name: &str, value: &str, min: i64, max: i64, signed: bool) -> Result<(), String> {
+    for item in value.split(',') {
+        let number: i64 = item
+            .parse()
+            .map_err(|_| format!("invalid {} value [{}], expected a number", name, item))?;
+
+        let absolute = if signed { number.abs() } else { number };
+        if item.starts_with('-') && !signed || absolute < min || absolute > max {
+            return Err(format!(
+                "{} value [{}] out of range, expected {}{} to {}",
+                name,
+                item,
+                if signed { "+/-" } else { "" },
+                min,
+                max
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resolve_default() {
+        crate::get_log_handle().await;
+        ut_info!("(test_resolve_default) Start.");
+
+        let schedule = resolve(None).unwrap();
+        assert_eq!(
+            schedule,
+            "DTSTART:20221020T180000Z;DURATION:PT24H\nRRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR,SA,SU"
+        );
+
+        ut_info!("(test_resolve_default) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_parse_valid() {
+        crate::get_log_handle().await;
+        ut_info!("(test_parse_valid) Start.");
+
+        // Opening hours with a night closure
+        let schedule = "  DTSTART:20240101T060000Z;DURATION:PT16H
+            RRULE:FREQ=DAILY;INTERVAL=1
+            EXDATE:20241225T060000Z,20241226T060000Z
+            DTSTART:20240106T090000Z;DURATION:PT1H30M
+            RRULE:FREQ=MONTHLY;BYDAY=1SA;COUNT=12";
+        let result = parse(schedule).unwrap();
+        assert!(result.starts_with("DTSTART:20240101T060000Z;DURATION:PT16H\nRRULE"));
+        assert_eq!(result.lines().count(), 5);
+
+        assert_eq!(parse_duration("P1W").unwrap(), 7 * 24 * 3600);
+        assert_eq!(parse_duration("P1DT2H").unwrap(), 26 * 3600);
+        assert_eq!(parse_duration("P1DT1M1S").unwrap(), 24 * 3600 + 61);
+
+        ut_info!("(test_parse_valid) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_parse_invalid() {
+        crate::get_log_handle().await;
+        ut_info!("(test_parse_invalid) Start.");
+
+        let invalid = [
+            "",
+            "RRULE:FREQ=DAILY",
+            "DTSTART:20240101T060000Z;DURATION:PT16H",
+            "DTSTART:2024-01-01;DURATION:PT16H\nRRULE:FREQ=DAILY",
+            "DTSTART:20240101T060000Z;DURATION:16H\nRRULE:FREQ=DAILY",
+            "DTSTART:20240101T060000Z;DURATION:PT0S\nRRULE:FREQ=DAILY",
+            "DTSTART:20240101T060000Z;DURATION:PT1H\nRRULE:FREQ=SOMETIMES",
+            "DTSTART:20240101T060000Z;DURATION:PT1H\nRRULE:BYDAY=MO",
+            "DTSTART:20240101T060000Z;DURATION:PT1H\nRRULE:FREQ=DAILY;BYDAY=XX",
+            "DTSTART:20240101T060000Z;DURATION:PT1H\nRRULE:FREQ=DAILY;BYHOUR=24",
+            "DTSTART:20240101T060000Z;DURATION:PT1H\nRRULE:FREQ=DAILY;COUNT=1;UNTIL=20250101",
+            "DTSTART:20240101T060000Z;DURATION:PT1H\nRRULE:FREQ=DAILY;FREQ=DAILY",
+            "DTSTART:20240101T060000Z;DURATION:PT1H\nRRULE:FREQ=DAILY\nEXDATE:tomorrow",
+            "DTSTART:20240101T060000Z;DURATION:P99999999999999999W\nRRULE:FREQ=DAILY",
+            "DTSTART:20240101T060000Z;DURATION:PT1H1H\nRRULE:FREQ=DAILY",
+            "DTSTART:20240101T060000Z;DURATION:P1DT\nRRULE:FREQ=DAILY",
+            "DTSTART:20240101T060000Z;DURATION:PT5M1H\nRRULE:FREQ=DAILY",
+            "DTSTART:20240101T060000Z;DURATION:PT1S1H\nRRULE:FREQ=DAILY",
+        ];
+
+        for schedule in invalid {
+            let result = parse(schedule);
+            ut_debug!("(test_parse_invalid) [{}]: {:?}", schedule, result);
+            assert!(result.is_err());
+        }
+
+        let error = resolve(Some(
+            "DTSTART:20240101T060000Z;DURATION:PT1H\nRRULE:FREQ=DAILY;BYHOUR=25",
+        ))
+        .unwrap_err();
+        assert_eq!(
+            error,
+            SeedError::invalid_field(
+                "schedule",
+                "line 2: RRULE: BYHOUR value [25] out of range, expected 0 to 23"
+            )
+        );
+
+        ut_info!("(test_parse_invalid) Success.");
+    }
+}
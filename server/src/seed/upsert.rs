@@ -0,0 +1,199 @@
+//! Idempotent creation of realm resources, matched by their natural key
+//!
+//! | Resource  | Natural key                   |
+//! |-----------|-------------------------------|
+//! | vertiport | label                         |
+//! | vertipad  | vertiport ID and label        |
+//! | aircraft  | registration number           |
+//! | user      | email                         |
+//!
+//! When a resource with the natural key exists, it's updated with the
+//! fields of the request. Optional fields which are not provided, such as
+//! the schedule, are left unchanged.
+
+use super::{lifecycle, query, resources, Saga, SeedError};
+use crate::grpc::client::GrpcClients;
+use crate::rest::api::rest_types::*;
+
+/// Returns the only resource matching a natural key, if any
+fn single_match<T>(kind: &str, key: &str, mut matches: Vec<T>) -> Result<Option<T>, SeedError> {
+    match matches.len() {
+        0 | 1 => Ok(matches.pop()),
+        n => Err(SeedError::Conflict(format!(
+            "{} {}s match [{}], can not decide which one to update",
+            n, kind, key
+        ))),
+    }
+}
+
+/// Create a vertiport, or update the vertiport with the same label
+pub async fn upsert_vertiport(
+    grpc_clients: &GrpcClients,
+    request: &AddVertiportRequest,
+    saga: &mut Saga,
+) -> Result<UpsertResponse, SeedError> {
+    seed_debug!("(upsert_vertiport) entry [{}].", request.label);
+
+    let filter = VertiportFilter {
+        label: Some(request.label.clone()),
+    };
+    let existing = query::list_vertiports(grpc_clients, &filter).await?;
+    let Some(existing) = single_match("vertiport", &request.label, existing)? else {
+        let id = resources::create_vertiport(grpc_clients, request, saga).await?;
+        return Ok(UpsertResponse {
+            id,
+            outcome: UpsertOutcome::Created,
+        });
+    };
+
+    let update = UpdateVertiportRequest {
+        label: None,
+        address: Some(request.address.clone()),
+        vertices: Some(request.vertices.clone()),
+        altitude: Some(request.altitude),
+        schedule: request.schedule.clone(),
+    };
+    let id = lifecycle::update_vertiport(grpc_clients, &existing.id, &update, saga)
+        .await?
+        .id;
+
+    Ok(UpsertResponse {
+        id,
+        outcome: UpsertOutcome::Updated,
+    })
+}
+
+/// Create a vertipad, or update the vertipad with the same label at the
+/// same vertiport
+pub async fn upsert_vertipad(
+    grpc_clients: &GrpcClients,
+    request: &AddVertipadRequest,
+    saga: &mut Saga,
+) -> Result<UpsertResponse, SeedError> {
+    seed_debug!("(upsert_vertipad) entry [{}].", request.label);
+
+    let filter = VertipadFilter {
+        vertiport_id: Some(request.vertiport_id.clone()),
+        label: Some(request.label.clone()),
+    };
+    let existing = query::list_vertipads(grpc_clients, &filter).await?;
+    let Some(existing) = single_match("vertipad", &request.label, existing)? else {
+        let id = resources::create_vertipad(grpc_clients, request, saga).await?;
+        return Ok(UpsertResponse {
+            id,
+            outcome: UpsertOutcome::Created,
+        });
+    };
+
+    let update = UpdateVertipadRequest {
+        latitude: Some(request.latitude),
+        longitude: Some(request.longitude),
+        altitude: Some(request.altitude),
+        schedule: request.schedule.clone(),
+        ..Default::default()
+    };
+    let id = lifecycle::update_vertipad(grpc_clients, &existing.id, &update, saga)
+        .await?
+        .id;
+
+    Ok(UpsertResponse {
+        id,
+        outcome: UpsertOutcome::Updated,
+    })
+}
+
+/// Create an aircraft, or update the aircraft with the same registration
+/// number
+pub async fn upsert_aircraft(
+    grpc_clients: &GrpcClients,
+    request: &AddAircraftRequest,
+    saga: &mut Saga,
+) -> Result<UpsertResponse, SeedError> {
+    seed_debug!("(upsert_aircraft) entry [{}].", request.registration_number);
+
+    let filter = AircraftFilter {
+        registration_number: Some(request.registration_number.clone()),
+        hangar_id: None,
+    };
+    let existing = query::list_aircraft(grpc_clients, &filter).await?;
+    let Some(existing) = single_match("aircraft", &request.registration_number, existing)? else {
+        let id = resources::create_aircraft(grpc_clients, request, saga).await?;
+        return Ok(UpsertResponse {
+            id,
+            outcome: UpsertOutcome::Created,
+        });
+    };
+
+    let update = UpdateAircraftRequest {
+        nickname: Some(request.nickname.clone()),
+        registration_number: None,
+        hangar_id: Some(request.hangar_id.clone()),
+        hangar_bay_id: Some(request.hangar_bay_id.clone()),
+        schedule: request.schedule.clone(),
+    };
+    let id = lifecycle::update_aircraft(grpc_clients, &existing.id, &update, saga)
+        .await?
+        .id;
+
+    Ok(UpsertResponse {
+        id,
+        outcome: UpsertOutcome::Updated,
+    })
+}
+
+/// Create a user, or update the user with the same email
+pub async fn upsert_user(
+    grpc_clients: &GrpcClients,
+    request: &AddUserRequest,
+    saga: &mut Saga,
+) -> Result<UpsertResponse, SeedError> {
+    seed_debug!("(upsert_user) entry [{}].", request.email);
+
+    let filter = UserFilter {
+        email: Some(request.email.clone()),
+        display_name: None,
+    };
+    let existing = query::list_users(grpc_clients, &filter).await?;
+    let Some(existing) = single_match("user", &request.email, existing)? else {
+        let id = resources::create_user(grpc_clients, request, saga).await?;
+        return Ok(UpsertResponse {
+            id,
+            outcome: UpsertOutcome::Created,
+        });
+    };
+
+    let update = UpdateUserRequest {
+        display_name: Some(request.display_name.clone()),
+        email: None,
+    };
+    let id = lifecycle::update_user(grpc_clients, &existing.id, &update)
+        .await?
+        .id;
+
+    Ok(UpsertResponse {
+        id,
+        outcome: UpsertOutcome::Updated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_single_match() {
+        crate::get_log_handle().await;
+        ut_info!("(test_single_match) Start.");
+
+        assert_eq!(single_match::<u8>("user", "a@b.c", vec![]), Ok(None));
+        assert_eq!(single_match("user", "a@b.c", vec![1]), Ok(Some(1)));
+        assert_eq!(
+            single_match("user", "a@b.c", vec![1, 2]),
+            Err(SeedError::Conflict(
+                "2 users match [a@b.c], can not decide which one to update".to_string()
+            ))
+        );
+
+        ut_info!("(test_single_match) Success.");
+    }
+}
@@ -0,0 +1,272 @@
+//! Catalog of vehicle models used by seeded aircraft
+//!
+//! svc-storage only keeps the vehicle model ID of an aircraft. The catalog
+//! gives these IDs a name and the performance parameters services such as
+//! the scheduler and pricing rely on. A set of representative eVTOL and VTOL
+//! models is built in, more can be loaded from a YAML or JSON file.
+
+use super::SeedError;
+use crate::rest::api::rest_types::VehicleModel;
+use std::path::Path;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Vehicle models in the catalog, built in models until changed
+static CATALOG: Mutex<Option<Vec<VehicleModel>>> = Mutex::new(None);
+
+/// Returns the built in vehicle models
+///
+/// The parameters are representative, based on published figures.
+pub fn built_in() -> Vec<VehicleModel> {
+    [
+        (
+            "0c7b6a3e-6c5f-4d43-9a51-5f0a3c1e0001",
+            "joby-s4",
+            "Joby Aviation",
+            "electric",
+            4,
+            450.0,
+            241.0,
+            265.0,
+        ),
+        (
+            "0c7b6a3e-6c5f-4d43-9a51-5f0a3c1e0002",
+            "archer-midnight",
+            "Archer Aviation",
+            "electric",
+            4,
+            450.0,
+            160.0,
+            241.0,
+        ),
+        (
+            "0c7b6a3e-6c5f-4d43-9a51-5f0a3c1e0003",
+            "volocity",
+            "Volocopter",
+            "electric",
+            1,
+            200.0,
+            35.0,
+            90.0,
+        ),
+        (
+            "0c7b6a3e-6c5f-4d43-9a51-5f0a3c1e0004",
+            "ehang-216",
+            "EHang",
+            "electric",
+            2,
+            220.0,
+            30.0,
+            100.0,
+        ),
+        (
+            "0c7b6a3e-6c5f-4d43-9a51-5f0a3c1e0005",
+            "wisk-gen6",
+            "Wisk Aero",
+            "electric",
+            4,
+            400.0,
+            144.0,
+            222.0,
+        ),
+        (
+            "0c7b6a3e-6c5f-4d43-9a51-5f0a3c1e0006",
+            "chaparral",
+            "Elroy Air",
+            "hybrid-electric",
+            0,
+            136.0,
+            480.0,
+            200.0,
+        ),
+    ]
+    .into_iter()
+    .map(
+        |(id, name, manufacturer, propulsion, passengers, payload, range, speed)| VehicleModel {
+            id: id.to_string(),
+            name: name.to_string(),
+            manufacturer: manufacturer.to_string(),
+            propulsion: propulsion.to_string(),
+            max_passengers: passengers,
+            max_payload_kg: payload,
+            max_range_km: range,
+            cruise_speed_kmh: speed,
+        },
+    )
+    .collect()
+}
+
+/// Checks that a vehicle model can be added to the catalog
+pub fn validate(model: &VehicleModel) -> Result<(), SeedError> {
+    if model.name.trim().is_empty() {
+        return Err(SeedError::invalid_field("name", "name must not be empty"));
+    }
+
+    Uuid::parse_str(&model.id)
+        .map_err(|e| SeedError::invalid_field("id", format!("invalid ID [{}]: {}", model.id, e)))?;
+
+    let parameters = [
+        ("max_payload_kg", model.max_payload_kg),
+        ("max_range_km", model.max_range_km),
+        ("cruise_speed_kmh", model.cruise_speed_kmh),
+    ];
+    for (field, value) in parameters {
+        if !value.is_finite() || value <= 0.0 {
+            return Err(SeedError::invalid_field(
+                field,
+                format!("value [{}] must be greater than 0", value),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the function on the catalog, initializing it with the built in
+/// models if needed. Models are only added or replaced as a whole, so a
+/// catalog poisoned by a panic is still consistent.
+fn with_catalog<T>(f: impl FnOnce(&mut Vec<VehicleModel>) -> T) -> T {
+    let mut catalog = match CATALOG.lock() {
+        Ok(catalog) => catalog,
+        Err(poisoned) => {
+            seed_warn!("(with_catalog) catalog lock poisoned, recovering.");
+            poisoned.into_inner()
+        }
+    };
+
+    f(catalog.get_or_insert_with(built_in))
+}
+
+/// Adds a model to the catalog, replacing the model with the same name.
+/// IDs have to be unique across models.
+pub fn add(model: VehicleModel) -> Result<VehicleModel, SeedError> {
+    validate(&model)?;
+
+    with_catalog(|catalog| {
+        if catalog
+            .iter()
+            .any(|existing| existing.id == model.id && existing.name != model.name)
+        {
+            return Err(SeedError::Conflict(format!(
+                "vehicle model ID [{}] is already used by another model",
+                model.id
+            )));
+        }
+
+        catalog.retain(|existing| existing.name != model.name);
+        catalog.push(model.clone());
+        seed_info!("(add) vehicle model [{}] added.", model.name);
+        Ok(model)
+    })
+}
+
+/// Returns all models in the catalog
+pub fn list() -> Vec<VehicleModel> {
+    with_catalog(|catalog| catalog.clone())
+}
+
+/// Returns the model with the provided name
+pub fn get(name: &str) -> Result<VehicleModel, SeedError> {
+    with_catalog(|catalog| catalog.iter().find(|model| model.name == name).cloned())
+        .ok_or_else(|| SeedError::NotFound(name.to_string()))
+}
+
+/// Adds all models of a YAML or JSON file to the catalog, returns the
+/// number of models added
+pub fn load_file(path: impl AsRef<Path>) -> Result<usize, SeedError> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        SeedError::InvalidRequest(format!(
+            "could not read vehicle model catalog [{}]: {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    // YAML parsing also accepts JSON documents
+    let models: Vec<VehicleModel> = serde_yaml::from_str(&content).map_err(|e| {
+        SeedError::InvalidRequest(format!(
+            "could not parse vehicle model catalog [{}]: {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    let count = models.len();
+    for (index, model) in models.into_iter().enumerate() {
+        add(model).map_err(|e| e.within(&format!("[{}]", index)))?;
+    }
+
+    seed_info!(
+        "(load_file) loaded {} vehicle models from [{}].",
+        count,
+        path.display()
+    );
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_built_in() {
+        crate::get_log_handle().await;
+        ut_info!("(test_built_in) Start.");
+
+        let models = built_in();
+        for model in &models {
+            assert!(validate(model).is_ok(), "{:?}", model);
+            assert_eq!(models.iter().filter(|m| m.id == model.id).count(), 1);
+            assert_eq!(models.iter().filter(|m| m.name == model.name).count(), 1);
+        }
+        assert_eq!(get("joby-s4").unwrap().manufacturer, "Joby Aviation");
+
+        ut_info!("(test_built_in) Success.");
+    }
+
+    #[tokio::test]
+    async fn test_load_file() {
+        crate::get_log_handle().await;
+        ut_info!("(test_load_file) Start.");
+
+        let path = std::env::temp_dir().join("test_load_file_vehicle_models.yaml");
+        std::fs::write(
+            &path,
+            r#"
+- id: 3d0fbd55-4d0f-4b57-8b0e-8a1c6c1c0001
+  name: test-load-file-model
+  manufacturer: Test
+  propulsion: electric
+  max_passengers: 2
+  max_payload_kg: 180.0
+  max_range_km: 50.0
+  cruise_speed_kmh: 110.0
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(load_file(&path), Ok(1));
+        assert_eq!(get("test-load-file-model").unwrap().max_range_km, 50.0);
+
+        // IDs of other models can't be reused
+        std::fs::write(
+            &path,
+            r#"
+- id: 0c7b6a3e-6c5f-4d43-9a51-5f0a3c1e0001
+  name: test-load-file-copy
+  manufacturer: Test
+  propulsion: electric
+  max_passengers: 2
+  max_payload_kg: 180.0
+  max_range_km: 50.0
+  cruise_speed_kmh: 110.0
+"#,
+        )
+        .unwrap();
+        assert!(matches!(load_file(&path), Err(SeedError::Conflict(_))));
+        std::fs::remove_file(&path).unwrap();
+
+        ut_info!("(test_load_file) Success.");
+    }
+}